@@ -1,10 +1,12 @@
-use std::collections::HashMap;
-
-use syn::{Item, ItemMod, Type};
+use proc_macro2::Ident;
+use quote::ToTokens;
+use syn::{Item, ItemFn, ItemMod, Type};
 
 use crate::{
-    domain::{AocGeneratorData, AocSolutionData, AocSolverData},
-    parser::{genargs::AocGeneratorArgs, solutionargs::AocSolutionArgs, solverargs::AocSolverArgs},
+    domain::{AocGeneratorData, AocSolutionData, AocSolverData, ExtraArg, InputBorrow},
+    parser::{
+        genargs::AocGeneratorArgs, solutionargs::AocSolutionArgs, solverargs::AocSolverArgs, tagargs::VariantTags,
+    },
     partflag::AocPart,
 };
 
@@ -13,21 +15,24 @@ const SOLUTION_TYPE_MISMATCH: &str = "All solvers and solutions for a given day
 pub struct AocSolutionsAggregation<'a> {
     pub solutions_p1: Vec<AocSolutionData<'a>>,
     pub solutions_p2: Vec<AocSolutionData<'a>>,
-    pub generators: HashMap<&'a Type, Vec<AocGeneratorData<'a>>>,
-    pub solvers_p1: HashMap<&'a Type, Vec<AocSolverData<'a>>>,
-    pub solvers_p2: HashMap<&'a Type, Vec<AocSolverData<'a>>>,
+    pub generators: Vec<AocGeneratorData<'a>>,
+    pub solvers_p1: Vec<AocSolverData<'a>>,
+    pub solvers_p2: Vec<AocSolverData<'a>>,
     pub p1_result_type: Option<&'a Type>,
     pub p2_result_type: Option<&'a Type>,
 }
 
+/// A generator feeding a solver, and how the solver takes the generator's output.
+pub type ComposedSoln<'s, 'a> = (&'s AocGeneratorData<'a>, &'s AocSolverData<'a>, InputBorrow);
+
 impl<'a> AocSolutionsAggregation<'a> {
     pub fn new() -> Self {
         AocSolutionsAggregation {
             solutions_p1: Vec::new(),
             solutions_p2: Vec::new(),
-            generators: HashMap::new(),
-            solvers_p1: HashMap::new(),
-            solvers_p2: HashMap::new(),
+            generators: Vec::new(),
+            solvers_p1: Vec::new(),
+            solvers_p2: Vec::new(),
             p1_result_type: None,
             p2_result_type: None,
         }
@@ -41,37 +46,286 @@ impl<'a> AocSolutionsAggregation<'a> {
         self.solutions_p2.iter()
     }
 
-    pub fn p1_composed_solns(&self) -> impl Iterator<Item = (&AocGeneratorData<'a>, &AocSolverData<'a>)> {
-        self.generators.iter().flat_map(|(ty, gens)| {
-            if !self.solvers_p1.contains_key(*ty) {
-                if cfg!(debug_assertions) && std::env::var("AOC_DEBUG_SOLVERS").is_ok() {
-                    println!("WARNING: Generator type has no corresponding part1 solvers:\n{:#?}", &ty);
-                }
-                vec![].into_iter()
-            } else {
-                gens.iter()
-                    .flat_map(|g| self.solvers_p1.get(ty).unwrap().iter().map(move |s| (g, s)))
-                    .collect::<Vec<(&AocGeneratorData<'a>, &AocSolverData<'a>)>>()
-                    .into_iter()
-            }
+    pub fn p1_composed_solns(&self) -> impl Iterator<Item = ComposedSoln<'_, 'a>> {
+        Self::compose(&self.generators, &self.solvers_p1)
+    }
+
+    pub fn p2_composed_solns(&self) -> impl Iterator<Item = ComposedSoln<'_, 'a>> {
+        Self::compose(&self.generators, &self.solvers_p2)
+    }
+
+    /// Every generator paired with every solver that accepts its output, in source order.
+    fn compose<'s>(
+        generators: &'s [AocGeneratorData<'a>],
+        solvers: &'s [AocSolverData<'a>],
+    ) -> impl Iterator<Item = ComposedSoln<'s, 'a>> {
+        generators.iter().flat_map(move |g| {
+            solvers
+                .iter()
+                .filter_map(move |s| s.accepts(g.gen_type).map(|borrow| (g, s, borrow)))
         })
     }
 
-    pub fn p2_composed_solns(&self) -> impl Iterator<Item = (&AocGeneratorData<'a>, &AocSolverData<'a>)> {
-        self.generators.iter().flat_map(|(ty, gens)| {
-            if !self.solvers_p2.contains_key(*ty) {
-                if cfg!(debug_assertions) && std::env::var("AOC_DEBUG_SOLVERS").is_ok() {
-                    println!("WARNING: Generator type has no corresponding part2 solvers:\n{:#?}", &ty);
+    /// A part's solvers and `#[solution]`s.
+    fn part_fns(&self, part: AocPart) -> (&[AocSolverData<'a>], &[AocSolutionData<'a>]) {
+        match part {
+            AocPart::Part1 => (&self.solvers_p1, &self.solutions_p1),
+            _ => (&self.solvers_p2, &self.solutions_p2),
+        }
+    }
+
+    /// The tags of each of a part's variants, listed as the composed solutions then the
+    /// `#[solution]`s. A composed solution is slow or experimental if its generator or solver is.
+    pub fn variant_tags(&self, part: AocPart) -> Vec<VariantTags> {
+        let (solvers, solutions) = self.part_fns(part);
+        Self::compose(&self.generators, solvers)
+            .map(|(g, s, _)| VariantTags {
+                slow: g.tags.slow || s.tags.slow,
+                experimental: g.tags.experimental || s.tags.experimental,
+                primary: g.tags.primary || s.tags.primary,
+            })
+            .chain(solutions.iter().map(|s| s.tags))
+            .collect()
+    }
+
+    /// Where the part's primary variant is in the `variant_tags` order: the `primary` solver or
+    /// solution, fed by the `primary` generator if there's a choice, or else the first variant
+    /// the `primary` generator feeds. `None` if nothing is marked, leaving the first variant.
+    pub fn primary_variant(&self, part: AocPart) -> Option<usize> {
+        let (solvers, solutions) = self.part_fns(part);
+        let ranks: Vec<(bool, bool)> = Self::compose(&self.generators, solvers)
+            .map(|(g, s, _)| (s.tags.primary, g.tags.primary))
+            .chain(solutions.iter().map(|s| (s.tags.primary, false)))
+            .collect();
+        let best = ranks.iter().max().filter(|&&rank| rank != (false, false))?;
+        ranks.iter().position(|rank| rank == best)
+    }
+
+    /// An error for each function marked `primary` beyond the first of its kind and part, and for
+    /// each `primary` that's also slow or experimental, and so wouldn't always run.
+    fn primary_errors(&self) -> Vec<syn::Error> {
+        let mut errs = Vec::new();
+        let tagged = self
+            .generators
+            .iter()
+            .map(|g| (&g.display_slug, g.tags))
+            .chain(self.solvers_p1.iter().map(|s| (&s.display_slug, s.tags)))
+            .chain(self.solvers_p2.iter().map(|s| (&s.display_slug, s.tags)))
+            .chain(self.solutions_p1.iter().map(|s| (&s.display_slug, s.tags)))
+            .chain(self.solutions_p2.iter().map(|s| (&s.display_slug, s.tags)));
+        for (slug, tags) in tagged {
+            if tags.primary && (tags.slow || tags.experimental) {
+                let message =
+                    format!("`{slug}` is `primary`, so it always runs and can't also be `slow` or `experimental`.");
+                if !errs.iter().any(|e: &syn::Error| e.to_string() == message) {
+                    errs.push(syn::Error::new(slug.span(), message));
                 }
-                vec![].into_iter()
-            } else {
-                gens.iter()
-                    .flat_map(|g| self.solvers_p2.get(ty).unwrap().iter().map(move |s| (g, s)))
-                    .collect::<Vec<(&AocGeneratorData<'a>, &AocSolverData<'a>)>>()
-                    .into_iter()
             }
-        })
+        }
+
+        let mut primaries = vec![(
+            "generator",
+            self.generators
+                .iter()
+                .filter(|g| g.tags.primary)
+                .map(|g| &g.display_slug)
+                .collect::<Vec<_>>(),
+        )];
+        for (name, part) in [("part 1 variant", AocPart::Part1), ("part 2 variant", AocPart::Part2)] {
+            let (solvers, solutions) = self.part_fns(part);
+            let marked = solvers
+                .iter()
+                .filter(|s| s.tags.primary)
+                .map(|s| &s.display_slug)
+                .chain(solutions.iter().filter(|s| s.tags.primary).map(|s| &s.display_slug));
+            primaries.push((name, marked.collect()));
+        }
+        for (name, marked) in primaries {
+            if let [first, rest @ ..] = marked.as_slice() {
+                for slug in rest {
+                    let message = format!("Only one {name} can be `primary`, and `{first}` already is.");
+                    errs.push(syn::Error::new(slug.span(), message));
+                }
+            }
+        }
+
+        errs
+    }
+
+    /// Every `params(..)` entry the day's functions declare, with the type it's taken as.
+    fn all_params(&self) -> impl Iterator<Item = (&Ident, &Type)> {
+        self.generators
+            .iter()
+            .map(|g| &g.params)
+            .chain(self.solvers_p1.iter().chain(self.solvers_p2.iter()).map(|s| &s.params))
+            .chain(self.solutions_p1.iter().chain(self.solutions_p2.iter()).map(|s| &s.params))
+            .flatten()
+            .filter_map(|arg| match arg {
+                ExtraArg::Param { param, ty } => Some((&param.name, ty.as_ref())),
+                ExtraArg::Recorder => None,
+            })
+    }
+
+    /// Each parameter the day declares, once, with the type it's taken as.
+    pub fn declared_params(&self) -> Vec<(&Ident, &Type)> {
+        let mut declared: Vec<(&Ident, &Type)> = Vec::new();
+        for (name, ty) in self.all_params() {
+            if !declared.iter().any(|(known, _)| *known == name) {
+                declared.push((name, ty));
+            }
+        }
+        declared
+    }
+
+    /// An error for each parameter taken as another type than where it's first declared, since
+    /// `#[aoc_case]` overrides it with one value for every function.
+    fn param_errors(&self) -> Vec<syn::Error> {
+        let declared = self.declared_params();
+        self.all_params()
+            .filter_map(|(name, ty)| {
+                let (_, first) = declared.iter().find(|(known, _)| *known == name)?;
+                let (first, ty) = (type_text(first), type_text(ty));
+                (first != ty).then(|| {
+                    let message = format!("`{name}` is taken as `{first}` elsewhere, so it can't be `{ty}` here.");
+                    syn::Error::new(name.span(), message)
+                })
+            })
+            .collect()
+    }
+
+    /// An error for each generator that no solver takes and each solver that no generator feeds,
+    /// unless it's marked `#[allow_unmatched]`. Either would otherwise be silently left out.
+    fn unmatched_errors(&self) -> Vec<syn::Error> {
+        let mut solvers: Vec<&AocSolverData> = Vec::new();
+        for solver in self.solvers_p1.iter().chain(self.solvers_p2.iter()) {
+            // `both` solvers are in each part's list.
+            if !solvers.iter().any(|s| s.source.sig.ident == solver.source.sig.ident) {
+                solvers.push(solver);
+            }
+        }
+
+        let mut errs = Vec::new();
+        for g in self.generators.iter().filter(|g| !allows_unmatched(g.source)) {
+            if solvers.iter().any(|s| s.accepts(g.gen_type).is_some()) {
+                continue;
+            }
+            let closest = closest(g.gen_type, solvers.iter().map(|s| (&s.source.sig.ident, s.input_type)));
+            let hint = match closest {
+                Some((name, ty)) => format!(
+                    "The closest solver input is `{name}`'s `{}`, and a solver would need to take {}.",
+                    type_text(ty),
+                    accepted_forms(g.gen_type)
+                ),
+                None => "The day has no solvers.".to_string(),
+            };
+            let message = format!(
+                "Generator `{}` returns `{}`, which no solver takes. {hint} {ALLOW_HINT}",
+                g.source.sig.ident,
+                type_text(g.gen_type)
+            );
+            errs.push(syn::Error::new_spanned(g.gen_type, message));
+        }
+        for s in solvers.iter().filter(|s| !allows_unmatched(s.source)) {
+            if self.generators.iter().any(|g| s.accepts(g.gen_type).is_some()) {
+                continue;
+            }
+            let closest = closest(
+                s.input_type,
+                self.generators.iter().map(|g| (&g.source.sig.ident, g.gen_type)),
+            );
+            let hint = match closest {
+                Some((name, ty)) => format!(
+                    "The closest is generator `{name}`, returning `{}`, which a solver can take as {}.",
+                    type_text(ty),
+                    accepted_forms(ty)
+                ),
+                None => "The day has no generators.".to_string(),
+            };
+            let message = format!(
+                "Solver `{}` takes `{}`, which no generator returns. {hint} {ALLOW_HINT}",
+                s.source.sig.ident,
+                type_text(s.input_type)
+            );
+            errs.push(syn::Error::new_spanned(s.input_type, message));
+        }
+
+        errs
+    }
+}
+
+const ALLOW_HINT: &str = "Mark it `#[allow_unmatched]` if that's intended.";
+
+fn allows_unmatched(source: &ItemFn) -> bool {
+    source.attrs.iter().any(|a| a.path().is_ident("allow_unmatched"))
+}
+
+/// A type as written, without the spacing `to_token_stream` puts between every token.
+fn type_text(ty: &Type) -> String {
+    let mut text = ty.to_token_stream().to_string();
+    for (spaced, tight) in [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        (" ;", ";"),
+        ("& ", "&"),
+        (" :: ", "::"),
+        (":: ", "::"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+    ] {
+        text = text.replace(spaced, tight);
+    }
+    text
+}
+
+/// How a solver may take a generator's output of type `ty`.
+fn accepted_forms(ty: &Type) -> String {
+    let text = type_text(ty);
+    match ty {
+        Type::Reference(_) => format!("exactly `{text}`"),
+        _ => format!("`{text}`, `&{text}` or `&mut {text}`"),
+    }
+}
+
+/// The candidate whose type is spelled most like `ty`, ignoring any references on either.
+fn closest<'t>(ty: &Type, candidates: impl Iterator<Item = (&'t Ident, &'t Type)>) -> Option<(&'t Ident, &'t Type)> {
+    let target = type_text(without_refs(ty));
+    candidates.min_by_key(|(_, candidate)| edit_distance(&target, &type_text(without_refs(candidate))))
+}
+
+fn without_refs(ty: &Type) -> &Type {
+    match ty {
+        Type::Reference(r) => without_refs(&r.elem),
+        Type::Paren(p) => without_refs(&p.elem),
+        _ => ty,
+    }
+}
+
+/// Levenshtein distance, by characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Records the first answer type seen for a part, and flags any later one that differs.
+fn check_answer_type<'a>(seen: &mut Option<&'a Type>, ty: &'a Type, slug: &Ident, errs: &mut Vec<syn::Error>) {
+    if seen.is_some_and(|t| t != ty) {
+        errs.push(syn::Error::new(slug.span(), SOLUTION_TYPE_MISMATCH))
     }
+    *seen = Some(ty);
 }
 
 pub fn discover_mod_contents(module: &ItemMod) -> syn::Result<AocSolutionsAggregation> {
@@ -79,9 +333,9 @@ pub fn discover_mod_contents(module: &ItemMod) -> syn::Result<AocSolutionsAggreg
 
     let mut solutions_p1: Vec<AocSolutionData> = Vec::new();
     let mut solutions_p2: Vec<AocSolutionData> = Vec::new();
-    let mut generators: HashMap<&Type, Vec<AocGeneratorData>> = HashMap::new();
-    let mut solvers_p1: HashMap<&Type, Vec<AocSolverData>> = HashMap::new();
-    let mut solvers_p2: HashMap<&Type, Vec<AocSolverData>> = HashMap::new();
+    let mut generators: Vec<AocGeneratorData> = Vec::new();
+    let mut solvers_p1: Vec<AocSolverData> = Vec::new();
+    let mut solvers_p2: Vec<AocSolverData> = Vec::new();
     let mut p1_solution_type: Option<&Type> = None;
     let mut p2_solution_type: Option<&Type> = None;
 
@@ -96,43 +350,53 @@ pub fn discover_mod_contents(module: &ItemMod) -> syn::Result<AocSolutionsAggreg
                         Some("generator") => {
                             let args = attr.parse_args::<AocGeneratorArgs>()?;
                             let data = AocGeneratorData::new(args, fn_data)?;
-                            generators.entry(data.gen_type).or_default().push(data);
+                            generators.push(data);
                         }
                         Some("solver") => {
                             let args = attr.parse_args::<AocSolverArgs>()?;
                             let data = AocSolverData::new(args, fn_data)?;
+                            let (in_p1, in_p2) = data.problem_part.parts();
 
-                            match data.problem_part {
-                                AocPart::Part1 => {
-                                    if p1_solution_type.is_some_and(|t| t != data.solution_type) {
-                                        errs.push(syn::Error::new(data.display_slug.span(), SOLUTION_TYPE_MISMATCH))
-                                    }
-                                    p1_solution_type = Some(data.solution_type);
-                                    solvers_p1.entry(data.input_type).or_default().push(data);
-                                }
-                                AocPart::Part2 => {
-                                    if p2_solution_type.is_some_and(|t| t != data.solution_type) {
-                                        errs.push(syn::Error::new(data.display_slug.span(), SOLUTION_TYPE_MISMATCH))
-                                    }
-                                    p2_solution_type = Some(data.solution_type);
-                                    solvers_p2.entry(data.input_type).or_default().push(data);
-                                }
+                            if in_p1 {
+                                check_answer_type(
+                                    &mut p1_solution_type,
+                                    data.answer_type(AocPart::Part1),
+                                    &data.display_slug,
+                                    &mut errs,
+                                );
+                                solvers_p1.push(data.clone());
+                            }
+                            if in_p2 {
+                                check_answer_type(
+                                    &mut p2_solution_type,
+                                    data.answer_type(AocPart::Part2),
+                                    &data.display_slug,
+                                    &mut errs,
+                                );
+                                solvers_p2.push(data);
                             }
                         }
                         Some("solution") => {
                             let args = attr.parse_args::<AocSolutionArgs>()?;
                             let data = AocSolutionData::new(args, fn_data)?;
-                            if data.problem_part == AocPart::Part1 {
-                                if p1_solution_type.is_some_and(|t| t != data.solution_type) {
-                                    errs.push(syn::Error::new(data.display_slug.span(), SOLUTION_TYPE_MISMATCH))
-                                }
-                                p1_solution_type = Some(data.solution_type);
-                                solutions_p1.push(data);
-                            } else {
-                                if p2_solution_type.is_some_and(|t| t != data.solution_type) {
-                                    errs.push(syn::Error::new(data.display_slug.span(), SOLUTION_TYPE_MISMATCH))
-                                }
-                                p2_solution_type = Some(data.solution_type);
+                            let (in_p1, in_p2) = data.problem_part.parts();
+
+                            if in_p1 {
+                                check_answer_type(
+                                    &mut p1_solution_type,
+                                    data.answer_type(AocPart::Part1),
+                                    &data.display_slug,
+                                    &mut errs,
+                                );
+                                solutions_p1.push(data.clone());
+                            }
+                            if in_p2 {
+                                check_answer_type(
+                                    &mut p2_solution_type,
+                                    data.answer_type(AocPart::Part2),
+                                    &data.display_slug,
+                                    &mut errs,
+                                );
                                 solutions_p2.push(data);
                             }
                         }
@@ -158,7 +422,7 @@ pub fn discover_mod_contents(module: &ItemMod) -> syn::Result<AocSolutionsAggreg
         return Err(combined);
     }
 
-    Ok(AocSolutionsAggregation {
+    let aggregation = AocSolutionsAggregation {
         solutions_p1,
         solutions_p2,
         generators,
@@ -166,5 +430,304 @@ pub fn discover_mod_contents(module: &ItemMod) -> syn::Result<AocSolutionsAggreg
         solvers_p2,
         p1_result_type: p1_solution_type,
         p2_result_type: p2_solution_type,
-    })
+    };
+    let errs = aggregation
+        .unmatched_errors()
+        .into_iter()
+        .chain(aggregation.primary_errors())
+        .chain(aggregation.param_errors());
+    if let Some(combined) = errs.reduce(|mut x, y| {
+        x.combine(y);
+        x
+    }) {
+        return Err(combined);
+    }
+
+    Ok(aggregation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ty(text: &str) -> Type {
+        syn::parse_str(text).unwrap()
+    }
+
+    /// The errors `#[aoc]` reports for a day module, one message each.
+    fn day_errors(module: &str) -> Vec<String> {
+        let module: ItemMod = syn::parse_str(module).unwrap();
+        match discover_mod_contents(&module) {
+            Ok(_) => Vec::new(),
+            Err(errs) => errs.into_iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    fn day(module: &str) -> AocSolutionsAggregation<'static> {
+        let module: &'static ItemMod = Box::leak(Box::new(syn::parse_str(module).unwrap()));
+        discover_mod_contents(module).unwrap()
+    }
+
+    #[test]
+    fn primary_variants_are_found_by_solver_then_generator() {
+        let agg = day("mod solutions {
+            #[generator(gen)]
+            fn parse(input: &str) -> Grid { todo!() }
+            #[generator(fast, primary)]
+            fn parse_fast(input: &str) -> Grid { todo!() }
+            #[solver(part1, main)]
+            fn solve(data: &Grid) -> u32 { todo!() }
+            #[solver(part1, columns, primary)]
+            fn columns(data: &Grid) -> u32 { todo!() }
+            #[solver(part2, main)]
+            fn solve_2(data: &Grid) -> u32 { todo!() }
+            #[solution(part2, direct, primary)]
+            fn part_2(input: &str) -> u32 { todo!() }
+        }");
+        // Part 1 lists gen/main, gen/columns, fast/main, fast/columns.
+        assert_eq!(agg.primary_variant(AocPart::Part1), Some(3));
+        // Part 2 lists gen/main, fast/main, then the `#[solution]`.
+        assert_eq!(agg.primary_variant(AocPart::Part2), Some(2));
+    }
+
+    #[test]
+    fn a_primary_generator_alone_picks_its_first_variant() {
+        let agg = day("mod solutions {
+            #[generator(gen)]
+            fn parse(input: &str) -> Grid { todo!() }
+            #[generator(fast, primary)]
+            fn parse_fast(input: &str) -> Grid { todo!() }
+            #[solver(part1, main)]
+            fn solve(data: &Grid) -> u32 { todo!() }
+            #[solver(part2, main)]
+            fn solve_2(data: &Grid) -> u32 { todo!() }
+        }");
+        assert_eq!(agg.primary_variant(AocPart::Part1), Some(1));
+        assert_eq!(agg.primary_variant(AocPart::Part2), Some(1));
+    }
+
+    #[test]
+    fn unmarked_days_have_no_primary_variant() {
+        let agg = day("mod solutions {
+            #[generator(gen)]
+            fn parse(input: &str) -> Grid { todo!() }
+            #[solver(part1, main)]
+            fn solve(data: &Grid) -> u32 { todo!() }
+            #[solver(part1, brute, slow)]
+            fn solve_slow(data: &Grid) -> u32 { todo!() }
+        }");
+        assert_eq!(agg.primary_variant(AocPart::Part1), None);
+        let tags = agg.variant_tags(AocPart::Part1);
+        assert_eq!(tags.iter().map(|t| t.slow).collect::<Vec<_>>(), [false, true]);
+    }
+
+    #[test]
+    fn only_one_primary_per_kind_and_part() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen, primary)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[generator(fast, primary)]
+                fn parse_fast(input: &str) -> Grid { todo!() }
+                #[solver(part1, main, primary)]
+                fn solve(data: &Grid) -> u32 { todo!() }
+                #[solution(part1, direct, primary)]
+                fn part_1(input: &str) -> u32 { todo!() }
+                #[solver(part2, main, primary)]
+                fn solve_2(data: &Grid) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(
+            errors,
+            [
+                "Only one generator can be `primary`, and `gen` already is.",
+                "Only one part 1 variant can be `primary`, and `main` already is.",
+            ]
+        );
+    }
+
+    #[test]
+    fn primary_variants_cant_be_slow_or_experimental() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[solver(part1, brute, primary, slow)]
+                fn solve(data: &Grid) -> u32 { todo!() }
+                #[solver(part2, wip, primary, experimental)]
+                fn solve_2(data: &Grid) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(
+            errors,
+            [
+                "`brute` is `primary`, so it always runs and can't also be `slow` or `experimental`.",
+                "`wip` is `primary`, so it always runs and can't also be `slow` or `experimental`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn params_are_declared_once_with_one_type() {
+        let agg = day("mod solutions {
+            #[generator(gen, params(size = 7))]
+            fn parse(input: &str, size: usize) -> Grid { todo!() }
+            #[solver(part1, main, params(steps = 64))]
+            fn solve(data: &Grid, steps: u32, viz: Option<Recorder>) -> u32 { todo!() }
+            #[solution(part1, direct, params(steps = 64))]
+            fn part_1(input: &str, steps: u32) -> u32 { todo!() }
+        }");
+        let declared: Vec<_> = agg
+            .declared_params()
+            .into_iter()
+            .map(|(name, ty)| (name.to_string(), type_text(ty)))
+            .collect();
+        assert_eq!(declared, [("size".into(), "usize".into()), ("steps".into(), "u32".into())]);
+
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[solver(part1, main, params(range = 7.0..=27.0))]
+                fn solve(data: &Grid, range: RangeInclusive<f64>) -> u32 { todo!() }
+                #[solution(part1, direct, params(range = 7..=27))]
+                fn part_1(input: &str, range: RangeInclusive<i64>) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(
+            errors,
+            ["`range` is taken as `RangeInclusive<f64>` elsewhere, so it can't be `RangeInclusive<i64>` here."]
+        );
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("Grid", "Grid"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Vec<u32>", "Vec<u64>"), 2);
+        assert_eq!(edit_distance("Grïd", "Grid"), 1);
+    }
+
+    #[test]
+    fn type_text_drops_token_spacing() {
+        assert_eq!(type_text(&ty("Vec < (u32 , & str) >")), "Vec<(u32, &str)>");
+        assert_eq!(
+            type_text(&ty("&mut std::collections::HashMap<u8, [u8; 4]>")),
+            "&mut std::collections::HashMap<u8, [u8; 4]>"
+        );
+        assert_eq!(type_text(&ty("&'a str")), "&'a str");
+    }
+
+    #[test]
+    fn closest_ignores_references() {
+        let (grid, lines) = (
+            Ident::new("grid", proc_macro2::Span::call_site()),
+            Ident::new("lines", proc_macro2::Span::call_site()),
+        );
+        let (grid_ty, lines_ty) = (ty("&Grid<u8>"), ty("Vec<String>"));
+        let candidates = [(&grid, &grid_ty), (&lines, &lines_ty)];
+        let (name, _) = closest(&ty("Grid<u16>"), candidates.into_iter()).unwrap();
+        assert_eq!(name, "grid");
+        assert!(closest(&ty("Grid"), std::iter::empty()).is_none());
+    }
+
+    #[test]
+    fn unmatched_generators_are_an_error() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Vec<u32> { todo!() }
+                #[generator(grid)]
+                fn grid(input: &str) -> Grid { todo!() }
+                #[solver(part1, main)]
+                fn solve(data: Grid) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(
+            errors,
+            ["Generator `parse` returns `Vec<u32>`, which no solver takes. The closest solver input is `solve`'s `Grid`, \
+              and a solver would need to take `Vec<u32>`, `&Vec<u32>` or `&mut Vec<u32>`. \
+              Mark it `#[allow_unmatched]` if that's intended."]
+        );
+    }
+
+    #[test]
+    fn unmatched_solvers_are_an_error() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[solver(part1, main)]
+                fn solve(data: Grid) -> u32 { todo!() }
+                #[solver(part2, main)]
+                fn solve_lines(data: Vec<String>) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(
+            errors,
+            ["Solver `solve_lines` takes `Vec<String>`, which no generator returns. The closest is generator `parse`, \
+              returning `Grid`, which a solver can take as `Grid`, `&Grid` or `&mut Grid`. \
+              Mark it `#[allow_unmatched]` if that's intended."]
+        );
+    }
+
+    #[test]
+    fn typos_suggest_the_closest_type() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> HashMap<Point, Tile> { todo!() }
+                #[generator(lines)]
+                fn lines(input: &str) -> Vec<&str> { todo!() }
+                #[solver(part1, main)]
+                fn solve(data: &HashMap<Point, Tiles>) -> u32 { todo!() }
+                #[solver(part2, main)]
+                fn solve_lines(data: Vec<&str>) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(
+            errors[0].contains("The closest solver input is `solve`'s `&HashMap<Point, Tiles>`"),
+            "{errors:?}"
+        );
+        assert!(
+            errors[1].contains("The closest is generator `parse`, returning `HashMap<Point, Tile>`"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn allow_unmatched_accepts_leftovers() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[generator(debug)]
+                #[allow_unmatched]
+                fn debug(input: &str) -> Vec<String> { todo!() }
+                #[solver(part1, main)]
+                fn solve(data: &Grid) -> u32 { todo!() }
+                #[allow_unmatched]
+                #[solver(part2, wip)]
+                fn solve_wip(data: Graph) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn days_without_solvers_say_so() {
+        let errors = day_errors(
+            "mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[solution(part1, main)]
+                fn part_1(input: &str) -> u32 { todo!() }
+            }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("The day has no solvers."), "{errors:?}");
+    }
 }
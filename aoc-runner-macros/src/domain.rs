@@ -1,16 +1,149 @@
 use proc_macro2::Ident;
-use syn::{spanned::Spanned, Error, ItemFn, ReturnType, Type};
+use syn::{spanned::Spanned, Error, GenericArgument, ItemFn, PathArguments, ReturnType, Type};
 
 use crate::{
-    parser::{genargs::AocGeneratorArgs, solutionargs::AocSolutionArgs, solverargs::AocSolverArgs},
+    parser::{
+        genargs::AocGeneratorArgs, paramargs::ParamArg, solutionargs::AocSolutionArgs, solverargs::AocSolverArgs,
+        tagargs::VariantTags,
+    },
     partflag::AocPart,
 };
 
+/// The `T` of a return type spelled `Result<T, E>`, or of aliases like `miette::Result<T>`.
+/// Only the spelling is checked, since macros can't resolve types.
+fn fallible_ok_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ok) => Some(ok),
+        _ => None,
+    }
+}
+
+/// A return type split into the type being produced and whether it comes wrapped in a `Result`.
+fn split_return_type(ty: &Type) -> (&Type, bool) {
+    match fallible_ok_type(ty) {
+        Some(ok) => (ok, true),
+        None => (ty, false),
+    }
+}
+
+const BOTH_PARTS_TYPE: &str = "Solvers and solutions for `both` parts must return a `(part1, part2)` tuple of the two answers, optionally inside a `Result`.";
+
+/// The two halves of a `(part1, part2)` answer type.
+fn answer_pair(ty: &Type) -> Option<(&Type, &Type)> {
+    match ty {
+        Type::Tuple(tuple) if tuple.elems.len() == 2 => Some((&tuple.elems[0], &tuple.elems[1])),
+        Type::Paren(inner) => answer_pair(&inner.elem),
+        _ => None,
+    }
+}
+
+/// Checks a `both` answer type is a pair.
+fn check_answer_type(part: AocPart, ty: &Type) -> syn::Result<()> {
+    match (part, answer_pair(ty)) {
+        (AocPart::Both, None) => Err(Error::new(ty.span(), BOTH_PARTS_TYPE)),
+        _ => Ok(()),
+    }
+}
+
+/// The answer type for one part: all of `solution_type`, or its half for `both`.
+fn part_answer_type(problem_part: AocPart, solution_type: &Type, part: AocPart) -> &Type {
+    match (problem_part, answer_pair(solution_type), part) {
+        (AocPart::Both, Some((p1, _)), AocPart::Part1) => p1,
+        (AocPart::Both, Some((_, p2)), AocPart::Part2) => p2,
+        _ => solution_type,
+    }
+}
+
+/// An argument after a function's input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtraArg {
+    /// A declared `params(..)` entry, with its default and the type it's taken as.
+    Param { param: Box<ParamArg>, ty: Box<Type> },
+    /// An `Option<Recorder>`, for `aoc viz`.
+    Recorder,
+}
+
+/// Whether `ty` is spelled `Option<Recorder>`, with any path to either.
+fn is_recorder(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(option) = path.path.segments.last().filter(|segment| segment.ident == "Option") else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &option.arguments else {
+        return false;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(Type::Path(inner))) => inner
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Recorder"),
+        _ => false,
+    }
+}
+
+/// Matches the arguments after a function's input with its declared `params(..)`, giving their
+/// defaults in argument order. Every extra argument must be declared, or be an `Option<Recorder>`,
+/// and every declaration used.
+fn resolve_params(source_fn: &ItemFn, declared: Vec<ParamArg>) -> syn::Result<Vec<ExtraArg>> {
+    let mut declared: Vec<Option<ParamArg>> = declared.into_iter().map(Some).collect();
+    let mut resolved = Vec::new();
+    for arg in source_fn.sig.inputs.iter().skip(1) {
+        let syn::FnArg::Typed(typed) = arg else {
+            return Err(Error::new(arg.span(), "Parameters must be plain named arguments"));
+        };
+        if is_recorder(&typed.ty) {
+            resolved.push(ExtraArg::Recorder);
+            continue;
+        }
+        let syn::Pat::Ident(pat) = typed.pat.as_ref() else {
+            return Err(Error::new(typed.pat.span(), "Parameters must be plain named arguments"));
+        };
+        let found = declared
+            .iter_mut()
+            .find(|param| param.as_ref().is_some_and(|param| param.name == pat.ident));
+        let Some(param) = found.and_then(Option::take) else {
+            let message = format!(
+                "`{0}` needs a default for the real input, declared with `params({0} = ..)` in the attribute",
+                pat.ident
+            );
+            return Err(Error::new(pat.ident.span(), message));
+        };
+        resolved.push(ExtraArg::Param {
+            param: Box::new(param),
+            ty: typed.ty.clone(),
+        });
+    }
+    if let Some(unused) = declared.into_iter().flatten().next() {
+        let message = format!("`{}` isn't an argument of `{}`", unused.name, source_fn.sig.ident);
+        return Err(Error::new(unused.name.span(), message));
+    }
+
+    Ok(resolved)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct AocGeneratorData<'a> {
     pub display_slug: Ident,
+    /// The generated type, without any `Result` around it.
     pub gen_type: &'a Type,
+    pub fallible: bool,
     pub source: &'a ItemFn,
+    /// Parameters and any recorder taken after the input, in argument order.
+    pub params: Vec<ExtraArg>,
+    pub tags: VariantTags,
 }
 
 impl<'a> AocGeneratorData<'a> {
@@ -22,27 +155,44 @@ impl<'a> AocGeneratorData<'a> {
             );
             return Err(e);
         };
+        let (gen_type, fallible) = split_return_type(ty_data);
         Ok(AocGeneratorData {
             display_slug: args.display_slug,
-            gen_type: ty_data.as_ref(),
+            gen_type,
+            fallible,
             source: source_fn,
+            params: resolve_params(source_fn, args.params)?,
+            tags: args.tags,
         })
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// How a solver takes the generator's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputBorrow {
+    Owned,
+    Shared,
+    Mutable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AocSolverData<'a> {
     pub problem_part: AocPart,
     pub display_slug: Ident,
     pub input_type: &'a Type,
     pub source: &'a ItemFn,
+    /// The answer type, without any `Result` around it.
     pub solution_type: &'a Type,
+    pub fallible: bool,
+    /// Parameters and any recorder taken after the input, in argument order.
+    pub params: Vec<ExtraArg>,
+    pub tags: VariantTags,
 }
 
 impl<'a> AocSolverData<'a> {
     pub fn new(args: AocSolverArgs, source_fn: &'a ItemFn) -> syn::Result<AocSolverData<'a>> {
-        if source_fn.sig.inputs.len() != 1 {
-            let e = Error::new(source_fn.sig.inputs.span(), "Solvers must accept exactly one argument, the data from the generator. This argument may be a tuple, struct, or other type.");
+        if source_fn.sig.inputs.is_empty() {
+            let e = Error::new(source_fn.sig.inputs.span(), "Solvers must accept the data from the generator as their first argument, followed only by any `params(..)` and an `Option<Recorder>`. This argument may be a tuple, struct, or other type.");
             Err(e)
         } else {
             let Some(solve_type) = source_fn.sig.inputs.first() else {
@@ -57,23 +207,52 @@ impl<'a> AocSolverData<'a> {
                 return Err(e);
             };
             let solve_type = &solve_type.ty;
+            let (solution_type, fallible) = split_return_type(solution_type_box);
+            check_answer_type(args.problem_part, solution_type)?;
             Ok(AocSolverData {
                 problem_part: args.problem_part,
                 display_slug: args.display_slug,
                 input_type: solve_type.as_ref(),
                 source: source_fn,
-                solution_type: solution_type_box,
+                solution_type,
+                fallible,
+                params: resolve_params(source_fn, args.params)?,
+                tags: args.tags,
             })
         }
     }
+
+    pub fn answer_type(&self, part: AocPart) -> &'a Type {
+        part_answer_type(self.problem_part, self.solution_type, part)
+    }
+
+    /// Whether this solver can take a generator's `T` output, either as `T`, `&T` or `&mut T`.
+    /// A generator that itself returns a reference only matches a solver taking exactly that type.
+    pub fn accepts(&self, gen_type: &Type) -> Option<InputBorrow> {
+        if self.input_type == gen_type {
+            return Some(InputBorrow::Owned);
+        }
+        match self.input_type {
+            Type::Reference(r) if r.elem.as_ref() == gen_type => Some(match r.mutability {
+                Some(_) => InputBorrow::Mutable,
+                None => InputBorrow::Shared,
+            }),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AocSolutionData<'a> {
     pub problem_part: AocPart,
     pub display_slug: Ident,
     pub source: &'a ItemFn,
+    /// The answer type, without any `Result` around it.
     pub solution_type: &'a Type,
+    pub fallible: bool,
+    /// Parameters and any recorder taken after the input, in argument order.
+    pub params: Vec<ExtraArg>,
+    pub tags: VariantTags,
 }
 
 impl<'a> AocSolutionData<'a> {
@@ -83,11 +262,20 @@ impl<'a> AocSolutionData<'a> {
             return Err(e);
         };
 
+        let (solution_type, fallible) = split_return_type(sol_type);
+        check_answer_type(args.problem_part, solution_type)?;
         Ok(AocSolutionData {
             problem_part: args.problem_part,
             display_slug: args.display_slug,
             source: source_fn,
-            solution_type: sol_type,
+            solution_type,
+            fallible,
+            params: resolve_params(source_fn, args.params)?,
+            tags: args.tags,
         })
     }
+
+    pub fn answer_type(&self, part: AocPart) -> &'a Type {
+        part_answer_type(self.problem_part, self.solution_type, part)
+    }
 }
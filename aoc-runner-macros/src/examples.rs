@@ -0,0 +1,138 @@
+//! Example inputs kept as files under the workspace's `examples/` folder, next to `input/`.
+//!
+//! `aoc_examples!(2023, day20)` turns every `examples/2023/20/<name>.txt` into a test, with the
+//! expected answers in `<name>.answers` alongside it. Both are read through `include_str!`, so edits
+//! rebuild the tests, but a newly added example is only picked up once the day file recompiles.
+//! `aoc run --example <name>` runs the day on the same files and checks the same answers.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::Span;
+use syn::Error;
+
+use crate::{parser::caseargs::AocCaseArgs, workspace};
+
+/// A path given relative to the workspace root, which must exist.
+pub fn resolve(relative: &str, span: Span) -> syn::Result<PathBuf> {
+    let path = workspace::root().join(relative);
+    if !path.is_file() {
+        let message = format!("Example file {} doesn't exist", path.display());
+        return Err(Error::new(span, message));
+    }
+    Ok(path)
+}
+
+/// One example input and what it should produce.
+pub struct Example {
+    /// The file stem, eg. `1` for `1.txt`.
+    pub name: String,
+    pub input: PathBuf,
+    pub answers: PathBuf,
+    pub args: AocCaseArgs,
+}
+
+/// Every example for a day, in name order.
+pub fn discover(year: u32, day: u32, span: Span) -> syn::Result<Vec<Example>> {
+    let dir = workspace::root()
+        .join("examples")
+        .join(year.to_string())
+        .join(day.to_string());
+    let entries = fs::read_dir(&dir).map_err(|e| Error::new(span, format!("Can't read {}: {e}", dir.display())))?;
+    let mut inputs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    inputs.sort();
+    if inputs.is_empty() {
+        return Err(Error::new(span, format!("No `*.txt` examples in {}", dir.display())));
+    }
+
+    inputs.into_iter().map(|input| read_example(input, span)).collect()
+}
+
+fn read_example(input: PathBuf, span: Span) -> syn::Result<Example> {
+    let answers = input.with_extension("answers");
+    let text = fs::read_to_string(&answers).map_err(|e| {
+        let message = format!(
+            "Example {} needs its answers in {}: {e}",
+            input.display(),
+            answers.display()
+        );
+        Error::new(span, message)
+    })?;
+    let args =
+        AocCaseArgs::parse_answers(&text).map_err(|e| Error::new(span, format!("In {}: {e}", answers.display())))?;
+    if args.file.is_some() {
+        let message = format!(
+            "In {}: `file` can't be given, the example is the input",
+            answers.display()
+        );
+        return Err(Error::new(span, message));
+    }
+
+    Ok(Example {
+        name: file_stem(&input),
+        input,
+        answers,
+        args,
+    })
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples_are_found_in_name_order() {
+        let examples = discover(2023, 8, Span::call_site()).unwrap();
+        let names: Vec<_> = examples.iter().map(|example| example.name.as_str()).collect();
+        assert_eq!(names, ["1", "2", "3"]);
+        assert!(examples[0].args.expected_p1.is_some() && examples[0].args.expected_p2.is_none());
+        assert!(examples[2].args.expected_p1.is_none() && examples[2].args.expected_p2.is_some());
+        assert_eq!(examples[0].answers, workspace::root().join("examples/2023/8/1.answers"));
+    }
+
+    #[test]
+    fn days_without_examples_are_an_error() {
+        let error = discover(2023, 99, Span::call_site()).err().unwrap();
+        assert!(error.to_string().starts_with("Can't read "), "{error}");
+    }
+
+    #[test]
+    fn examples_without_answers_are_an_error() {
+        let dir = std::env::temp_dir().join(format!("aoc-examples-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("1.txt");
+        fs::write(&input, "1\n").unwrap();
+
+        let error = read_example(input, Span::call_site()).err().unwrap();
+        assert!(error.to_string().contains("needs its answers in"), "{error}");
+
+        fs::write(dir.join("1.answers"), "part1 = 1\nfile = \"x.txt\"\n").unwrap();
+        let error = read_example(dir.join("1.txt"), Span::call_site()).err().unwrap();
+        assert!(
+            error
+                .to_string()
+                .ends_with("`file` can't be given, the example is the input"),
+            "{error}"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolved_paths_must_exist() {
+        assert!(resolve("examples/2023/20/1.txt", Span::call_site()).is_ok());
+        assert!(resolve("examples/2023/20/9.txt", Span::call_site()).is_err());
+    }
+}
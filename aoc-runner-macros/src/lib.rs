@@ -1,22 +1,26 @@
-use aggregate::{discover_mod_contents, AocSolutionsAggregation};
-use anyhow::Context;
-use cargo_metadata::MetadataCommand;
-use domain::{AocGeneratorData, AocSolverData};
-use parser::caseargs::AocCaseArgs;
+use aggregate::{discover_mod_contents, AocSolutionsAggregation, ComposedSoln};
+use domain::{AocGeneratorData, AocSolutionData, AocSolverData, ExtraArg, InputBorrow};
+use parser::caseargs::{AocCaseArgs, CaseConst, Expected};
 use parser::macroargs::AocMacroArgs;
+use parser::paramargs::ParamArg;
+use parser::propertyargs::AocPropertyArgs;
+use parser::tagargs::VariantTags;
+use partflag::AocPart;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
 use quote::ToTokens;
+use quote::{format_ident, quote};
 use syn::parse_macro_input;
+use syn::ItemFn;
 use syn::Type;
 use syn::{Ident, ItemMod};
-use syn::{ItemConst, ItemFn};
 
 mod aggregate;
 mod domain;
+mod examples;
 mod parser;
 mod partflag;
+mod workspace;
 
 // Flag macros ------------------------------------------------------------
 #[proc_macro_attribute]
@@ -34,6 +38,13 @@ pub fn solution(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+/// Marks a generator that no solver takes, or a solver that no generator feeds, as intended.
+/// `#[aoc]` otherwise refuses to compile such a day.
+#[proc_macro_attribute]
+pub fn allow_unmatched(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
 #[proc_macro_attribute]
 pub fn flag(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as ItemFn);
@@ -45,47 +56,210 @@ pub fn flag(_attr: TokenStream, item: TokenStream) -> TokenStream {
 #[proc_macro_attribute]
 pub fn aoc_case(attr: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as AocCaseArgs);
-    let exp_p1 = args.expected_p1;
-    let p2 = args.expected_p2;
-    let input = parse_macro_input!(item as ItemConst);
-    let in_name = &input.ident;
-    let slug_str: String = format!("aoc_test_{}", &input.ident.to_string().to_lowercase());
-    let slug = Ident::new(&slug_str, input.ident.span());
-
-    if let Some(exp_p2) = p2 {
-        quote! {
-            #input
+    let case = parse_macro_input!(item as CaseConst);
+    gen_case(&args, &case)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-            #[test]
-            fn #slug() {
-                let expected_p1 = #exp_p1;
-                let expected_p2 = #exp_p2;
+/// The case's const, with its input read from `file` if given, and its test.
+fn gen_case(args: &AocCaseArgs, case: &CaseConst) -> syn::Result<proc_macro2::TokenStream> {
+    let in_name = &case.ident;
+    let slug_str: String = format!("aoc_test_{}", &case.ident.to_string().to_lowercase());
+    let slug = Ident::new(&slug_str, case.ident.span());
+
+    let input = match (&args.file, &case.value) {
+        (Some(file), None) => {
+            let path = examples::resolve(&file.value(), file.span())?;
+            let path = path.to_string_lossy().into_owned();
+            case.with_value(quote!(include_str!(#path)))
+        }
+        (None, Some(_)) => case.to_token_stream(),
+        (Some(file), Some(_)) => {
+            return Err(syn::Error::new(
+                file.span(),
+                "The input comes from `file`, so the const can't have a value",
+            ));
+        }
+        (None, None) => {
+            return Err(syn::Error::new(
+                case.ident.span(),
+                "Give the const a value, or `file = \"..\"` to read one",
+            ));
+        }
+    };
+    let test = gen_case_test(args, &slug, quote!(#in_name), quote!());
 
-                for (idx, p1) in super::_gen_lists::P1_SOLUTIONS.iter().enumerate() {
-                    let test_label = super::_gen_lists::P1_LABELS[idx];
-                    assert_eq!(expected_p1, p1(#in_name), "Part 1 Test failed solution: {}", test_label);
-                }
-                for (idx, p2) in super::_gen_lists::P2_SOLUTIONS.iter().enumerate() {
-                    let test_label = super::_gen_lists::P1_LABELS[idx];
-                    assert_eq!(expected_p2, p2(#in_name), "Part 2 Test failed solution: {}", test_label);
+    Ok(quote! {
+        #input
+
+        #test
+    })
+}
+
+/// Differential testing of a day's variants. On a `fn(&mut aoc_runner::property::Rng, usize) -> String`
+/// in the day's test module that writes a random input of about the given size, this generates a
+/// test running every variant of each part on many such inputs, eg. `#[aoc_property(cases = 200)]`.
+#[proc_macro_attribute]
+pub fn aoc_property(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as AocPropertyArgs);
+    let generator = parse_macro_input!(item as ItemFn);
+    let name = &generator.sig.ident;
+    let test = format_ident!("aoc_property_{}", name);
+    let AocPropertyArgs { cases, max_size, part } = args;
+    let part = match part {
+        Some(part) => quote!(Some(#part)),
+        None => quote!(None),
+    };
+
+    quote! {
+        #generator
+
+        #[test]
+        fn #test() {
+            aoc_runner::property::check(
+                &super::AOC_DAY,
+                #name,
+                aoc_runner::property::Config {
+                    cases: #cases,
+                    max_size: #max_size,
+                    part: #part,
+                },
+            );
+        }
+    }
+    .into()
+}
+
+/// One test per example file of a day, eg. `aoc_examples!(2023, day20);` in a day's test module.
+/// See `examples` for the layout.
+#[proc_macro]
+pub fn aoc_examples(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as AocMacroArgs);
+    let examples = match examples::discover(args.year_num, args.day_num, Span::call_site()) {
+        Ok(examples) => examples,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    let tests = examples.iter().map(|example| {
+        let name: String = example
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
                 }
-            }
+            })
+            .collect();
+        let slug = Ident::new(&format!("aoc_example_{name}"), Span::call_site());
+        let input = example.input.to_string_lossy().into_owned();
+        let answers = example.answers.to_string_lossy().into_owned();
+        // Including the answers file too makes editing it rebuild the test.
+        let prelude = quote! {
+            const EXAMPLE: &str = include_str!(#input);
+            const _: &str = include_str!(#answers);
+        };
+        gen_case_test(&example.args, &slug, quote!(EXAMPLE), prelude)
+    });
+
+    quote!(#(#tests)*).into()
+}
+
+/// The `#[test]` for one case: every requested part's check on `input`, with any parameters
+/// overridden around them.
+fn gen_case_test(
+    args: &AocCaseArgs,
+    slug: &Ident,
+    input: proc_macro2::TokenStream,
+    prelude: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let ignore = args.ignore.as_ref().map(|reason| quote!(#[ignore = #reason]));
+    let checks = [(1u8, &args.expected_p1), (2u8, &args.expected_p2)]
+        .into_iter()
+        .filter_map(|(part, expected)| Some(gen_case_part(part, expected.as_ref()?, args.variant.as_ref(), &input)));
+
+    let body = quote!(#(#checks)*);
+    let body = match &args.params {
+        None => body,
+        Some(params) => {
+            let overrides = params
+                .iter()
+                .map(|ParamArg { name, value }| quote!(super::_gen_lists::params::#name(#value)));
+            quote!(aoc_runner::params::with_overrides(vec![ #(#overrides),* ], || { #body });)
         }
-        .into()
-    } else {
-        quote! {
-            #input
+    };
 
-            #[test]
-            fn #slug() {
-                let expected_p1 = #exp_p1;
+    quote! {
+        #[test]
+        #ignore
+        fn #slug() {
+            #prelude
+            #body
+        }
+    }
+}
 
-                for p1 in _gen_lists::P1_SOLUTIONS {
-                    assert_eq!(expected_p1, p1(#in_name));
+/// Checks every solution of one part against the case, but slow ones unless they're asked for,
+/// or only those matching `variant`.
+fn gen_case_part(
+    part: u8,
+    expected: &Expected,
+    variant: Option<&syn::LitStr>,
+    input: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let (labels, tags, solutions) = match part {
+        1 => (quote!(P1_LABELS), quote!(P1_TAGS), quote!(P1_SOLUTIONS)),
+        _ => (quote!(P2_LABELS), quote!(P2_TAGS), quote!(P2_SOLUTIONS)),
+    };
+    let check = gen_case_check(part, expected, quote!(solution(#input)));
+    let Some(variant) = variant else {
+        return quote! {
+            for (idx, solution) in super::_gen_lists::#solutions.iter().enumerate() {
+                if !super::_gen_lists::#tags[idx].included() {
+                    continue;
                 }
+                let test_label = super::_gen_lists::#labels[idx];
+                #check
+            }
+        };
+    };
+
+    quote! {
+        let mut checked = 0;
+        for (idx, solution) in super::_gen_lists::#solutions.iter().enumerate() {
+            let test_label = super::_gen_lists::#labels[idx];
+            if !aoc_runner::harness::label_matches(test_label, #variant) {
+                continue;
             }
+            checked += 1;
+            #check
+        }
+        assert!(
+            checked > 0,
+            "Part {} has no solution matching variant {:?}, found {:?}",
+            #part, #variant, super::_gen_lists::#labels
+        );
+    }
+}
+
+/// Asserts that one solution's result, `call`, meets the expectation. Expects `test_label` in scope.
+fn gen_case_check(part: u8, expected: &Expected, call: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match expected {
+        Expected::Answer(answer) => quote! {
+            match #call {
+                Ok(answer) => assert_eq!(#answer, answer, "Part {} Test failed solution: {}", #part, test_label),
+                Err(e) => panic!("Part {} Test failed solution: {}: {}", #part, test_label, e),
+            }
+        },
+        Expected::Error(message) => {
+            let message = match message {
+                Some(message) => quote!(Some(#message)),
+                None => quote!(None),
+            };
+            quote!(aoc_runner::harness::expect_error(#part, test_label, #call, #message);)
         }
-        .into()
     }
 }
 
@@ -93,8 +267,8 @@ pub fn aoc_case(attr: TokenStream, item: TokenStream) -> TokenStream {
 
 #[proc_macro_attribute]
 pub fn aoc(args: TokenStream, item: TokenStream) -> TokenStream {
-    let item = parse_macro_input!(item as ItemMod);
-    let mod_name = &item.ident;
+    let mut item = parse_macro_input!(item as ItemMod);
+    let mod_name = &item.ident.clone();
 
     let macro_args = parse_macro_input!(args as AocMacroArgs);
 
@@ -105,43 +279,295 @@ pub fn aoc(args: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    let mod_extension = gen_solution_lists_mod(&agg_result, mod_name);
+    let mod_extension = gen_solution_lists_mod(&agg_result, mod_name, (macro_args.year_num, macro_args.day_num));
+    let param_setters = gen_param_setters(&agg_result);
+    if let Some((_, contents)) = &mut item.content {
+        contents.push(syn::Item::Verbatim(param_setters));
+    }
 
     let mut item_ts = item.into_token_stream();
 
     item_ts.extend(mod_extension);
     // Disabled benchmarking to avoid dependency conflicts
     // item_ts.extend(gen_quick_microbench());
-    // item_ts.extend(gen_slow_microbench());
     item_ts.extend(gen_main(macro_args.year_num, macro_args.day_num));
+    item_ts.extend(gen_bench_cases(macro_args.day_num));
 
     item_ts.into()
 }
 
-fn gen_idents_from_solns<'a>(
-    part_indicator: &str,
-    solns: impl Iterator<Item = (&'a AocGeneratorData<'a>, &'a AocSolverData<'a>)>,
-) -> Vec<(&'a Ident, &'a Ident, Ident)> {
-    solns
-        .map(|(gen, sol)| {
-            let g_ident = &gen.source.sig.ident;
-            let g_slug = &gen.display_slug;
-            let s_ident = &sol.source.sig.ident;
-            let s_slug = &sol.display_slug;
-            let f_ident = Ident::new(
-                format!("f_{part_indicator}_{g_slug}_{s_slug}").as_str(),
-                Span::call_site(),
-            );
-            (g_ident, s_ident, f_ident)
-        })
-        .collect()
+/// The wrapper name and body for one generator feeding one solver. Errors from fallible stages
+/// are tagged with the stage that raised them.
+fn gen_composed_wrapper(
+    day: DayId,
+    part: AocPart,
+    (gen, sol, borrow): ComposedSoln,
+) -> (Ident, proc_macro2::TokenStream) {
+    let part_indicator = part_indicator(part);
+    let g_ident = &gen.source.sig.ident;
+    let g_slug = gen.display_slug.to_string();
+    let s_ident = &sol.source.sig.ident;
+    let s_slug = sol.display_slug.to_string();
+    let f_ident = Ident::new(
+        format!("f_{part_indicator}_{g_slug}_{s_slug}").as_str(),
+        Span::call_site(),
+    );
+    let (binding, arg) = gen_solver_arg(borrow);
+    let g_params = gen_param_args(&gen.params);
+    let s_params = gen_param_args(&sol.params);
+
+    let generate = quote!(aoc_runner::harness::stage("generator", #g_slug, || #g_ident(input #g_params)));
+    let generate = gen_traced(day, part_number(part), "generator", &g_slug, generate);
+    let generate = match gen.fallible {
+        true => quote!(#generate.map_err(|e| aoc_runner::harness::SolveError::new("generator", #g_slug, e))?),
+        false => generate,
+    };
+    let solve = quote!(aoc_runner::harness::stage("solver", #s_slug, || #s_ident(#arg #s_params)));
+    let solve = gen_traced(day, part_number(part), "solver", &s_slug, solve);
+    let solve = match sol.fallible {
+        true => quote!(#solve.map_err(|e| aoc_runner::harness::SolveError::new("solver", #s_slug, e))),
+        false => quote!(::core::result::Result::Ok(#solve)),
+    };
+    let project = gen_answer_projection(sol.problem_part, part);
+
+    let body = quote! {
+        let #binding = #generate;
+        #solve #project
+    };
+    (f_ident, body)
+}
+
+/// The wrapper name and body for a `#[solution]`, bringing it to the same signature as the
+/// composed solutions.
+fn gen_solution_wrapper(day: DayId, part: AocPart, sln: &AocSolutionData) -> (Ident, proc_macro2::TokenStream) {
+    let part_indicator = part_indicator(part);
+    let slug = sln.display_slug.to_string();
+    let f_ident = Ident::new(format!("s_{part_indicator}_{slug}").as_str(), Span::call_site());
+    let call = gen_solution_call(day, part_number(part), sln);
+    let project = gen_answer_projection(sln.problem_part, part);
+
+    (f_ident, quote!(#call #project))
+}
+
+/// Calls a `#[solution]` on the input, as a `Result` with any error tagged.
+fn gen_solution_call(day: DayId, part: Option<u8>, sln: &AocSolutionData) -> proc_macro2::TokenStream {
+    let ident = &sln.source.sig.ident;
+    let slug = sln.display_slug.to_string();
+    let params = gen_param_args(&sln.params);
+    let call = gen_traced(day, part, "solution", &slug, quote!(#ident(input #params)));
+    match sln.fallible {
+        true => quote!(#call.map_err(|e| aoc_runner::harness::SolveError::new("solution", #slug, e))),
+        false => quote!(::core::result::Result::Ok(#call)),
+    }
+}
+
+/// The `(year, day)` the generated code is for.
+type DayId = (u32, u32);
+
+/// Wraps `call` in a span named after the year, day, part and label of what it calls, for
+/// `aoc run --trace`. `part` is `None` for calls shared by both parts.
+fn gen_traced(
+    (year, day): DayId,
+    part: Option<u8>,
+    kind: &str,
+    slug: &str,
+    call: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let (name, part) = match part {
+        Some(part) => (
+            format!("{year} day {day} part {part}: {kind} {slug}"),
+            quote!(part = #part,),
+        ),
+        None => (format!("{year} day {day}: {kind} {slug}"), quote!()),
+    };
+    quote!(aoc_runner::tracing::info_span!(#name, year = #year, day = #day, #part label = %#slug).in_scope(|| #call))
+}
+
+fn part_number(part: AocPart) -> Option<u8> {
+    match part {
+        AocPart::Part1 => Some(1),
+        AocPart::Part2 => Some(2),
+        AocPart::Both => None,
+    }
+}
+
+/// The extra arguments for a function's `params(..)`, each its current value or the default, and
+/// the recorder it may take.
+fn gen_param_args(params: &[ExtraArg]) -> proc_macro2::TokenStream {
+    let args = params.iter().map(|param| match param {
+        ExtraArg::Param { param, .. } => {
+            let ParamArg { name, value } = param.as_ref();
+            let name = name.to_string();
+            quote!(, aoc_runner::params::get(#name, || #value))
+        }
+        ExtraArg::Recorder => quote!(, aoc_runner::viz::recorder()),
+    });
+    quote!(#(#args)*)
+}
+
+/// A function per parameter, taking it as the type it's declared with, for `#[aoc_case(params(..))]`
+/// to call, so an override of the wrong type doesn't compile. They're in the day's module to see
+/// its types as its functions do.
+fn gen_param_setters(agg_result: &AocSolutionsAggregation) -> proc_macro2::TokenStream {
+    let setters = agg_result.declared_params().into_iter().map(|(name, ty)| {
+        let text = name.to_string();
+        quote! {
+            pub fn #name(value: #ty) -> aoc_runner::params::Override {
+                aoc_runner::params::Override::new(#text, value)
+            }
+        }
+    });
+    quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        pub mod _aoc_params {
+            use super::*;
+
+            #(#setters)*
+        }
+    }
+}
+
+fn part_indicator(part: AocPart) -> &'static str {
+    match part {
+        AocPart::Part1 => "p1",
+        AocPart::Part2 => "p2",
+        AocPart::Both => "both",
+    }
+}
+
+/// For solvers and solutions of `both` parts, picks one part's answer out of the pair.
+fn gen_answer_projection(problem_part: AocPart, part: AocPart) -> proc_macro2::TokenStream {
+    match (problem_part, part) {
+        (AocPart::Both, AocPart::Part1) => quote!(.map(|(answer, _)| answer)),
+        (AocPart::Both, AocPart::Part2) => quote!(.map(|(_, answer)| answer)),
+        _ => quote!(),
+    }
+}
+
+/// A runner for a `#[solution]` of `both` parts, so shared runs get both answers from one call.
+fn gen_shared_solution_runner(
+    day: DayId,
+    sln: &AocSolutionData,
+    p1_index: usize,
+    p2_index: usize,
+) -> (Ident, proc_macro2::TokenStream) {
+    let slug = sln.display_slug.to_string();
+    let f_ident = Ident::new(format!("shared_both_{slug}").as_str(), Span::call_site());
+    let call = gen_solution_call(day, None, sln);
+
+    (f_ident, quote!(run.solve_both(#p1_index, #p2_index, || #call);))
+}
+
+/// A solver fed by a shared generator: its index in each part's solutions it appears in, and how
+/// it takes the input. Solvers of `both` parts have both indices and run once.
+type SharedConsumer<'s, 'a> = ([Option<usize>; 2], &'s AocSolverData<'a>, InputBorrow);
+
+/// One solver call within a shared runner, on `input`.
+fn gen_shared_solve(
+    day: DayId,
+    &(indices, sol, borrow): &SharedConsumer,
+    input: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let s_ident = &sol.source.sig.ident;
+    let s_slug = sol.display_slug.to_string();
+    let params = gen_param_args(&sol.params);
+    let arg = match borrow {
+        InputBorrow::Owned => quote!(#input),
+        InputBorrow::Shared => quote!(&#input),
+        InputBorrow::Mutable => quote!(&mut #input),
+    };
+    let part = match indices {
+        [Some(_), None] => Some(1),
+        [None, Some(_)] => Some(2),
+        _ => None,
+    };
+    let call = gen_traced(day, part, "solver", &s_slug, quote!(#s_ident(#arg #params)));
+    let call = match sol.fallible {
+        true => quote!(#call.map_err(|e| aoc_runner::harness::SolveError::new("solver", #s_slug, e))),
+        false => quote!(::core::result::Result::Ok(#call)),
+    };
+    match indices {
+        [Some(p1), Some(p2)] => quote!(run.solve_both(#p1, #p2, || #call);),
+        [Some(index), None] => quote!(run.solve(1u8, #index, || #call);),
+        [None, Some(index)] => quote!(run.solve(2u8, #index, || #call);),
+        [None, None] => quote!(),
+    }
+}
+
+/// A runner that parses with one generator and hands the result to every solver of either part
+/// which accepts it, for `SharedGenerator`. Solvers borrowing the input go first, then those
+/// consuming or mutating it, each on its own copy but the last, which gets the original.
+fn gen_shared_runner(
+    day: DayId,
+    gen: &AocGeneratorData,
+    consumers: &[SharedConsumer],
+) -> (Ident, proc_macro2::TokenStream) {
+    let g_ident = &gen.source.sig.ident;
+    let g_slug = gen.display_slug.to_string();
+    let f_ident = Ident::new(format!("shared_{g_slug}").as_str(), Span::call_site());
+    let params = gen_param_args(&gen.params);
+    let call = gen_traced(day, None, "generator", &g_slug, quote!(#g_ident(input #params)));
+
+    let generate = match gen.fallible {
+        true => quote!(#call.map_err(|e| aoc_runner::harness::SolveError::new("generator", #g_slug, e))),
+        false => quote!(::core::result::Result::Ok(#call)),
+    };
+    let regenerate = match gen.fallible {
+        true => quote!(#call.unwrap_or_else(|e| panic!("generator `{}` failed on a second run: {}", #g_slug, e))),
+        false => call.clone(),
+    };
+
+    let (borrowing, taking): (Vec<_>, Vec<_>) = consumers
+        .iter()
+        .partition(|(_, _, borrow)| *borrow == InputBorrow::Shared);
+    let mut steps: Vec<proc_macro2::TokenStream> = borrowing
+        .iter()
+        .map(|c| gen_shared_solve(day, c, quote!(parsed)))
+        .collect();
+    if let Some((last, copied)) = taking.split_last() {
+        if !copied.is_empty() {
+            steps.push(quote!(
+                use aoc_runner::harness::{ReuseClone as _, ReuseFallback as _};
+            ));
+        }
+        for consumer in copied {
+            let call = gen_shared_solve(day, consumer, quote!(copy));
+            steps.push(quote! {
+                #[allow(unused_mut)]
+                let mut copy = (&aoc_runner::harness::Reuse(&parsed)).copy().unwrap_or_else(|| #regenerate);
+                #call
+            });
+        }
+        let call = gen_shared_solve(day, last, quote!(parsed));
+        steps.push(quote! {
+            #[allow(unused_mut)]
+            let mut parsed = parsed;
+            #call
+        });
+    }
+
+    let body = quote! {
+        let Some(parsed) = run.generate(|| #generate) else {
+            return;
+        };
+        #(#steps)*
+    };
+    (f_ident, body)
+}
+
+/// The binding for the generator's output and the expression handing it to the solver.
+fn gen_solver_arg(borrow: InputBorrow) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    match borrow {
+        InputBorrow::Owned => (quote!(parsed), quote!(parsed)),
+        InputBorrow::Shared => (quote!(parsed), quote!(&parsed)),
+        InputBorrow::Mutable => (quote!(mut parsed), quote!(&mut parsed)),
+    }
 }
 
-fn gen_composed_labels<'a>(
-    solns: impl Iterator<Item = (&'a AocGeneratorData<'a>, &'a AocSolverData<'a>)>,
-) -> Vec<String> {
+fn gen_composed_labels<'a>(solns: impl Iterator<Item = ComposedSoln<'a, 'a>>) -> Vec<String> {
     solns
-        .map(|(gen, sol)| {
+        .map(|(gen, sol, _)| {
             let g_slug = &gen.display_slug.to_string();
             let s_slug = &sol.display_slug.to_string();
             let label = format!("{g_slug} / {s_slug}");
@@ -150,72 +576,241 @@ fn gen_composed_labels<'a>(
         .collect()
 }
 
-fn gen_solution_lists_mod(agg_result: &AocSolutionsAggregation, mod_name: &Ident) -> proc_macro2::TokenStream {
-    let p1_composed_data: Vec<(&Ident, &Ident, Ident)> = gen_idents_from_solns("p1", agg_result.p1_composed_solns());
+/// The order to list a part's `len` variants in, as indices into the declared order: the primary
+/// first, then the rest as declared.
+fn variant_order(len: usize, primary: Option<usize>) -> Vec<usize> {
+    primary
+        .into_iter()
+        .chain((0..len).filter(|&i| Some(i) != primary))
+        .collect()
+}
 
-    let p1_fn_idents: Vec<&Ident> = p1_composed_data.iter().map(|(_, _, f)| f).collect();
-    let p1_gen_idents: Vec<&Ident> = p1_composed_data.iter().map(|(g, _, _)| *g).collect();
-    let p1_solver_idents: Vec<&Ident> = p1_composed_data.iter().map(|(_, s, _)| *s).collect();
+/// Where each declared variant ends up in `order`.
+fn positions(order: &[usize]) -> Vec<usize> {
+    let mut positions = vec![0; order.len()];
+    for (position, &index) in order.iter().enumerate() {
+        positions[index] = position;
+    }
+    positions
+}
+
+fn reorder<T: Clone>(items: &[T], order: &[usize]) -> Vec<T> {
+    order.iter().map(|&index| items[index].clone()).collect()
+}
+
+/// A variant's tags as the runner sees them. Which one's primary is already settled by the order.
+fn gen_variant_tags(tags: VariantTags) -> proc_macro2::TokenStream {
+    let VariantTags { slow, experimental, .. } = tags;
+    quote!(aoc_runner::harness::VariantTags { slow: #slow, experimental: #experimental })
+}
+
+fn gen_solution_lists_mod(
+    agg_result: &AocSolutionsAggregation,
+    mod_name: &Ident,
+    day: DayId,
+) -> proc_macro2::TokenStream {
+    let (mut p1_fn_idents, mut p1_bodies): (Vec<_>, Vec<_>) = agg_result
+        .p1_composed_solns()
+        .map(|soln| gen_composed_wrapper(day, AocPart::Part1, soln))
+        .unzip();
+    p1_fn_idents.extend(
+        agg_result
+            .p1_user_solns()
+            .map(|sln| gen_solution_wrapper(day, AocPart::Part1, sln).0),
+    );
+    p1_bodies.extend(
+        agg_result
+            .p1_user_solns()
+            .map(|sln| gen_solution_wrapper(day, AocPart::Part1, sln).1),
+    );
 
     let mut p1_labels = gen_composed_labels(agg_result.p1_composed_solns());
-    let mut p1_impls = p1_fn_idents.clone();
-    p1_impls.extend(agg_result.p1_user_solns().map(|sln| &sln.source.sig.ident));
     p1_labels.extend(agg_result.p1_user_solns().map(|sln| sln.display_slug.to_string()));
+    // The primary variant goes first; `p1_positions` maps the declared order to the listed one.
+    let p1_order = variant_order(p1_labels.len(), agg_result.primary_variant(AocPart::Part1));
+    let p1_positions = positions(&p1_order);
+    let p1_fn_idents = reorder(&p1_fn_idents, &p1_order);
+    let p1_bodies = reorder(&p1_bodies, &p1_order);
+    let p1_labels = reorder(&p1_labels, &p1_order);
+    let p1_tags = reorder(&agg_result.variant_tags(AocPart::Part1), &p1_order)
+        .into_iter()
+        .map(gen_variant_tags);
     let p1_ret = agg_result
         .p1_result_type
         .unwrap_or(&Type::Verbatim(quote!(String)))
         .to_owned();
-    let p1_len = p1_impls.len();
-
-    let p2_data: Vec<(&Ident, &Ident, Ident)> = gen_idents_from_solns("p2", agg_result.p2_composed_solns());
-
-    let p2_fn_idents: Vec<&Ident> = p2_data.iter().map(|(_, _, f)| f).collect();
-    let p2_gen_idents: Vec<&Ident> = p2_data.iter().map(|(g, _, _)| *g).collect();
-    let p2_solver_idents: Vec<&Ident> = p2_data.iter().map(|(_, s, _)| *s).collect();
+    let p1_len = p1_fn_idents.len();
+    let p1_indices = 0..p1_len;
+
+    let (mut p2_fn_idents, mut p2_bodies): (Vec<_>, Vec<_>) = agg_result
+        .p2_composed_solns()
+        .map(|soln| gen_composed_wrapper(day, AocPart::Part2, soln))
+        .unzip();
+    p2_fn_idents.extend(
+        agg_result
+            .p2_user_solns()
+            .map(|sln| gen_solution_wrapper(day, AocPart::Part2, sln).0),
+    );
+    p2_bodies.extend(
+        agg_result
+            .p2_user_solns()
+            .map(|sln| gen_solution_wrapper(day, AocPart::Part2, sln).1),
+    );
 
     let mut p2_labels = gen_composed_labels(agg_result.p2_composed_solns());
-    let mut p2_impls = p2_fn_idents.clone();
     p2_labels.extend(agg_result.p2_user_solns().map(|sln| sln.display_slug.to_string()));
-    p2_impls.extend(agg_result.p2_user_solns().map(|sln| &sln.source.sig.ident));
+    // The primary variant goes first; `p2_positions` maps the declared order to the listed one.
+    let p2_order = variant_order(p2_labels.len(), agg_result.primary_variant(AocPart::Part2));
+    let p2_positions = positions(&p2_order);
+    let p2_fn_idents = reorder(&p2_fn_idents, &p2_order);
+    let p2_bodies = reorder(&p2_bodies, &p2_order);
+    let p2_labels = reorder(&p2_labels, &p2_order);
+    let p2_tags = reorder(&agg_result.variant_tags(AocPart::Part2), &p2_order)
+        .into_iter()
+        .map(gen_variant_tags);
     let p2_ret = agg_result
         .p2_result_type
         .unwrap_or(&Type::Verbatim(quote!(String)))
         .to_owned();
-    let p2_len = p2_impls.len();
+    let p2_len = p2_fn_idents.len();
+    let p2_indices = 0..p2_len;
+
+    // Where each generator's output goes: the indices into the solution lists, solver and borrow.
+    let mut consumers: Vec<Vec<SharedConsumer>> = vec![Vec::new(); agg_result.generators.len()];
+    let composed = agg_result
+        .p1_composed_solns()
+        .enumerate()
+        .map(|(index, soln)| (0, index, soln))
+        .chain(
+            agg_result
+                .p2_composed_solns()
+                .enumerate()
+                .map(|(index, soln)| (1, index, soln)),
+        );
+    let positions = [&p1_positions, &p2_positions];
+    for (slot, index, (gen, sol, borrow)) in composed {
+        let index = positions[slot][index];
+        let Some(g) = agg_result.generators.iter().position(|g| std::ptr::eq(g, gen)) else {
+            continue;
+        };
+        // A `both` solver shows up in each part's list, but should still only run once.
+        let existing = consumers[g]
+            .iter_mut()
+            .find(|(_, s, _)| sol.problem_part == AocPart::Both && s.source.sig.ident == sol.source.sig.ident);
+        match existing {
+            Some((indices, _, _)) => indices[slot] = Some(index),
+            None => {
+                let mut indices = [None; 2];
+                indices[slot] = Some(index);
+                consumers[g].push((indices, sol, borrow));
+            }
+        }
+    }
+    let variants_of = |indices: &[Option<usize>; 2]| -> Vec<proc_macro2::TokenStream> {
+        let (p1, p2) = (indices[0].into_iter(), indices[1].into_iter());
+        p1.map(|i| quote!((1u8, #i)))
+            .chain(p2.map(|i| quote!((2u8, #i))))
+            .collect()
+    };
+    let mut shared: Vec<_> = agg_result
+        .generators
+        .iter()
+        .zip(consumers.iter())
+        .filter(|(_, consumers)| !consumers.is_empty())
+        .map(|(gen, consumers)| {
+            let (ident, body) = gen_shared_runner(day, gen, consumers);
+            let label = gen.display_slug.to_string();
+            let variants: Vec<_> = consumers
+                .iter()
+                .flat_map(|(indices, _, _)| variants_of(indices))
+                .collect();
+            (ident, body, label, variants)
+        })
+        .collect();
+    // `#[solution]`s come after the composed solutions in each list.
+    let p1_composed = agg_result.p1_composed_solns().count();
+    let p2_composed = agg_result.p2_composed_solns().count();
+    for (p1, sln) in agg_result.p1_user_solns().enumerate() {
+        let p2 = agg_result
+            .p2_user_solns()
+            .position(|other| other.source.sig.ident == sln.source.sig.ident);
+        if let (AocPart::Both, Some(p2)) = (sln.problem_part, p2) {
+            let (p1, p2) = (p1_positions[p1_composed + p1], p2_positions[p2_composed + p2]);
+            let indices = [Some(p1), Some(p2)];
+            let (ident, body) = gen_shared_solution_runner(day, sln, p1, p2);
+            shared.push((ident, body, sln.display_slug.to_string(), variants_of(&indices)));
+        }
+    }
+    let shared_idents: Vec<_> = shared.iter().map(|(ident, ..)| ident).collect();
+    let shared_bodies: Vec<_> = shared.iter().map(|(_, body, ..)| body).collect();
+    let shared_labels: Vec<_> = shared.iter().map(|(_, _, label, ..)| label).collect();
+    let shared_variants: Vec<_> = shared
+        .iter()
+        .map(|(_, _, _, variants)| quote!(&[ #(#variants),* ]))
+        .collect();
+    let shared_len = shared.len();
 
     quote! {
         mod _gen_lists {
             use super::#mod_name::*;
             use std::fmt::Display;
 
+            pub use super::#mod_name::_aoc_params as params;
+
             pub const P1_LABELS: [&str; #p1_len] = [ #(#p1_labels),* ];
             pub const P2_LABELS: [&str; #p2_len] = [ #(#p2_labels),* ];
-
-            #(pub fn #p1_fn_idents(input: &str) -> #p1_ret { #p1_solver_idents(#p1_gen_idents(input)) })*
-            #(pub fn #p2_fn_idents(input: &str) -> #p2_ret { #p2_solver_idents(#p2_gen_idents(input)) })*
-            pub const P1_SOLUTIONS: [for<'r> fn(&'r str) -> #p1_ret; #p1_len] = [ #(#p1_impls),* ];
-            pub const P2_SOLUTIONS: [for<'r> fn(&'r str) -> #p2_ret; #p2_len] = [ #(#p2_impls),* ];
+            pub const P1_TAGS: [aoc_runner::harness::VariantTags; #p1_len] = [ #(#p1_tags),* ];
+            pub const P2_TAGS: [aoc_runner::harness::VariantTags; #p2_len] = [ #(#p2_tags),* ];
+
+            #(pub fn #p1_fn_idents(input: &str) -> ::core::result::Result<#p1_ret, aoc_runner::harness::SolveError> {
+                #p1_bodies
+            })*
+            #(pub fn #p2_fn_idents(input: &str) -> ::core::result::Result<#p2_ret, aoc_runner::harness::SolveError> {
+                #p2_bodies
+            })*
+            pub const P1_SOLUTIONS: [for<'r> fn(&'r str) -> ::core::result::Result<#p1_ret, aoc_runner::harness::SolveError>; #p1_len] = [ #(#p1_fn_idents),* ];
+            pub const P2_SOLUTIONS: [for<'r> fn(&'r str) -> ::core::result::Result<#p2_ret, aoc_runner::harness::SolveError>; #p2_len] = [ #(#p2_fn_idents),* ];
+
+            #(fn #shared_idents(input: &str, run: &mut aoc_runner::harness::SharedRun) {
+                #shared_bodies
+            })*
+            pub const SHARED: [aoc_runner::harness::SharedGenerator; #shared_len] = [
+                #(aoc_runner::harness::SharedGenerator {
+                    label: #shared_labels,
+                    variants: #shared_variants,
+                    run: #shared_idents,
+                }),*
+            ];
+
+            pub const SOLUTIONS: aoc_runner::harness::DaySolutions<#p1_ret, #p2_ret> = aoc_runner::harness::DaySolutions {
+                p1_labels: &P1_LABELS,
+                p1_tags: &P1_TAGS,
+                p1_solutions: &P1_SOLUTIONS,
+                p2_labels: &P2_LABELS,
+                p2_tags: &P2_TAGS,
+                p2_solutions: &P2_SOLUTIONS,
+                shared: &SHARED,
+            };
+
+            #[allow(dead_code)]
+            fn erased_p1<const I: usize>(input: &str) -> ::core::result::Result<String, aoc_runner::harness::SolveError> {
+                P1_SOLUTIONS[I](input).map(|answer| answer.to_string())
+            }
+            #[allow(dead_code)]
+            fn erased_p2<const I: usize>(input: &str) -> ::core::result::Result<String, aoc_runner::harness::SolveError> {
+                P2_SOLUTIONS[I](input).map(|answer| answer.to_string())
+            }
+            pub const P1_ERASED: [aoc_runner::registry::ErasedSolution; #p1_len] = [ #(erased_p1::<#p1_indices>),* ];
+            pub const P2_ERASED: [aoc_runner::registry::ErasedSolution; #p2_len] = [ #(erased_p2::<#p2_indices>),* ];
         }
     }
 }
 
 fn gen_main(year_num: u32, day_num: u32) -> proc_macro2::TokenStream {
-    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
-    let meta_res = MetadataCommand::new()
-        .current_dir(manifest_dir)
-        .exec()
-        .context("Could not use cargo metadata to find inputs directory");
-    if meta_res.is_err() {
-        let err_str = meta_res.err().unwrap().to_string();
-        return quote! { compile_error!(#err_str) };
-    }
-    let meta = meta_res.unwrap();
-    let mut input_path = meta.workspace_root;
-    input_path.push("input");
-    input_path.push(year_num.to_string());
-    input_path.push(format!("{day_num}.txt"));
-
-    let input_file = input_path.as_str();
+    let input_path = workspace::input_dir()
+        .join(year_num.to_string())
+        .join(format!("{day_num}.txt"));
+    let input_file = input_path.to_string_lossy();
 
     let input_blob = if input_path.exists() {
         quote! { include_str!(#input_file) }
@@ -223,57 +818,54 @@ fn gen_main(year_num: u32, day_num: u32) -> proc_macro2::TokenStream {
         quote! { "" }
     };
 
-    quote! {
-        const AOC_RAW_INPUT: &str = #input_blob;
-
-        #[cfg(not(test))]
-        fn main() {
-            println!("## AOC {}, Day {} ----------", #year_num, #day_num);
-            if AOC_RAW_INPUT.len() == 0 {
-                println!("No input found.");
-                return;
-            }
-            let p1len = _gen_lists::P1_SOLUTIONS.len();
-            let p2len = _gen_lists::P2_SOLUTIONS.len();
-            if p1len > 0 {
-                let solution_p1 = _gen_lists::P1_SOLUTIONS[0](AOC_RAW_INPUT);
-                let label = _gen_lists::P1_LABELS[0];
-                println!("Part 1, {} Solution: {}", label, solution_p1);
-                if p1len > 1 {
-                    println!("Checking alternative Part 1 solutions...");
-                    for (idx, solver) in _gen_lists::P1_SOLUTIONS.iter().enumerate().skip(1) {
-                        let solution = solver(AOC_RAW_INPUT);
-                        if solution == solution_p1 {
-                            print!("✅");
-                        } else {
-                            println!("\nSolver {} found {}", _gen_lists::P1_LABELS[idx], solution);
-                        }
-                    }
-                    println!("\n");
-                }
-            }
-            if p2len > 0 {
-                let solution_p2 = _gen_lists::P2_SOLUTIONS[0](AOC_RAW_INPUT);
-                let label = _gen_lists::P2_LABELS[0];
-                println!("Part 2, {} Solution: {}", label, solution_p2);
-                if p2len > 1 {
-                    println!("Checking alternative Part 2 solutions...");
-                    for (idx, solver) in _gen_lists::P2_SOLUTIONS.iter().enumerate().skip(1) {
-                        let solution = solver(AOC_RAW_INPUT);
-                        if solution == solution_p2 {
-                            print!("✅");
-                        } else {
-                            println!("\nSolver {} found {}", _gen_lists::P2_LABELS[idx], solution);
-                        }
-                    }
-                    println!("\n");
+    // A day compiled as its own binary runs from `main`. Library days are run through `AOC_DAY`
+    // by the crate's dispatcher instead, where a `main` would be dead code.
+    let main = std::env::var_os("CARGO_BIN_NAME").map(|_| {
+        quote! {
+            #[cfg(not(test))]
+            fn main() {
+                if let Err(failure) = aoc_runner::harness::run_day(#year_num, #day_num, AOC_RAW_INPUT, &_gen_lists::SOLUTIONS) {
+                    failure.exit();
                 }
+
+                // Benchmarking disabled
+                // println!(" ---- Quick Benches ----- ");
+                // bench_quick::run_benches();
             }
+        }
+    });
 
-            // Benchmarking disabled
-            // println!(" ---- Quick Benches ----- ");
-            // bench_quick::run_benches();
+    let input_dir_var = workspace::INPUT_DIR_VAR;
+
+    quote! {
+        const AOC_RAW_INPUT: &str = #input_blob;
+        // Cargo can't see the macro read this, but tracks it here, and rebuilds the day when it changes.
+        const _: Option<&str> = option_env!(#input_dir_var);
+
+        fn aoc_run(
+            input: &str,
+            options: &aoc_runner::harness::RunOptions,
+        ) -> Result<(), aoc_runner::harness::DayFailure> {
+            aoc_runner::harness::run_day_with(#year_num, #day_num, input, &_gen_lists::SOLUTIONS, options)
         }
+
+        /// This day's entry in the crate's `DAYS`.
+        #[allow(dead_code)]
+        pub const AOC_DAY: aoc_runner::registry::DayEntry = aoc_runner::registry::DayEntry {
+            year: #year_num,
+            day: #day_num,
+            input: AOC_RAW_INPUT,
+            p1_labels: &_gen_lists::P1_LABELS,
+            p1_tags: &_gen_lists::P1_TAGS,
+            p1_solutions: &_gen_lists::P1_ERASED,
+            p2_labels: &_gen_lists::P2_LABELS,
+            p2_tags: &_gen_lists::P2_TAGS,
+            p2_solutions: &_gen_lists::P2_ERASED,
+            run: aoc_run,
+            bench_cases: Some(aoc_bench_cases),
+        };
+
+        #main
     }
 }
 
@@ -300,39 +892,108 @@ fn gen_quick_microbench() -> proc_macro2::TokenStream {
     }
 }
 
-#[allow(dead_code)]
-fn gen_slow_microbench() -> proc_macro2::TokenStream {
+/// Lists the day's solutions for the `cargo bench` targets built with `aoc_runner::bench`.
+fn gen_bench_cases(day_num: u32) -> proc_macro2::TokenStream {
     quote! {
-        use pprof::criterion::{PProfProfiler, Output};
-        use pprof::flamegraph::Options as FGOptions;
-        use criterion::{Criterion, criterion_group, criterion_main, black_box};
-
-        fn bench(c: &mut Criterion) {
-            let mut group1 = c.benchmark_group("Part 1");
-            for (idx, solver_fn) in _gen_lists::P1_SOLUTIONS.iter().enumerate() {
-                let label = _gen_lists::P1_LABELS[idx];
-                group1.bench_function(label, |b| b.iter(|| solver_fn(black_box(AOC_RAW_INPUT))));
+        pub fn aoc_bench_cases() -> Vec<aoc_runner::bench::BenchCase> {
+            aoc_runner::bench::day_cases(#day_num, AOC_RAW_INPUT, &_gen_lists::SOLUTIONS)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(attr: &str, item: &str) -> syn::Result<String> {
+        let args: AocCaseArgs = syn::parse_str(attr)?;
+        let case: CaseConst = syn::parse_str(item)?;
+        gen_case(&args, &case).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn days_tell_cargo_they_read_the_input_dir() {
+        let expanded = gen_main(2023, 1).to_string();
+        let tracked = quote! { const _: Option<&str> = option_env!("AOC_INPUT_DIR"); }.to_string();
+        assert!(expanded.contains(&tracked), "{expanded}");
+    }
+
+    #[test]
+    fn params_get_a_setter_taking_their_type() {
+        let module: ItemMod = syn::parse_quote! {
+            mod solutions {
+                #[generator(gen)]
+                fn parse(input: &str) -> Grid { todo!() }
+                #[solver(part1, main, params(steps = 64))]
+                fn solve(data: &Grid, steps: u32) -> u32 { todo!() }
             }
-            group1.finish();
-            let mut group2 = c.benchmark_group("Part 2");
-            for (idx, solver_fn) in _gen_lists::P2_SOLUTIONS.iter().enumerate() {
-                let label = _gen_lists::P2_LABELS[idx];
-                group2.bench_function(label, |b| b.iter(|| solver_fn(black_box(AOC_RAW_INPUT))));
+        };
+        let setters = gen_param_setters(&discover_mod_contents(&module).unwrap()).to_string();
+        let setter = quote! {
+            pub fn steps(value: u32) -> aoc_runner::params::Override {
+                aoc_runner::params::Override::new("steps", value)
             }
-            group2.finish();
-        }
+        };
+        assert!(setters.contains(&setter.to_string()), "{setters}");
+    }
+
+    #[test]
+    fn case_params_are_set_through_the_days_setters() {
+        let expanded = case("part1 = 16, params(steps = 6)", r#"const EXAMPLE: &str = "";"#).unwrap();
+        let overrides = quote! { vec![super::_gen_lists::params::steps(6)] }.to_string();
+        assert!(expanded.contains(&format!("aoc_runner :: params :: with_overrides ({overrides} ,")), "{expanded}");
+    }
+
+    #[test]
+    fn primary_variants_are_listed_first() {
+        assert_eq!(variant_order(4, None), [0, 1, 2, 3]);
+        assert_eq!(variant_order(4, Some(0)), [0, 1, 2, 3]);
+        assert_eq!(variant_order(4, Some(2)), [2, 0, 1, 3]);
+        assert_eq!(
+            reorder(&["main", "columns", "fast"], &variant_order(3, Some(2))),
+            ["fast", "main", "columns"]
+        );
+    }
 
-        criterion_group! {
-            name = benches;
-            config = Criterion::default()
-                .with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)))
-                .with_output_color(true)
-                .with_plots();
-            targets = bench
+    #[test]
+    fn positions_undo_the_order() {
+        let order = variant_order(4, Some(2));
+        let positions = positions(&order);
+        assert_eq!(positions, [1, 2, 0, 3]);
+        for (index, &position) in positions.iter().enumerate() {
+            assert_eq!(order[position], index);
         }
+    }
+
+    #[test]
+    fn case_files_are_included_from_the_workspace_root() {
+        let expanded = case(r#"19114, file = "examples/2023/19/1.txt""#, "const EXAMPLE: &str;").unwrap();
+        let path = workspace::root().join("examples/2023/19/1.txt");
+        let path = path.to_string_lossy();
+        let include = quote! { const EXAMPLE: &str = include_str!(#path); }.to_string();
+        assert!(expanded.starts_with(&include), "{expanded}");
+        assert!(expanded.contains("fn aoc_test_example"), "{expanded}");
+    }
+
+    #[test]
+    fn missing_case_files_are_an_error() {
+        let error = case(r#"1, file = "examples/2023/19/missing.txt""#, "const EXAMPLE: &str;").unwrap_err();
+        assert!(error.to_string().starts_with("Example file "), "{error}");
+        assert!(error.to_string().ends_with("missing.txt doesn't exist"), "{error}");
+    }
 
-        // We need this call to happen only when benchmarking. This is the closest we can get.
-        #[cfg(test)]
-        criterion_main!(benches);
+    #[test]
+    fn case_inputs_come_from_the_const_or_a_file() {
+        let both = case(r#"1, file = "examples/2023/19/1.txt""#, r#"const EXAMPLE: &str = "";"#);
+        assert_eq!(
+            both.unwrap_err().to_string(),
+            "The input comes from `file`, so the const can't have a value"
+        );
+        let neither = case("1", "const EXAMPLE: &str;");
+        assert_eq!(
+            neither.unwrap_err().to_string(),
+            "Give the const a value, or `file = \"..\"` to read one"
+        );
+        assert!(case("1", r#"const EXAMPLE: &str = "1";"#).is_ok());
     }
 }
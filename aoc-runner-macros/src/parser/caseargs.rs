@@ -1,29 +1,288 @@
-use syn::{parse::Parse, token::Comma, Expr, Token};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{parse::Parse, token::Comma, Attribute, Expr, Ident, LitStr, Token, Type, Visibility};
 
+use super::paramargs::{parse_params, peek_params, ParamArg};
+
+/// What a test case expects from every solution of one part.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Expected {
+    Answer(Expr),
+    /// `Err(_)` for any error, or `Err("text")` for an error whose message contains the text.
+    Error(Option<Expr>),
+}
+
+impl From<Expr> for Expected {
+    fn from(expr: Expr) -> Self {
+        let Expr::Call(call) = &expr else {
+            return Expected::Answer(expr);
+        };
+        let is_err = matches!(call.func.as_ref(), Expr::Path(p) if p.path.is_ident("Err"));
+        match (is_err, call.args.first()) {
+            (true, Some(Expr::Infer(_))) if call.args.len() == 1 => Expected::Error(None),
+            (true, Some(message)) if call.args.len() == 1 => Expected::Error(Some(message.clone())),
+            _ => Expected::Answer(expr),
+        }
+    }
+}
+
+/// `(p1)`, `(p1, p2)`, or named arguments such as `(part2 = 5, variant = "main", ignore = "slow")`
+/// and `params(steps = 6)`. `file = "examples/.."` reads the input from a file instead of the const.
+/// Positional answers may be followed by named arguments, but not the other way around.
 #[derive(Debug, PartialEq, Eq)]
 pub struct AocCaseArgs {
-    pub expected_p1: Expr,
-    pub expected_p2: Option<Expr>,
+    pub expected_p1: Option<Expected>,
+    pub expected_p2: Option<Expected>,
+    /// Only check the solutions whose label, or whose generator or solver slug, is this.
+    pub variant: Option<LitStr>,
+    /// Mark the test `#[ignore]` with this reason.
+    pub ignore: Option<LitStr>,
+    /// Puzzle parameters to override for this input.
+    pub params: Option<Vec<ParamArg>>,
+    /// The input's path, relative to the workspace root.
+    pub file: Option<LitStr>,
+}
+
+const POSITIONAL_ERROR: &str = "Expected: a single expression for just testing Part 1, or two expressions as two arguments if testing Part 1 and Part 2.";
+const NAMED_ERROR: &str =
+    "Expected one of `part1 = ..`, `part2 = ..`, `variant = \"..\"`, `ignore = \"..\"`, `file = \"..\"` or `params(name = ..)`";
+
+pub fn is_named(input: syn::parse::ParseStream) -> bool {
+    input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![==])
+}
+
+pub fn set_once<T>(slot: &mut Option<T>, name: &Ident, value: T) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new(
+            name.span(),
+            format!("`{name}` is given more than once"),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
 }
 
 impl Parse for AocCaseArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let p1: Expr = input.parse()?;
-        let lookahead = input.lookahead1();
-        if lookahead.peek(Token![,]) {
-            let _: Comma = input.parse()?;
-            let p2: Expr = input.parse()?;
-            Ok(AocCaseArgs {
-                expected_p1: p1,
-                expected_p2: Some(p2),
-            })
-        } else if !input.is_empty() {
-            Err(input.error("Expected: a single expression for just testing Part 1, or two expressions as two arguments if testing Part 1 and Part 2."))
-        } else {
-            Ok(AocCaseArgs {
-                expected_p1: p1,
-                expected_p2: None,
-            })
+        let mut args = AocCaseArgs {
+            expected_p1: None,
+            expected_p2: None,
+            variant: None,
+            ignore: None,
+            params: None,
+            file: None,
+        };
+
+        let mut positional = 0;
+        while !input.is_empty() && !is_named(input) && !peek_params(input) {
+            let slot = match positional {
+                0 => &mut args.expected_p1,
+                1 => &mut args.expected_p2,
+                _ => return Err(input.error(POSITIONAL_ERROR)),
+            };
+            *slot = Some(input.parse::<Expr>()?.into());
+            positional += 1;
+            if input.is_empty() {
+                break;
+            }
+            let _: Comma = input.parse().map_err(|_| input.error(POSITIONAL_ERROR))?;
+        }
+
+        while !input.is_empty() {
+            if peek_params(input) {
+                let name: Ident = input.fork().parse()?;
+                set_once(&mut args.params, &name, parse_params(input)?)?;
+                if !input.is_empty() {
+                    let _: Comma = input.parse()?;
+                }
+                continue;
+            }
+            if !is_named(input) {
+                return Err(input.error(NAMED_ERROR));
+            }
+            let name: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            match name.to_string().as_str() {
+                "part1" => set_once(&mut args.expected_p1, &name, input.parse::<Expr>()?.into())?,
+                "part2" => set_once(&mut args.expected_p2, &name, input.parse::<Expr>()?.into())?,
+                "variant" => set_once(&mut args.variant, &name, input.parse()?)?,
+                "ignore" => set_once(&mut args.ignore, &name, input.parse()?)?,
+                "file" => set_once(&mut args.file, &name, input.parse()?)?,
+                _ => return Err(syn::Error::new(name.span(), NAMED_ERROR)),
+            }
+            if !input.is_empty() {
+                let _: Comma = input.parse()?;
+            }
+        }
+
+        if args.expected_p1.is_none() && args.expected_p2.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Expected an answer for at least one part, eg. `part1 = 42`",
+            ));
         }
+        Ok(args)
+    }
+}
+
+impl AocCaseArgs {
+    /// Parses an example's expected-answers file: the same arguments as `#[aoc_case]`, one per
+    /// line, with blank lines and `#` comments skipped.
+    pub fn parse_answers(text: &str) -> syn::Result<AocCaseArgs> {
+        let args: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.trim_end_matches(','))
+            .collect();
+        syn::parse_str(&args.join(", "))
+    }
+}
+
+/// The const an `#[aoc_case]` is on. With `file = ".."` it has no value of its own, as in
+/// `const EXAMPLE: &str;`, which `syn::ItemConst` doesn't accept.
+pub struct CaseConst {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    pub ident: Ident,
+    ty: Type,
+    pub value: Option<Expr>,
+}
+
+impl Parse for CaseConst {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        input.parse::<Token![const]>()?;
+        let ident: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty: Type = input.parse()?;
+        let value = match input.parse::<Option<Token![=]>>()? {
+            Some(_) => Some(input.parse()?),
+            None => None,
+        };
+        input.parse::<Token![;]>()?;
+        Ok(CaseConst {
+            attrs,
+            vis,
+            ident,
+            ty,
+            value,
+        })
+    }
+}
+
+impl CaseConst {
+    /// The const, given `value` in place of its own.
+    pub fn with_value(&self, value: TokenStream) -> TokenStream {
+        let CaseConst {
+            attrs, vis, ident, ty, ..
+        } = self;
+        quote!(#(#attrs)* #vis const #ident: #ty = #value;)
+    }
+}
+
+impl ToTokens for CaseConst {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match &self.value {
+            Some(value) => tokens.extend(self.with_value(value.to_token_stream())),
+            None => tokens.extend(self.with_value(quote!(""))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(text: &str) -> Expected {
+        syn::parse_str::<Expr>(text).unwrap().into()
+    }
+
+    fn expr(text: &str) -> Expr {
+        syn::parse_str(text).unwrap()
+    }
+
+    fn args(text: &str) -> syn::Result<AocCaseArgs> {
+        syn::parse_str(text)
+    }
+
+    #[test]
+    fn answers_are_positional_or_named() {
+        let both = args("136, 64").unwrap();
+        assert_eq!(both.expected_p1, Some(expected("136")));
+        assert_eq!(both.expected_p2, Some(expected("64")));
+
+        let second = args("part2 = 64").unwrap();
+        assert_eq!((second.expected_p1, second.expected_p2), (None, Some(expected("64"))));
+
+        let mixed = args(r#"136, part2 = 64, variant = "columns", ignore = "slow", file = "examples/1.txt""#).unwrap();
+        assert_eq!(mixed.expected_p1, Some(expected("136")));
+        assert_eq!(mixed.expected_p2, Some(expected("64")));
+        assert_eq!(mixed.variant.unwrap().value(), "columns");
+        assert_eq!(mixed.ignore.unwrap().value(), "slow");
+        assert_eq!(mixed.file.unwrap().value(), "examples/1.txt");
+    }
+
+    #[test]
+    fn params_can_go_anywhere_after_the_answers() {
+        let before = args("part2 = 1030, params(expansion = 10)").unwrap();
+        let after = args("params(expansion = 10), part2 = 1030").unwrap();
+        assert_eq!(before, after);
+        assert_eq!(before.params.unwrap().len(), 1);
+        assert_eq!(args("1030, params(expansion = 10)").unwrap().params.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn comparisons_are_answers_not_names() {
+        let args = args("a == b").unwrap();
+        assert_eq!(args.expected_p1, Some(expected("a == b")));
+    }
+
+    #[test]
+    fn arguments_can_only_be_given_once() {
+        let message = |text| args(text).unwrap_err().to_string();
+        assert_eq!(message("1, part1 = 2"), "`part1` is given more than once");
+        assert_eq!(message("part2 = 1, part2 = 2"), "`part2` is given more than once");
+        assert_eq!(message(r#"1, variant = "a", variant = "b""#), "`variant` is given more than once");
+        assert_eq!(message("1, params(a = 1), params(b = 2)"), "`params` is given more than once");
+    }
+
+    #[test]
+    fn malformed_arguments_are_errors() {
+        let message = |text| args(text).unwrap_err().to_string();
+        assert_eq!(message("1, part3 = 2"), NAMED_ERROR);
+        assert_eq!(message("1, 2, 3"), POSITIONAL_ERROR);
+        assert_eq!(message("part1 = 1, 2"), NAMED_ERROR);
+        assert_eq!(message(r#"variant = "main""#), "Expected an answer for at least one part, eg. `part1 = 42`");
+        assert_eq!(message(""), "Expected an answer for at least one part, eg. `part1 = 42`");
+    }
+
+    #[test]
+    fn answers_files_read_one_argument_per_line() {
+        let from_file = AocCaseArgs::parse_answers("# The first example\npart1 = 32000000,\n\npart2 = 5\n").unwrap();
+        assert_eq!(from_file, args("part1 = 32000000, part2 = 5").unwrap());
+    }
+
+    #[test]
+    fn err_calls_expect_an_error() {
+        assert_eq!(expected("Err(_)"), Expected::Error(None));
+        assert_eq!(expected(r#"Err("no path")"#), Expected::Error(Some(expr(r#""no path""#))));
+        assert_eq!(expected("Err(MESSAGE)"), Expected::Error(Some(expr("MESSAGE"))));
+    }
+
+    #[test]
+    fn other_expressions_are_answers() {
+        assert_eq!(expected("42"), Expected::Answer(expr("42")));
+        assert_eq!(expected("Ok(42)"), Expected::Answer(expr("Ok(42)")));
+        assert_eq!(expected("Err(1, 2)"), Expected::Answer(expr("Err(1, 2)")));
+        assert_eq!(expected("Some(Err(_))"), Expected::Answer(expr("Some(Err(_))")));
+    }
+
+    #[test]
+    fn either_part_can_expect_an_error() {
+        let args: AocCaseArgs = syn::parse_str(r#"part1 = Err("bad input"), part2 = Err(_)"#).unwrap();
+        assert_eq!(args.expected_p1, Some(Expected::Error(Some(expr(r#""bad input""#)))));
+        assert_eq!(args.expected_p2, Some(Expected::Error(None)));
     }
 }
@@ -1,13 +1,23 @@
 use proc_macro2::Ident;
 use syn::parse::Parse;
 
+use super::paramargs::ParamArg;
+use super::tagargs::{parse_trailing_options, VariantTags};
+
 pub struct AocGeneratorArgs {
     pub display_slug: Ident,
+    pub params: Vec<ParamArg>,
+    pub tags: VariantTags,
 }
 
 impl Parse for AocGeneratorArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let slug = input.parse::<Ident>()?;
-        Ok(AocGeneratorArgs { display_slug: slug })
+        let (params, tags) = parse_trailing_options(input)?;
+        Ok(AocGeneratorArgs {
+            display_slug: slug,
+            params,
+            tags,
+        })
     }
 }
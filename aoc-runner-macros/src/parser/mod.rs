@@ -1,5 +1,8 @@
 pub mod caseargs;
 pub mod genargs;
 pub mod macroargs;
+pub mod paramargs;
+pub mod propertyargs;
 pub mod solutionargs;
 pub mod solverargs;
+pub mod tagargs;
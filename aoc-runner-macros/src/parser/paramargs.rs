@@ -0,0 +1,35 @@
+use proc_macro2::Ident;
+use syn::{parenthesized, parse::ParseStream, punctuated::Punctuated, Expr, Token};
+
+/// One `name = value` inside `params(..)`: a declared default, or a test case's override.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamArg {
+    pub name: Ident,
+    pub value: Expr,
+}
+
+impl syn::parse::Parse for ParamArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value: Expr = input.parse()?;
+        Ok(ParamArg { name, value })
+    }
+}
+
+/// Whether `params(..)` comes next.
+pub fn peek_params(input: ParseStream) -> bool {
+    input.fork().parse::<Ident>().is_ok_and(|ident| ident == "params") && input.peek2(syn::token::Paren)
+}
+
+/// Parses `params(name = value, ..)`.
+pub fn parse_params(input: ParseStream) -> syn::Result<Vec<ParamArg>> {
+    let keyword: Ident = input.parse()?;
+    if keyword != "params" {
+        return Err(syn::Error::new(keyword.span(), "Expected `params(name = value, ..)`"));
+    }
+    let content;
+    parenthesized!(content in input);
+    let args = Punctuated::<ParamArg, Token![,]>::parse_terminated(&content)?;
+    Ok(args.into_iter().collect())
+}
@@ -0,0 +1,55 @@
+use proc_macro2::Ident;
+use syn::{parse::Parse, token::Comma, LitInt, Token};
+
+use super::caseargs::{is_named, set_once};
+
+const DEFAULT_CASES: u32 = 100;
+const DEFAULT_MAX_SIZE: usize = 10;
+
+/// `()` or any of `cases = 500`, `max_size = 20` and `part = 1`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AocPropertyArgs {
+    /// How many generated inputs to try.
+    pub cases: u32,
+    /// The size the last input is generated with, growing from 1.
+    pub max_size: usize,
+    /// Only compare this part's variants.
+    pub part: Option<u8>,
+}
+
+const NAMED_ERROR: &str = "Expected one of `cases = ..`, `max_size = ..` or `part = 1` / `part = 2`";
+
+impl Parse for AocPropertyArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut cases = None;
+        let mut max_size = None;
+        let mut part = None;
+
+        while !input.is_empty() {
+            if !is_named(input) {
+                return Err(input.error(NAMED_ERROR));
+            }
+            let name: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            let value: LitInt = input.parse()?;
+            match name.to_string().as_str() {
+                "cases" => set_once(&mut cases, &name, value.base10_parse()?)?,
+                "max_size" => set_once(&mut max_size, &name, value.base10_parse()?)?,
+                "part" => match value.base10_parse()? {
+                    p @ (1 | 2) => set_once(&mut part, &name, p)?,
+                    _ => return Err(syn::Error::new(value.span(), "`part` must be 1 or 2")),
+                },
+                _ => return Err(syn::Error::new(name.span(), NAMED_ERROR)),
+            }
+            if !input.is_empty() {
+                let _: Comma = input.parse()?;
+            }
+        }
+
+        Ok(AocPropertyArgs {
+            cases: cases.unwrap_or(DEFAULT_CASES),
+            max_size: max_size.unwrap_or(DEFAULT_MAX_SIZE),
+            part,
+        })
+    }
+}
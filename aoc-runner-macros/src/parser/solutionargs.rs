@@ -1,12 +1,16 @@
 use proc_macro2::Ident;
 use syn::{parse::Parse, token::Comma};
 
+use super::paramargs::ParamArg;
+use super::tagargs::{parse_trailing_options, VariantTags};
 use crate::partflag::AocPart;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct AocSolutionArgs {
     pub problem_part: AocPart,
     pub display_slug: Ident,
+    pub params: Vec<ParamArg>,
+    pub tags: VariantTags,
 }
 
 impl Parse for AocSolutionArgs {
@@ -14,9 +18,12 @@ impl Parse for AocSolutionArgs {
         let part: AocPart = input.parse()?;
         input.parse::<Comma>()?;
         let slug: Ident = input.parse()?;
+        let (params, tags) = parse_trailing_options(input)?;
         Ok(AocSolutionArgs {
             problem_part: part,
             display_slug: slug,
+            params,
+            tags,
         })
     }
 }
@@ -0,0 +1,52 @@
+use proc_macro2::Ident;
+use syn::{parse::ParseStream, Token};
+
+use super::caseargs::set_once;
+use super::paramargs::{parse_params, peek_params, ParamArg};
+
+const TAG_ERROR: &str = "Expected `params(name = value, ..)` or a tag: `slow`, `experimental` or `primary`";
+
+/// Tags after a generator, solver or solution's slug, eg. `#[solver(part2, brute, slow)]`. A
+/// generator's tags apply to every variant it feeds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VariantTags {
+    /// Skipped in runs and tests unless slow variants are asked for, or it's picked by name.
+    pub slow: bool,
+    /// Left out of `aoc verify`.
+    pub experimental: bool,
+    /// The part's primary variant, whose answer the others are checked against. On a generator,
+    /// picks which one feeds the primary solver.
+    pub primary: bool,
+}
+
+/// Parses the `, params(..)` and tags, in any order, that may end a generator, solver or solution
+/// attribute.
+pub fn parse_trailing_options(input: ParseStream) -> syn::Result<(Vec<ParamArg>, VariantTags)> {
+    let mut params = None;
+    let mut tags = VariantTags::default();
+    while !input.is_empty() {
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+        if peek_params(input) {
+            let keyword: Ident = input.fork().parse()?;
+            set_once(&mut params, &keyword, parse_params(input)?)?;
+            continue;
+        }
+
+        let tag: Ident = input.parse()?;
+        let slot = match tag.to_string().as_str() {
+            "slow" => &mut tags.slow,
+            "experimental" => &mut tags.experimental,
+            "primary" => &mut tags.primary,
+            _ => return Err(syn::Error::new(tag.span(), TAG_ERROR)),
+        };
+        if *slot {
+            return Err(syn::Error::new(tag.span(), format!("`{tag}` is given more than once")));
+        }
+        *slot = true;
+    }
+
+    Ok((params.unwrap_or_default(), tags))
+}
@@ -5,6 +5,19 @@ use syn::parse::Parse;
 pub enum AocPart {
     Part1,
     Part2,
+    /// Both answers at once, returned as a `(part1, part2)` tuple.
+    Both,
+}
+
+impl AocPart {
+    /// Whether this answers part 1 and part 2.
+    pub fn parts(self) -> (bool, bool) {
+        match self {
+            AocPart::Part1 => (true, false),
+            AocPart::Part2 => (false, true),
+            AocPart::Both => (true, true),
+        }
+    }
 }
 
 impl Parse for AocPart {
@@ -19,7 +32,9 @@ impl Parse for AocPart {
             "Part2" => Ok(AocPart::Part2),
             "p2" => Ok(AocPart::Part2),
             "P2" => Ok(AocPart::Part2),
-            _ => Err(input.error("Expected a Part 1 / Part 2 indicator, such as `part1` or `part2`.")),
+            "both" => Ok(AocPart::Both),
+            "Both" => Ok(AocPart::Both),
+            _ => Err(input.error("Expected a Part 1 / Part 2 indicator, such as `part1`, `part2` or `both`.")),
         }
     }
 }
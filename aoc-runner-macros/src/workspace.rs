@@ -0,0 +1,51 @@
+//! Where the workspace's `input/` and `examples/` folders are.
+//!
+//! This is worked out from the filesystem rather than `cargo metadata`, so expanding a day doesn't
+//! spawn cargo, and is remembered for the rest of the compile.
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Overrides where day inputs are read from, eg. through `[env]` in `.cargo/config.toml`. A
+/// relative path is taken from the workspace root. Days that read it also name it in an
+/// `option_env!`, so cargo rebuilds them when it changes.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Roots found so far, by the manifest folder of the crate being compiled. One macro process can
+/// expand several crates, eg. under rust-analyzer.
+static ROOTS: Mutex<Option<HashMap<PathBuf, PathBuf>>> = Mutex::new(None);
+
+/// The folder holding the `Cargo.toml` with a `[workspace]` table, found by walking up from the
+/// crate being compiled. A crate outside any workspace is its own root.
+pub fn root() -> PathBuf {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let mut roots = ROOTS.lock().unwrap_or_else(|e| e.into_inner());
+    roots
+        .get_or_insert_with(HashMap::new)
+        .entry(manifest_dir)
+        .or_insert_with_key(|manifest_dir| find_root(manifest_dir))
+        .clone()
+}
+
+fn find_root(manifest_dir: &Path) -> PathBuf {
+    manifest_dir
+        .ancestors()
+        .find(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.lines().any(|line| line.trim() == "[workspace]"))
+        })
+        .unwrap_or(manifest_dir)
+        .to_path_buf()
+}
+
+/// The folder holding `YEAR/DAY.txt` inputs: `input/` in the workspace root, unless overridden.
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => root().join(dir),
+        None => root().join("input"),
+    }
+}
@@ -0,0 +1,184 @@
+//! Opt-in heap accounting for solutions.
+//!
+//! With the `alloc-stats` feature, `CountingAlloc` becomes the global allocator of any binary
+//! that links this crate, and the harness reports peak and total heap use per stage. `aoc run
+//! --alloc` turns the feature on with `--features aoc-runner/alloc-stats`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+static CURRENT: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+static COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Whether this build counts allocations at all.
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Wraps the system allocator, tracking live, peak and total bytes plus the allocation count.
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    fn grow(size: u64) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        TOTAL.fetch_add(size, Ordering::Relaxed);
+        COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn shrink(size: u64) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size() as u64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size() as u64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size() as u64);
+    }
+
+    // Counted as freeing the old block and allocating the new one.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::shrink(layout.size() as u64);
+            Self::grow(new_size as u64);
+        }
+        new_ptr
+    }
+}
+
+/// Heap use over one measured call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocStats {
+    /// Highest live heap size during the call, above what was live when it started.
+    pub peak_bytes: u64,
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+/// Runs `f`, returning heap statistics for it if allocations are being counted.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+    let (result, stats) = counted(f);
+    (result, Some(stats))
+}
+
+/// The counting behind `measure`, on whatever the counters see.
+fn counted<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let start_current = CURRENT.load(Ordering::Relaxed);
+    let start_total = TOTAL.load(Ordering::Relaxed);
+    let start_count = COUNT.load(Ordering::Relaxed);
+    // Measurements nest (a solution's stages inside the solution), so the enclosing peak is
+    // restored afterwards rather than lost.
+    let outer_peak = PEAK.swap(start_current, Ordering::Relaxed);
+
+    let result = f();
+
+    let peak = PEAK.fetch_max(outer_peak, Ordering::Relaxed);
+    let stats = AllocStats {
+        peak_bytes: peak.saturating_sub(start_current),
+        total_bytes: TOTAL.load(Ordering::Relaxed) - start_total,
+        allocations: COUNT.load(Ordering::Relaxed) - start_count,
+    };
+    (result, stats)
+}
+
+/// Formats a byte count with a binary unit, eg. `1.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // Moves up a unit before the value would round to `1024.0`.
+    while value >= 1023.95 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Drives the counters by hand, which only these tests touch unless the feature installs the
+    // counting allocator for the whole test binary.
+    #[cfg(not(feature = "alloc-stats"))]
+    #[test]
+    fn nested_measurements_each_report_their_own_peak() {
+        let (inner, outer) = counted(|| {
+            CountingAlloc::grow(100);
+            let (_, inner) = counted(|| {
+                CountingAlloc::grow(300);
+                CountingAlloc::shrink(300);
+            });
+            CountingAlloc::grow(50);
+            CountingAlloc::shrink(150);
+            inner
+        });
+        assert_eq!(inner, AllocStats { peak_bytes: 300, total_bytes: 300, allocations: 1 });
+        assert_eq!(outer, AllocStats { peak_bytes: 400, total_bytes: 450, allocations: 3 });
+
+        let (inner, outer) = counted(|| {
+            CountingAlloc::grow(500);
+            CountingAlloc::shrink(500);
+            counted(|| {
+                CountingAlloc::grow(100);
+                CountingAlloc::shrink(100);
+            })
+            .1
+        });
+        assert_eq!(inner.peak_bytes, 100);
+        assert_eq!(outer.peak_bytes, 500);
+    }
+
+    #[test]
+    fn measure_reports_nothing_without_the_feature() {
+        let (answer, stats) = measure(|| vec![1u8; 64].len());
+        assert_eq!(answer, 64);
+        assert_eq!(stats.is_some(), ENABLED);
+    }
+
+    #[test]
+    fn bytes_are_shown_in_the_largest_whole_unit() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes((1 << 20) - 1), "1.0 MiB");
+        assert_eq!(format_bytes(1 << 20), "1.0 MiB");
+        assert_eq!(format_bytes(5 << 30), "5.0 GiB");
+        assert_eq!(format_bytes(1 << 40), "1.0 TiB");
+        assert_eq!(format_bytes(2048 << 40), "2048.0 TiB");
+    }
+}
@@ -0,0 +1,309 @@
+//! `cargo bench` support for `#[aoc]` days.
+//!
+//! Every `#[aoc]` day has an `aoc_bench_cases()` function listing its composed solutions, which its
+//! `AOC_DAY` entry points to. A year crate's `aoc` bench target hands its `DAYS` to `benches!`,
+//! which times them without any framework, so `cargo bench -p aoc_2023 -- day17` just works.
+//! `criterion_benches!` and `divan_benches!` do the same with those frameworks, and need this
+//! crate's `criterion` or `divan` feature. Year crates turn these on through features of their own
+//! (`bench`, and `bench-divan` in `aoc_2023`) that their other bench targets require, so normal
+//! builds and `cargo test` don't compile criterion or pprof. Cases are named
+//! `dayNN/partN/label`, so the same filter picks one day with any of them, eg.
+//! `cargo bench -p aoc_2023 --features bench --bench aoc-criterion -- day17`.
+
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::{harness::DaySolutions, registry::DayEntry};
+
+#[cfg(feature = "criterion")]
+pub use criterion;
+#[cfg(feature = "divan")]
+pub use divan;
+
+/// One solution of one part, with the day's input, ready to be timed.
+pub struct BenchCase {
+    pub name: String,
+    input: &'static str,
+    run: Box<dyn Fn(&str) + Send + Sync>,
+}
+
+impl BenchCase {
+    /// Runs the solution once on the day's input.
+    pub fn run(&self) {
+        (self.run)(black_box(self.input))
+    }
+}
+
+impl fmt::Display for BenchCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Entry point for the generated `aoc_bench_cases`. Days without an input have nothing to run, and
+/// slow variants are left out unless `AOC_INCLUDE_SLOW` is set.
+pub fn day_cases<R1: 'static, R2: 'static>(
+    day: u32,
+    input: &'static str,
+    solutions: &'static DaySolutions<R1, R2>,
+) -> Vec<BenchCase> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+
+    let case = |part: u8, label: &str, run: Box<dyn Fn(&str) + Send + Sync>| BenchCase {
+        name: format!("day{day:02}/part{part}/{label}"),
+        input,
+        run,
+    };
+    let p1 = solutions
+        .p1_labels
+        .iter()
+        .zip(solutions.p1_tags)
+        .zip(solutions.p1_solutions)
+        .filter(|((_, tags), _)| tags.included())
+        .map(|((label, _), solution)| case(1, label, Box::new(move |input| drop(black_box(solution(input))))));
+    let p2 = solutions
+        .p2_labels
+        .iter()
+        .zip(solutions.p2_tags)
+        .zip(solutions.p2_solutions)
+        .filter(|((_, tags), _)| tags.included())
+        .map(|((label, _), solution)| case(2, label, Box::new(move |input| drop(black_box(solution(input))))));
+
+    p1.chain(p2).collect()
+}
+
+/// Every case of the given days.
+pub fn registry_cases(days: &[DayEntry]) -> Vec<BenchCase> {
+    days.iter()
+        .filter_map(|entry| entry.bench_cases)
+        .flat_map(|cases| cases())
+        .collect()
+}
+
+/// How long `benches!` samples each case for, and the most samples it takes.
+const SAMPLE_TIME: Duration = Duration::from_secs(1);
+const MAX_SAMPLES: usize = 1000;
+
+/// The cases whose names contain any of `filters`, or all of them without any.
+fn filtered(cases: Vec<BenchCase>, filters: &[String]) -> Vec<BenchCase> {
+    cases
+        .into_iter()
+        .filter(|case| filters.is_empty() || filters.iter().any(|filter| case.name.contains(filter.as_str())))
+        .collect()
+}
+
+/// Times each case picked by the bench target's arguments, printing its median. Flags such as the
+/// `--bench` cargo passes are ignored, except `--test` from `cargo test --benches`, which just
+/// runs each case once.
+pub fn run_cases(cases: Vec<BenchCase>, args: impl IntoIterator<Item = String>) {
+    let (flags, filters): (Vec<_>, Vec<_>) = args.into_iter().partition(|arg| arg.starts_with('-'));
+    let cases = filtered(cases, &filters);
+    if cases.is_empty() {
+        println!("No cases to run. Days without an input have none.");
+        return;
+    }
+    if flags.iter().any(|flag| flag == "--test") {
+        for case in cases {
+            case.run();
+            println!("{case}: ok");
+        }
+        return;
+    }
+
+    let width = cases.iter().map(|case| case.name.len()).max().unwrap_or(0);
+    for case in cases {
+        let mut samples = Vec::new();
+        let start = Instant::now();
+        while start.elapsed() < SAMPLE_TIME && samples.len() < MAX_SAMPLES {
+            let sample_start = Instant::now();
+            case.run();
+            samples.push(sample_start.elapsed());
+        }
+        samples.sort_unstable();
+        println!(
+            "{:width$}  {:>10.2?}  (min {:.2?}, max {:.2?}, {} samples)",
+            case.name,
+            samples[samples.len() / 2],
+            samples[0],
+            samples[samples.len() - 1],
+            samples.len()
+        );
+    }
+}
+
+/// The `main` of a bench target (with `harness = false`) timing a year crate's days without a
+/// benchmarking framework, eg. `aoc_runner::benches!(aoc_2023::DAYS);`.
+#[macro_export]
+macro_rules! benches {
+    ($days:path) => {
+        fn main() {
+            $crate::bench::run_cases($crate::bench::registry_cases($days), std::env::args().skip(1));
+        }
+    };
+}
+
+/// Criterion settings for day benchmarks. `--profile-time` writes a flamegraph per case.
+#[cfg(feature = "criterion")]
+pub fn criterion_config() -> criterion::Criterion {
+    let config = criterion::Criterion::default().with_output_color(true);
+    #[cfg(unix)]
+    let config = config.with_profiler(pprof::criterion::PProfProfiler::new(
+        100,
+        pprof::criterion::Output::Flamegraph(None),
+    ));
+
+    config
+}
+
+/// Registers every case with criterion.
+#[cfg(feature = "criterion")]
+pub fn criterion_bench_cases(c: &mut criterion::Criterion, cases: Vec<BenchCase>) {
+    for case in cases {
+        c.bench_function(&case.name, |b| b.iter(|| case.run()));
+    }
+}
+
+/// The `main` of a criterion bench target (with `harness = false`) covering a year crate's days,
+/// eg. `aoc_runner::criterion_benches!(aoc_2023::DAYS);`.
+#[cfg(feature = "criterion")]
+#[macro_export]
+macro_rules! criterion_benches {
+    ($days:path) => {
+        fn aoc_benches(c: &mut $crate::bench::criterion::Criterion) {
+            $crate::bench::criterion_bench_cases(c, $crate::bench::registry_cases($days));
+        }
+
+        $crate::bench::criterion::criterion_group! {
+            name = benches;
+            config = $crate::bench::criterion_config();
+            targets = aoc_benches
+        }
+        $crate::bench::criterion::criterion_main!(benches);
+    };
+}
+
+/// The `main` of a divan bench target (with `harness = false`) covering a year crate's days, eg.
+/// `aoc_runner::divan_benches!(aoc_2023::DAYS);`.
+#[cfg(feature = "divan")]
+#[macro_export]
+macro_rules! divan_benches {
+    ($days:path) => {
+        fn main() {
+            $crate::bench::divan::main();
+        }
+
+        #[$crate::bench::divan::bench(
+            crate = $crate::bench::divan,
+            args = $crate::bench::registry_cases($days),
+        )]
+        fn aoc(case: &$crate::bench::BenchCase) {
+            case.run()
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::harness::{DayFailure, RunOptions, Solution, SolveError, VariantTags, INCLUDE_SLOW_VAR};
+
+    fn answer(input: &str) -> Result<usize, SolveError> {
+        Ok(input.len())
+    }
+
+    const PLAIN: VariantTags = VariantTags { slow: false, experimental: false };
+
+    static SOLUTIONS: DaySolutions<usize, usize> = DaySolutions {
+        p1_labels: &["gen / main", "gen / fast"],
+        p1_tags: &[PLAIN, VariantTags { slow: false, experimental: true }],
+        p1_solutions: &[answer as Solution<usize>, answer],
+        p2_labels: &["brute_force"],
+        p2_tags: &[VariantTags { slow: true, experimental: false }],
+        p2_solutions: &[answer],
+        shared: &[],
+    };
+
+    fn names(cases: &[BenchCase]) -> Vec<&str> {
+        cases.iter().map(|case| case.name.as_str()).collect()
+    }
+
+    fn case(name: &str) -> BenchCase {
+        BenchCase { name: name.to_string(), input: "", run: Box::new(|_| {}) }
+    }
+
+    #[test]
+    fn cases_are_named_by_day_part_and_label_and_skip_slow_variants() {
+        let cases = day_cases(7, "input", &SOLUTIONS);
+        let mut expected = vec!["day07/part1/gen / main", "day07/part1/gen / fast"];
+        if std::env::var_os(INCLUDE_SLOW_VAR).is_some() {
+            expected.push("day07/part2/brute_force");
+        }
+        assert_eq!(names(&cases), expected);
+    }
+
+    #[test]
+    fn days_without_an_input_have_no_cases() {
+        assert!(day_cases(7, "", &SOLUTIONS).is_empty());
+    }
+
+    #[test]
+    fn registry_cases_collects_every_days_cases() {
+        fn run(_: &str, _: &RunOptions) -> Result<(), DayFailure> {
+            Ok(())
+        }
+        fn cases() -> Vec<BenchCase> {
+            day_cases(7, "input", &SOLUTIONS)
+        }
+        let entry = |day, bench_cases| DayEntry {
+            year: 2099,
+            day,
+            input: "input",
+            p1_labels: &[],
+            p1_tags: &[],
+            p1_solutions: &[],
+            p2_labels: &[],
+            p2_tags: &[],
+            p2_solutions: &[],
+            run,
+            bench_cases,
+        };
+        let found = registry_cases(&[entry(7, Some(cases)), entry(8, None), entry(7, Some(cases))]);
+        assert_eq!(found.len(), 2 * cases().len());
+        assert_eq!(found[0].name, "day07/part1/gen / main");
+    }
+
+    #[test]
+    fn filters_pick_cases_by_part_of_their_name() {
+        let cases = || vec![case("day07/part1/main"), case("day07/part2/main"), case("day17/part1/fast")];
+        let filters = |filters: &[&str]| filters.iter().map(|filter| filter.to_string()).collect::<Vec<_>>();
+        assert_eq!(names(&filtered(cases(), &[])).len(), 3);
+        assert_eq!(names(&filtered(cases(), &filters(&["day07"]))), ["day07/part1/main", "day07/part2/main"]);
+        assert_eq!(
+            names(&filtered(cases(), &filters(&["fast", "part2"]))),
+            ["day07/part2/main", "day17/part1/fast"]
+        );
+        assert!(filtered(cases(), &filters(&["day25"])).is_empty());
+    }
+
+    #[test]
+    fn test_mode_runs_each_picked_case_once() {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let counted = |name: &str| BenchCase {
+            name: name.to_string(),
+            input: "",
+            run: Box::new(|_| {
+                RUNS.fetch_add(1, Ordering::Relaxed);
+            }),
+        };
+        let args = ["--test", "day07"].map(String::from);
+        run_cases(vec![counted("day07/part1/main"), counted("day17/part1/main")], args);
+        assert_eq!(RUNS.load(Ordering::Relaxed), 1);
+    }
+}
@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand, Args};
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand, Args, ValueEnum};
+
+use crate::harness::parse_memory;
 
 #[derive(Parser, Clone, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -39,16 +43,231 @@ pub enum Commands {
     Prep,
 
     /// Run a specific day's solution
-    Run,
+    Run {
+        /// Which language's solution to run
+        #[arg(short, long, value_enum, default_value_t = Lang::Rs)]
+        lang: Lang,
+
+        #[command(flatten)]
+        limits: LimitArgs,
+
+        #[command(flatten)]
+        report: ReportArgs,
+
+        /// Parse once per generator and hand the result to both parts' solvers, timing parsing on its own
+        #[arg(long)]
+        shared: bool,
+
+        /// Override a puzzle parameter declared with `params(..)`, eg. `--param expansion=2`
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = crate::params::parse_assignment)]
+        params: Vec<(String, String)>,
+
+        /// Write a Chrome trace of every generator, solver and solution span, for Perfetto or `chrome://tracing`
+        #[arg(long, value_name = "FILE")]
+        trace: Option<PathBuf>,
+
+        /// Also run variants tagged `slow`
+        #[arg(long)]
+        include_slow: bool,
+
+        /// Run on `examples/<year>/<day>/<NAME>.txt` instead of the day's input, and check the answers in `<NAME>.answers`
+        #[arg(long, value_name = "NAME")]
+        example: Option<String>,
+    },
+
+    /// Run every language's solution for a day on the same input, and compare answers and timings
+    Compare {
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+
+    /// Run every variant of a day's solution and report any that disagree, time out or run out of memory
+    Verify {
+        #[command(flatten)]
+        limits: LimitArgs,
+
+        /// Also run variants tagged `slow`
+        #[arg(long)]
+        include_slow: bool,
+    },
 
     /// Benchmark your solution code with more precision
-    Bench,
+    Bench {
+        /// How long to sample each solution for
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "1s")]
+        time: Duration,
+
+        /// Compare against the latest results saved for a git revision or a named baseline
+        #[arg(long, value_name = "REV|BASELINE")]
+        compare: Option<String>,
+
+        /// Save these results under a name, for later use with `--compare`
+        #[arg(long, value_name = "NAME")]
+        save_baseline: Option<String>,
+
+        /// Smallest change in median time, in percent, that `--compare` will flag
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+
+        /// Draw charts of the year, this day's variants and their history
+        #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "svg")]
+        plot: Option<PlotFormat>,
+
+        /// Where `--plot` writes charts, relative to the workspace root
+        #[arg(long, value_name = "DIR", default_value = "assets/bench")]
+        report_dir: PathBuf,
+
+        #[command(flatten)]
+        limits: LimitArgs,
+
+        #[command(flatten)]
+        report: ReportArgs,
+
+        /// Also run variants tagged `slow`
+        #[arg(long)]
+        include_slow: bool,
+    },
+
+    /// Run a day with recording on, and draw the frames its solutions record as animations
+    Viz {
+        /// Only run this part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Only run the variants with this label, or this generator or solver slug
+        #[arg(long)]
+        variant: Option<String>,
+
+        /// Draw an animated GIF, or a folder of SVG frames, per variant
+        #[arg(short, long, value_enum, default_value_t = VizFormat::Gif)]
+        format: VizFormat,
+
+        /// Time between frames
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "100ms")]
+        delay: Duration,
+
+        /// Where to draw, relative to the workspace root
+        #[arg(long, value_name = "DIR", default_value = "assets/viz")]
+        out_dir: PathBuf,
+
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
 
     /// Run your code against unit tests defined in the code
-    Test,
+    Test {
+        /// Also test variants tagged `slow`
+        #[arg(long)]
+        include_slow: bool,
+    },
+
+    /// Report workspace problems: unbuildable crates, stub solvers, missing tests and inputs
+    Doctor,
+
+    /// Rewrite legacy `common::solution!` days into the `#[aoc]` macro form
+    Migrate {
+        /// Only report what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Manage alternative generators and solvers within a day
+    Variant {
+        #[command(subcommand)]
+        action: VariantAction,
+    },
     // /// Generate flamegraphs of CPU time used by your solution code
     // Flamegraph,
 
     // /// Run the Coz causal profiler on your solution code
     // Profile,
 }
+
+/// Resource limits applied to each solution, which runs in its own process.
+#[derive(Args, Clone, Debug, Default)]
+pub struct LimitArgs {
+    /// Wall-clock limit per solution, eg. `30s` or `5m` [default: 60s, `0` to disable]
+    #[arg(long, value_parser = humantime::parse_duration)]
+    pub timeout: Option<Duration>,
+
+    /// CPU time limit per solution, eg. `30s`
+    #[arg(long, value_parser = humantime::parse_duration)]
+    pub cpu_time: Option<Duration>,
+
+    /// Address-space limit per solution, eg. `512M` or `4G`
+    #[arg(long, value_parser = parse_memory)]
+    pub memory: Option<u64>,
+}
+
+/// Extra measurements and output for `run` and `bench`.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ReportArgs {
+    /// Count heap allocations per generator and solver (rebuilds with `aoc-runner/alloc-stats`)
+    #[arg(long)]
+    pub alloc: bool,
+
+    /// Also write the results, with timings and heap statistics, to a JSON file
+    #[arg(long, value_name = "FILE")]
+    pub json: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum VariantAction {
+    /// Insert a new solver or generator stub into a day's `solutions` module
+    Add {
+        /// What kind of function to scaffold
+        #[arg(value_enum)]
+        kind: VariantKind,
+
+        /// Display slug used in the `#[solver]` / `#[generator]` attribute
+        slug: String,
+
+        /// Which part the new solver answers (1 or 2)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariantKind {
+    Solver,
+    Generator,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlotFormat {
+    Svg,
+    Png,
+}
+
+impl PlotFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlotFormat::Svg => "svg",
+            PlotFormat::Png => "png",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VizFormat {
+    Gif,
+    Svg,
+}
+
+impl VizFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            VizFormat::Gif => "gif",
+            VizFormat::Svg => "svg",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    #[value(alias = "rust")]
+    Rs,
+    #[value(alias = "python")]
+    Py,
+}
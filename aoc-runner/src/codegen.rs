@@ -1,11 +1,13 @@
 use std::fs::write;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use cargo_metadata::camino::Utf8Path;
 use liquid::ParserBuilder;
 use toml_edit::{value, Array, DocumentMut, Table};
 
-const YEAR_CARGO_TEMPLATE: &str = include_str!("templates/year-Cargo.toml");
+use crate::iodomain::daysource::{AocPart, DayItem, DayItemKind, DaySource};
+
+pub(crate) const YEAR_CARGO_TEMPLATE: &str = include_str!("templates/year-Cargo.toml");
 const GITIGNORE: &str = include_str!("templates/year-gitignore");
 pub fn populate_year_package(year_root: &Utf8Path, year_num: u32) -> Result<()> {
     // Cargo.toml
@@ -37,7 +39,7 @@ pub fn add_package_to_workspace(workspace_toml: &Utf8Path, year_num: u32) -> Res
     Ok(())
 }
 
-const DAY_RS: &str = include_str!("templates/dayX.rs");
+pub(crate) const DAY_RS: &str = include_str!("templates/dayX.rs");
 pub fn generate_day_file(day_file: &Utf8Path, year_num: u32, day_num: u32) -> Result<()> {
     // Generate the day file -- eg, day4.rs
     let parser = ParserBuilder::with_stdlib().build()?;
@@ -78,3 +80,312 @@ pub fn add_day_to_package(
 
     Ok(())
 }
+
+const VARIANT_SOLVER_RS: &str = include_str!("templates/variant-solver.rs");
+const VARIANT_GENERATOR_RS: &str = include_str!("templates/variant-generator.rs");
+
+/// Splices a new solver or generator stub into a day's `#[aoc]` module, leaving the rest of the file as written.
+/// Input and return types are taken from the day's existing generators and solvers.
+/// Returns the 1-based line the stub starts on.
+pub fn add_variant_to_day(day: &DaySource, kind: DayItemKind, slug: &str) -> Result<usize> {
+    if day.items.iter().any(|i| i.kind == kind && i.display_slug == slug) {
+        return Err(anyhow!("{} already has a {kind:?} labelled `{slug}`", day.path));
+    }
+
+    let (template, fn_name, args) = match kind {
+        DayItemKind::Solver(part) => {
+            let input_type = day
+                .part_items(part)
+                .find_map(|i| i.input_type.clone())
+                .or_else(|| day.solvers().find_map(|i| i.input_type.clone()))
+                .or_else(|| day.generators().find_map(|i| i.output_type.clone()))
+                .ok_or(anyhow!("Cannot infer a solver input type: {} has no generators or solvers", day.path))?;
+            // Matches the macro's fallback answer type when a part has no solvers yet.
+            let output_type = day
+                .part_items(part)
+                .find_map(|i| i.output_type.clone())
+                .unwrap_or(match part {
+                    AocPart::Both => "(String, String)".to_string(),
+                    _ => "String".to_string(),
+                });
+            let part = part.flag();
+            (
+                VARIANT_SOLVER_RS,
+                format!("solve_{part}_{slug}"),
+                liquid::object!({"part": part, "slug": slug, "input_type": input_type, "output_type": output_type}),
+            )
+        }
+        DayItemKind::Generator => {
+            let output_type = generator_output_type(day)?;
+            (
+                VARIANT_GENERATOR_RS,
+                format!("input_generator_{slug}"),
+                liquid::object!({"slug": slug, "output_type": output_type}),
+            )
+        }
+        DayItemKind::Solution(_) => return Err(anyhow!("Solution stubs are not supported")),
+    };
+
+    if day.text.contains(&format!("fn {fn_name}(")) {
+        return Err(anyhow!("{} already defines `{fn_name}`", day.path));
+    }
+
+    let parser = ParserBuilder::with_stdlib().build()?;
+    let stub = parser.parse(template)?.render(&args)?;
+
+    // Keep like items together: after the last one of the same kind, or ahead of the first solver for generators.
+    let anchor_after: Option<&DayItem> = match kind {
+        DayItemKind::Solver(part) => day
+            .items
+            .iter()
+            .rfind(|i| i.kind == DayItemKind::Solver(part))
+            .or_else(|| day.solvers().last())
+            .or_else(|| day.generators().last()),
+        _ => day.generators().last(),
+    };
+
+    let (pos, new_text) = if let Some(item) = anchor_after {
+        let indent = day.indent_of(item.start);
+        (day.offset_of(item.end), format!("\n\n{}", indent_lines(&stub, indent)))
+    } else if let Some(item) = day.items.iter().min_by_key(|i| (i.start.line, i.start.column)) {
+        let indent = day.indent_of(item.start);
+        let line_start = day.offset_of(item.start) - indent.len();
+        (line_start, format!("{}\n\n", indent_lines(&stub, indent)))
+    } else {
+        let close_indent = day.indent_of(day.mod_close);
+        let indent = format!("{close_indent}    ");
+        let mut close = day.offset_of(day.mod_close);
+        if close_indent.len() == day.mod_close.column {
+            close -= close_indent.len();
+        }
+        (close, format!("\n{}\n", indent_lines(&stub, &indent)))
+    };
+
+    let mut text = day.text.clone();
+    text.insert_str(pos, &new_text);
+    write(&day.path, text.as_bytes())?;
+
+    let stub_start = pos + new_text.len() - new_text.trim_start().len();
+    let stub_line = text[..stub_start].matches('\n').count() + 1;
+
+    Ok(stub_line)
+}
+
+/// What a new generator returns: the same as the day's other generators, or else what its solvers
+/// take, owned, since they can borrow a generator's output.
+fn generator_output_type(day: &DaySource) -> Result<String> {
+    day.generators()
+        .find_map(|i| i.output_type.clone())
+        .or_else(|| day.solvers().find_map(|i| i.input_type.as_deref().map(owned_type)))
+        .ok_or(anyhow!("Cannot infer a generator output type: {} has no generators or solvers", day.path))
+}
+
+/// `ty` without a leading `&`, `&mut` or `&'a`.
+fn owned_type(ty: &str) -> String {
+    let Some(referent) = ty.strip_prefix('&') else {
+        return ty.to_string();
+    };
+    let referent = referent.trim_start();
+    let referent = match referent.strip_prefix('\'') {
+        Some(lifetime) => lifetime.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_'),
+        None => referent,
+    };
+    let referent = referent.trim_start();
+    referent.strip_prefix("mut ").unwrap_or(referent).trim_start().to_string()
+}
+
+fn indent_lines(text: &str, indent: &str) -> String {
+    text.trim_end()
+        .lines()
+        .map(|l| if l.is_empty() { l.to_string() } else { format!("{indent}{l}") })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use cargo_metadata::camino::Utf8PathBuf;
+
+    use super::*;
+
+    fn day(items: &str) -> DaySource {
+        let text = format!("#[aoc(2022, day7)]\npub mod solutions {{\n{items}\n}}\n");
+        DaySource::parse(Utf8Path::new("day_07.rs"), text).unwrap()
+    }
+
+    /// Adds a variant to a copy of `day` in a temp file, returning the stub's line and the new text.
+    fn add(day: &DaySource, kind: DayItemKind, slug: &str) -> Result<(usize, String)> {
+        let path = std::env::temp_dir().join(format!("aoc-codegen-{}-{slug}.rs", std::process::id()));
+        let path = Utf8PathBuf::from_path_buf(path).unwrap();
+        let day = DaySource::parse(&path, day.text.clone()).unwrap();
+        let added = add_variant_to_day(&day, kind, slug).and_then(|line| Ok((line, read_to_string(&path)?)));
+        let _ = std::fs::remove_file(&path);
+        added
+    }
+
+    #[test]
+    fn solvers_go_after_the_parts_last_solver() {
+        let day = day(indoc::indoc! {"
+            #[generator(gen)]
+            pub fn input_generator(input: &str) -> Input { todo!() }
+
+            #[solver(part1, main)]
+            pub fn solve_part1(input: &Input) -> u64 { todo!() }
+
+            #[solver(part2, main)]
+            pub fn solve_part2(input: &Input) -> u32 { todo!() }"});
+        let (line, text) = add(&day, DayItemKind::Solver(AocPart::Part1), "fast").unwrap();
+        let expected = indoc::indoc! {"
+            #[aoc(2022, day7)]
+            pub mod solutions {
+            #[generator(gen)]
+            pub fn input_generator(input: &str) -> Input { todo!() }
+
+            #[solver(part1, main)]
+            pub fn solve_part1(input: &Input) -> u64 { todo!() }
+
+            #[solver(part1, fast)]
+            pub fn solve_part1_fast(_input: &Input) -> u64 {
+                todo!()
+            }
+
+            #[solver(part2, main)]
+            pub fn solve_part2(input: &Input) -> u32 { todo!() }
+            }
+        "};
+        assert_eq!(text, expected);
+        assert_eq!(line, 9);
+    }
+
+    #[test]
+    fn generators_go_after_the_last_generator() {
+        let day = day(indoc::indoc! {"
+            #[generator(gen)]
+            pub fn input_generator(input: &str) -> Vec<u8> { todo!() }
+
+            #[solver(both, main)]
+            pub fn solve(input: &[u8]) -> (u64, u64) { todo!() }"});
+        let (line, text) = add(&day, DayItemKind::Generator, "bytes").unwrap();
+        let stub = indoc::indoc! {"
+            pub fn input_generator(input: &str) -> Vec<u8> { todo!() }
+
+            #[generator(bytes)]
+            pub fn input_generator_bytes(_input: &str) -> Vec<u8> {
+                todo!()
+            }
+
+            #[solver(both, main)]"};
+        assert!(text.contains(stub), "{text}");
+        assert_eq!(line, 6);
+    }
+
+    #[test]
+    fn both_parts_solvers_answer_with_a_pair() {
+        let day = day(indoc::indoc! {"
+            #[generator(gen)]
+            pub fn input_generator(input: &str) -> Vec<u8> { todo!() }"});
+        let (_, text) = add(&day, DayItemKind::Solver(AocPart::Both), "simd").unwrap();
+        let stub = "#[solver(both, simd)]\npub fn solve_both_simd(_input: Vec<u8>) -> (String, String) {";
+        assert!(text.contains(stub), "{text}");
+    }
+
+    #[test]
+    fn generators_without_an_anchor_go_before_the_first_item() {
+        let text = indoc::indoc! {"
+            #[aoc(2022, day7)]
+            pub mod solutions {
+                use super::*;
+
+                #[solver(part1, main)]
+                pub fn solve_part1(input: &Grid) -> u64 { todo!() }
+            }
+        "};
+        let day = DaySource::parse(Utf8Path::new("day_07.rs"), text.to_string()).unwrap();
+        let (line, text) = add(&day, DayItemKind::Generator, "gen").unwrap();
+        let expected = indoc::indoc! {"
+            #[aoc(2022, day7)]
+            pub mod solutions {
+                use super::*;
+
+                #[generator(gen)]
+                pub fn input_generator_gen(_input: &str) -> Grid {
+                    todo!()
+                }
+
+                #[solver(part1, main)]
+                pub fn solve_part1(input: &Grid) -> u64 { todo!() }
+            }
+        "};
+        assert_eq!(text, expected);
+        assert_eq!(line, 5);
+    }
+
+    #[test]
+    fn days_without_generators_or_solvers_cant_get_a_stub() {
+        let day = day(indoc::indoc! {"
+            #[solution(part1, main)]
+            pub fn part1(input: &str) -> u64 { todo!() }"});
+        let message = |kind| add(&day, kind, "new").unwrap_err().to_string();
+        assert!(message(DayItemKind::Generator).starts_with("Cannot infer a generator output type"));
+        assert!(message(DayItemKind::Solver(AocPart::Part2)).starts_with("Cannot infer a solver input type"));
+    }
+
+    #[test]
+    fn existing_variants_and_functions_arent_replaced() {
+        let day = day(indoc::indoc! {"
+            #[generator(gen)]
+            pub fn input_generator(input: &str) -> Input { todo!() }
+
+            #[solver(part1, main)]
+            pub fn solve_part1(input: &Input) -> u64 { todo!() }
+
+            fn solve_part2_main() {}"});
+        let message = |kind, slug| add(&day, kind, slug).unwrap_err().to_string();
+        let (part1, part2) = (DayItemKind::Solver(AocPart::Part1), DayItemKind::Solver(AocPart::Part2));
+        assert!(message(part1, "main").ends_with("already has a Solver(Part1) labelled `main`"));
+        assert!(message(DayItemKind::Generator, "gen").ends_with("already has a Generator labelled `gen`"));
+        assert!(message(part2, "main").ends_with("already defines `solve_part2_main`"));
+        assert_eq!(message(DayItemKind::Solution(AocPart::Part1), "direct"), "Solution stubs are not supported");
+    }
+
+    #[test]
+    fn generator_output_type_matches_existing_generator() {
+        let day = day(indoc::indoc! {"
+            #[generator(gen)]
+            pub fn input_generator(input: &str) -> Input { todo!() }
+
+            #[solver(part1, main)]
+            pub fn solve_part1(input: &Input) -> u64 { todo!() }
+        "});
+        assert_eq!(generator_output_type(&day).unwrap(), "Input");
+    }
+
+    #[test]
+    fn generator_output_type_falls_back_to_owned_solver_input() {
+        let day = day(indoc::indoc! {"
+            #[solver(part1, main)]
+            pub fn solve_part1(input: &mut Vec<u8>) -> u64 { todo!() }
+        "});
+        assert_eq!(generator_output_type(&day).unwrap(), "Vec<u8>");
+    }
+
+    #[test]
+    fn generator_output_type_needs_generator_or_solver() {
+        let day = day(indoc::indoc! {"
+            #[solution(part1, main)]
+            pub fn part1(input: &str) -> u64 { todo!() }
+        "});
+        assert!(generator_output_type(&day).is_err());
+    }
+
+    #[test]
+    fn owned_type_strips_references() {
+        assert_eq!(owned_type("Input"), "Input");
+        assert_eq!(owned_type("&Input"), "Input");
+        assert_eq!(owned_type("&mut Input"), "Input");
+        assert_eq!(owned_type("&'a mut Grid<u8>"), "Grid<u8>");
+        assert_eq!(owned_type("& 'static str"), "str");
+    }
+}
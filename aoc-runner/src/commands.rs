@@ -2,11 +2,13 @@ use std::{
     cmp::min,
     fs::{create_dir_all, write, remove_file},
     io::{BufRead, Write},
-    process::Command,
+    process::{Command, ExitStatus},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{anyhow, Context, Ok};
+use cargo_metadata::camino::Utf8PathBuf;
 use chrono::{Datelike, Timelike, Utc};
 use chrono_tz::US::Eastern;
 use regex::Regex;
@@ -14,12 +16,26 @@ use reqwest::{blocking::ClientBuilder, cookie::Jar, Url};
 use thiserror::Error;
 
 use crate::{
-    cli::{Aoc, Commands},
-    codegen::{add_day_to_package, add_package_to_workspace, generate_day_file, populate_year_package},
+    cli::{Aoc, Commands, Lang, LimitArgs, ReportArgs, VariantAction, VariantKind},
+    codegen::{
+        add_day_to_package, add_package_to_workspace, add_variant_to_day, generate_day_file, populate_year_package,
+    },
+    doctor::{diagnose, Severity},
+    examples,
+    harness::{DayFailure, DayReport, Limits, RunOptions, VariantReport, INCLUDE_SLOW_VAR, SKIPPING_SLOW},
+    history::{compare_samples, select_reference, BenchRecord, Comparison, GitState, HistoryStore, Reference, Verdict},
     iodomain::{
-        cargo::{day_from_bin, year_from_package, WorkspaceMeta},
+        cargo::{year_from_package, WorkspaceMeta},
         credentials::{CookieStore, SessionFileCookieStore},
+        daysource::{AocPart, DayItemKind, DaySource},
+    },
+    lang::{self, Language, PartAnswer, RustBackend},
+    migrate::{
+        find_dangling_legacy_files, migrate_day_file, migrate_lib_file, migrate_workspace_manifest,
+        migrate_year_manifest, Migration, MigrationAction,
     },
+    plot::plot_day,
+    viz::{self, Settings},
 };
 
 const AUTH_MESSAGE: &str = "This command doesn't implement proper authenticaion yet. Use your browser to visit and log in to the AOC website, then copy the value of the 'session' cookie, and paste it here: ";
@@ -220,12 +236,132 @@ pub fn prepare<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, _cli
 
 #[derive(Error, Debug)]
 enum RunError {
-    #[error("No targets found. Are there binaries in your Cargo.toml named similar to `day15`?")]
-    NoTargetsFound,
+    #[error("No targets found. Are there {0}?")]
+    NoTargetsFound(&'static str),
     #[error("Could not pick out a default year. Are you currently in a year-specific crate's folder?")]
     NoYearsFound,
     #[error("Could not find year specified. Is that year a crate in your workspace?")]
     YearNotFound,
+    #[error("The {lang} solution exited with {}.", describe_exit(.status))]
+    SolutionFailed { lang: &'static str, status: ExitStatus },
+    #[error("The tests exited with {0}.")]
+    TestsFailed(ExitStatus),
+    #[error("No solutions printed any answers.")]
+    NoAnswers,
+    #[error("There is no example {0}.")]
+    ExampleNotFound(Utf8PathBuf),
+    #[error("{0} answer(s) differ from the example's.")]
+    ExampleMismatch(usize),
+}
+
+/// The exit status, with what it means when it's one of a day's `DayFailure` codes.
+fn describe_exit(status: &ExitStatus) -> String {
+    match status.code().and_then(DayFailure::from_code) {
+        Some(failure) => format!("{status} ({failure})"),
+        None => status.to_string(),
+    }
+}
+
+/// Whether the day ran to the end, even if some of its variants failed or disagreed, so that its
+/// output and report cover every variant.
+fn ran_to_end(status: &ExitStatus) -> bool {
+    status.success()
+        || matches!(
+            status.code().and_then(DayFailure::from_code),
+            Some(DayFailure::Mismatch | DayFailure::Failed | DayFailure::Panicked)
+        )
+}
+
+/// The code for the CLI to exit with after `error`: the day's or the tests' own, when the error is
+/// that they failed, so scripts see the same code through `aoc` as from the day binary.
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    let status = match (error.downcast_ref::<RunError>(), error.downcast_ref::<VerifyError>()) {
+        (Some(RunError::SolutionFailed { status, .. } | RunError::TestsFailed(status)), _) => Some(status),
+        (_, Some(VerifyError::VariantsFailed { status, .. })) => Some(status),
+        _ => None,
+    };
+    status
+        .and_then(ExitStatus::code)
+        .and_then(|code| u8::try_from(code).ok())
+        .filter(|&code| code != 0)
+        .unwrap_or(1)
+}
+
+fn run_options(limits: &LimitArgs, report: Option<&ReportArgs>) -> anyhow::Result<RunOptions> {
+    let defaults = Limits::default();
+    let limits = Limits {
+        wall: match limits.timeout {
+            None => defaults.wall,
+            Some(timeout) => Some(timeout).filter(|t| !t.is_zero()),
+        },
+        cpu: limits.cpu_time.filter(|t| !t.is_zero()),
+        memory: limits.memory.filter(|&m| m > 0),
+    };
+
+    // The day binary runs from its crate's folder, so relative paths are resolved here.
+    let json = match report.and_then(|r| r.json.as_ref()) {
+        Some(path) => Some(std::env::current_dir()?.join(path)),
+        None => None,
+    };
+
+    Ok(RunOptions {
+        limits,
+        bench: None,
+        alloc_stats: report.is_some_and(|r| r.alloc),
+        json,
+        shared: false,
+        params: Vec::new(),
+        part: None,
+        variant: None,
+        viz: None,
+        trace: None,
+        include_slow: false,
+        skip_experimental: false,
+    })
+}
+
+fn resolve_run_day(backend: &dyn Language, data: &WorkspaceMeta, year_num: u16, cli: &Aoc) -> anyhow::Result<u8> {
+    let days = backend.discover(data, year_num);
+    match cli.day {
+        None => days.last().copied(),
+        Some(d) => days.contains(&d).then_some(d),
+    }
+    .ok_or(RunError::NoTargetsFound(backend.layout_hint()).into())
+}
+
+fn resolve_run_year(data: &WorkspaceMeta, cli: &Aoc) -> anyhow::Result<u16> {
+    match cli.year {
+        None => year_from_package(data.current_package().ok_or(RunError::NoYearsFound)?),
+        Some(y) => Ok(y),
+    }
+}
+
+/// Downloads the day's input if it isn't on disk yet, returning where it lives.
+fn ensure_input<T: BufRead, U: Write>(
+    readfn: fn() -> T,
+    writefn: fn() -> U,
+    data: &WorkspaceMeta,
+    year_num: u16,
+    day_num: u8,
+) -> Utf8PathBuf {
+    let input_file = data.get_input_file_for_day(&year_num, &day_num);
+    if !input_file.exists() {
+        println!("Creating input file: {}", input_file);
+        let input_args = Aoc {
+            verbose: 0,
+            day: Some(day_num),
+            year: Some(year_num),
+            command: Some(Commands::Input),
+        };
+        let res = input(readfn, writefn, input_args);
+        if let Err(e) = res {
+            println!("Error while downloading input: {}", e);
+        }
+    } else {
+        println!("File exists: {}", input_file);
+    }
+
+    input_file
 }
 
 pub fn run<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc, cmd: &str) -> anyhow::Result<()> {
@@ -233,16 +369,6 @@ pub fn run<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc
     let data = WorkspaceMeta::load()
         .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
 
-    // for pack_id in data.worspace_data.workspace_members.iter() {
-    //     println!("  Pack ID: {}", pack_id);
-    //     if let Some(pack) = data.package_map().get(&pack_id) {
-    //         println!("    Manifest path: {}", pack.manifest_path);
-    //         for t in pack.targets.iter() {
-    //             println!("    Target: {} ({})", t.name, t.src_path);
-    //         }
-    //     }
-    // }
-
     match cmd {
         "run" => {}
         "test" => {}
@@ -251,57 +377,612 @@ pub fn run<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc
         }
     }
 
+    let (lang, example, options) = match &cli.command {
+        Some(Commands::Run {
+            lang,
+            limits,
+            report,
+            shared,
+            params,
+            trace,
+            include_slow,
+            example,
+        }) => (
+            *lang,
+            example.as_deref(),
+            RunOptions {
+                shared: *shared,
+                params: params.clone(),
+                include_slow: *include_slow,
+                trace: match trace {
+                    Some(path) => {
+                        let path = std::env::current_dir()?.join(path);
+                        if let Some(dir) = path.parent() {
+                            create_dir_all(dir)?;
+                        }
+                        Some(path)
+                    }
+                    None => None,
+                },
+                ..run_options(limits, Some(report))?
+            },
+        ),
+        _ => (Lang::Rs, None, RunOptions::default()),
+    };
+    let backend = lang::backend(lang);
+
     // Figure out which year we're in
+    let year_num = resolve_run_year(&data, &cli)?;
+    if lang == Lang::Rs && !data.get_year_map().contains_key(&year_num) {
+        return Err(RunError::YearNotFound.into());
+    }
+
+    // Figure out the selected day
+    let day_num = resolve_run_day(backend.as_ref(), &data, year_num, &cli)?;
+
+    // Try to get the input for the problem if we don't have it, unless an example was asked for.
+    let input_file = match example {
+        Some(name) => {
+            let path = examples::input_path(&data.worspace_data.workspace_root, year_num, day_num, name);
+            if !path.exists() {
+                return Err(RunError::ExampleNotFound(path).into());
+            }
+            path
+        }
+        None => ensure_input(readfn, writefn, &data, year_num, day_num),
+    };
+
+    if cmd == "test" {
+        let pack = data.get_year_map()[&year_num];
+        let mut command = Command::new("cargo");
+        command.arg(cmd).arg("--release").current_dir(pack.manifest_path.parent().unwrap());
+        if let Some(Commands::Test { include_slow: true }) = &cli.command {
+            command.env(INCLUDE_SLOW_VAR, "1");
+        }
+        match data.get_day_map(pack).get(&day_num) {
+            Some(target) => {
+                println!("Running tests for {}", target.name);
+                command.arg("--bin").arg(&target.name);
+            }
+            None => {
+                let module = data.get_lib_day_modules(pack).remove(&day_num).unwrap_or_default();
+                println!("Running tests for {}::{module}", pack.name);
+                command.arg("--lib").arg("--").arg(format!("{module}::"));
+            }
+        }
+
+        let mut child = command.spawn()?;
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(RunError::TestsFailed(status).into());
+        }
+        return Ok(());
+    }
+
+    // And now, to run the solution!
+    println!("Running {} solutions for {} day {}", backend.name(), year_num, day_num);
+
+    let output = backend.run(&data, year_num, day_num, &input_file, &options)?;
+    print!("{}", output.stdout);
+    if !output.status.success() {
+        return Err(RunError::SolutionFailed {
+            lang: backend.name(),
+            status: output.status,
+        }
+        .into());
+    }
+
+    if let Some(expected) = example.map(|_| examples::expected_answers(&input_file)).transpose()?.flatten() {
+        let answers = backend.parse_answers(&output)?;
+        let mismatches = examples::mismatches(&expected, &answers);
+        for (answer, wanted) in &mismatches {
+            println!(
+                "Part {}, {} answered {}, but the example expects {}.",
+                answer.part, answer.label, answer.answer, wanted
+            );
+        }
+        if !mismatches.is_empty() {
+            return Err(RunError::ExampleMismatch(mismatches.len()).into());
+        }
+        println!("Every answer matches the example's.");
+    }
+
+    Ok(())
+}
+
+pub fn compare<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc) -> anyhow::Result<()> {
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+
+    let options = match &cli.command {
+        Some(Commands::Compare { limits }) => run_options(limits, None)?,
+        _ => RunOptions::default(),
+    };
+    let backends = lang::all_backends();
+    let year_num = resolve_run_year(&data, &cli)?;
+    let discovered: Vec<Vec<u8>> = backends.iter().map(|b| b.discover(&data, year_num)).collect();
+    let Some(day_num) = cli.day.or_else(|| discovered.iter().flatten().max().copied()) else {
+        return Err(RunError::NoTargetsFound(backends[0].layout_hint()).into());
+    };
+
+    let input_file = ensure_input(readfn, writefn, &data, year_num, day_num);
+
+    let mut rows: Vec<(&str, PartAnswer)> = Vec::new();
+    for (backend, days) in backends.iter().zip(discovered.iter()) {
+        if !days.contains(&day_num) {
+            println!("No {} solution for {} day {}, skipping.", backend.name(), year_num, day_num);
+            continue;
+        }
+
+        println!("Running {} solutions for {} day {}", backend.name(), year_num, day_num);
+        let output = match backend.run(&data, year_num, day_num, &input_file, &options) {
+            Result::Ok(output) => output,
+            Err(e) => {
+                println!("Could not run the {} solution: {}", backend.name(), e);
+                continue;
+            }
+        };
+        if !output.status.success() {
+            println!("The {} solution exited with {}.", backend.name(), describe_exit(&output.status));
+        }
+        match backend.parse_answers(&output) {
+            Result::Ok(answers) => rows.extend(answers.into_iter().map(|a| (backend.name(), a))),
+            Err(e) => println!("Could not read the {} solution's answers: {}", backend.name(), e),
+        }
+    }
+
+    if rows.is_empty() {
+        return Err(RunError::NoAnswers.into());
+    }
+    rows.sort_by_key(|(_, a)| a.part);
+
+    let times: Vec<String> = rows
+        .iter()
+        .map(|(_, a)| a.elapsed.map(|e| format!("{e:.2?}")).unwrap_or("-".to_string()))
+        .collect();
+    let lang_width = rows.iter().map(|(l, _)| l.len()).chain(["Language".len()]).max().unwrap();
+    let label_width = rows.iter().map(|(_, a)| a.label.chars().count()).chain(["Label".len()]).max().unwrap();
+    let time_width = times.iter().map(|t| t.chars().count()).chain(["Time".len()]).max().unwrap();
+
+    println!("\n## AOC {}, Day {} ----------", year_num, day_num);
+    println!(
+        "Part  {:lang_width$}  {:label_width$}  {:>time_width$}  Answer",
+        "Language", "Label", "Time"
+    );
+    for ((lang, answer), time) in rows.iter().zip(times.iter()) {
+        // The first language to answer a part is the one the others are checked against.
+        let reference = rows.iter().find(|(_, a)| a.part == answer.part).map(|(_, a)| &a.answer);
+        let mark = if reference == Some(&answer.answer) { "✅" } else { "❌" };
+        println!(
+            "{:<4}  {:lang_width$}  {:label_width$}  {:>time_width$}  {} {}",
+            answer.part, lang, answer.label, time, answer.answer, mark
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+enum VerifyError {
+    #[error("{count} variant(s) failed verification.")]
+    VariantsFailed { count: usize, status: ExitStatus },
+}
+
+pub fn verify<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc) -> anyhow::Result<()> {
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+
+    // Experimental variants may well be wrong, so they aren't verified.
+    let mut options = match &cli.command {
+        Some(Commands::Verify { limits, include_slow }) => RunOptions {
+            include_slow: *include_slow,
+            skip_experimental: true,
+            ..run_options(limits, None)?
+        },
+        _ => RunOptions {
+            skip_experimental: true,
+            ..RunOptions::default()
+        },
+    };
+
+    // Variants only exist for Rust solutions.
+    let backend = RustBackend;
+    let year_num = resolve_run_year(&data, &cli)?;
+    let day_num = resolve_run_day(&backend, &data, year_num, &cli)?;
+
+    let input_file = ensure_input(readfn, writefn, &data, year_num, day_num);
+
+    // Results are read back from the report, not the output, which solutions print into too.
+    let json_path = data.worspace_data.target_directory.join("aoc").join("verify-latest.json").into_std_path_buf();
+    if let Some(dir) = json_path.parent() {
+        create_dir_all(dir)?;
+    }
+    let _ = remove_file(&json_path);
+    options.json = Some(json_path.clone());
+
+    println!("Verifying variants for {} day {}", year_num, day_num);
+    let output = backend.run(&data, year_num, day_num, &input_file, &options)?;
+    let reports = match std::fs::read_to_string(&json_path) {
+        Result::Ok(json) => VariantReport::from_day_report(&serde_json::from_str::<DayReport>(&json)?),
+        Err(_) => Vec::new(),
+    };
+    if reports.is_empty() {
+        print!("{}", output.stdout);
+        return Err(RunError::NoAnswers.into());
+    }
+
+    let times: Vec<String> = reports
+        .iter()
+        .map(|r| r.elapsed.map(|e| format!("{e:.2?}")).unwrap_or("-".to_string()))
+        .collect();
+    let label_width = reports.iter().map(|r| r.label.chars().count()).chain(["Variant".len()]).max().unwrap();
+    let time_width = times.iter().map(|t| t.chars().count()).chain(["Time".len()]).max().unwrap();
+
+    println!("\n## AOC {}, Day {} ----------", year_num, day_num);
+    println!("Part  {:label_width$}  {:>time_width$}  Status", "Variant", "Time");
+    for (report, time) in reports.iter().zip(times.iter()) {
+        println!(
+            "{:<4}  {:label_width$}  {:>time_width$}  {}",
+            report.part, report.label, time, report.status
+        );
+    }
+
+    let failed = reports.iter().filter(|r| !r.status.is_ok()).count();
+    if failed > 0 {
+        return Err(VerifyError::VariantsFailed {
+            count: failed,
+            status: output.status,
+        }
+        .into());
+    }
+    println!("\nAll {} variant(s) agree.", reports.len());
+    for line in output.stdout.lines().filter(|line| line.starts_with(SKIPPING_SLOW)) {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+enum VizError {
+    #[error("No variant recorded any frames. Take an `Option<aoc_runner::viz::Recorder>` argument and record with it.")]
+    NothingRecorded,
+}
+
+pub fn visualize<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc) -> anyhow::Result<()> {
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+
+    let Some(Commands::Viz {
+        part,
+        variant,
+        format,
+        delay,
+        out_dir,
+        limits,
+    }) = &cli.command
+    else {
+        return Err(anyhow!("Invalid command given to `visualize`. This should not happen."));
+    };
+
+    let options = RunOptions {
+        part: *part,
+        variant: variant.clone(),
+        viz: Some(Settings {
+            dir: data.worspace_data.workspace_root.join(Utf8PathBuf::try_from(out_dir.clone())?).into(),
+            format: *format,
+            delay: *delay,
+        }),
+        ..run_options(limits, None)?
+    };
+
+    // Recorders are handed out by the harness that `#[aoc]` generates, so only Rust days can record.
+    let backend = RustBackend;
+    let year_num = resolve_run_year(&data, &cli)?;
+    let day_num = resolve_run_day(&backend, &data, year_num, &cli)?;
+    let input_file = ensure_input(readfn, writefn, &data, year_num, day_num);
+
+    println!("Recording {} day {}", year_num, day_num);
+    let output = backend.run(&data, year_num, day_num, &input_file, &options)?;
+    print!("{}", output.stdout);
+    // Variants that fail or disagree can still have recorded something worth seeing.
+    if !ran_to_end(&output.status) {
+        return Err(RunError::SolutionFailed {
+            lang: backend.name(),
+            status: output.status,
+        }
+        .into());
+    }
+    if viz::drawn(&output.stdout).is_empty() {
+        return Err(VizError::NothingRecorded.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+enum BenchError {
+    #[error("{0} variant(s) got slower.")]
+    Regressions(usize),
+    #[error("The benchmark did not write a report to {0}.")]
+    NoReport(String),
+}
+
+pub fn benchmark<T: BufRead, U: Write>(readfn: fn() -> T, writefn: fn() -> U, cli: Aoc) -> anyhow::Result<()> {
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+
+    let Some(Commands::Bench {
+        time,
+        compare,
+        save_baseline,
+        threshold,
+        plot,
+        report_dir,
+        limits,
+        report,
+        include_slow,
+    }) = &cli.command
+    else {
+        return Err(anyhow!("Invalid command given to `benchmark`. This should not happen."));
+    };
+
+    // Every run is saved to the history, so the report is needed even without `--json`.
+    let mut options = RunOptions {
+        bench: Some(*time),
+        include_slow: *include_slow,
+        ..run_options(limits, Some(report))?
+    };
+    let user_json = options.json.is_some();
+    let json_path = options
+        .json
+        .get_or_insert_with(|| data.worspace_data.target_directory.join("aoc").join("bench-latest.json").into())
+        .clone();
+    if let Some(dir) = json_path.parent() {
+        create_dir_all(dir)?;
+    }
+    let _ = remove_file(&json_path);
+
+    // Checked before benching, so a mistyped revision doesn't cost a full run.
+    let root = &data.worspace_data.workspace_root;
+    let store = HistoryStore::for_workspace(root);
+    let history = store.load()?;
+    let reference = match compare {
+        Some(reference) => Some(select_reference(&history, root, reference)?),
+        None => None,
+    };
+
+    // Sampling happens inside the harness that `#[aoc]` generates, so only Rust days can be benched.
+    let backend = RustBackend;
+    let year_num = resolve_run_year(&data, &cli)?;
+    let day_num = resolve_run_day(&backend, &data, year_num, &cli)?;
+    let input_file = ensure_input(readfn, writefn, &data, year_num, day_num);
+
+    println!("Benchmarking {} day {}", year_num, day_num);
+    let output = backend.run(&data, year_num, day_num, &input_file, &options)?;
+    print!("{}", output.stdout);
+    // Variants that fail or disagree are saved with their outcome like the rest.
+    if !ran_to_end(&output.status) {
+        return Err(RunError::SolutionFailed {
+            lang: backend.name(),
+            status: output.status,
+        }
+        .into());
+    }
+    if user_json {
+        println!("Wrote results to {}", json_path.display());
+    }
+
+    let day_report: DayReport = serde_json::from_str(
+        &std::fs::read_to_string(&json_path)
+            .map_err(|_| BenchError::NoReport(json_path.display().to_string()))?,
+    )?;
+
+    let git = GitState::current(root);
+    let records = BenchRecord::from_report(&day_report, &git, save_baseline.as_deref());
+    store.append(&records)?;
+    println!(
+        "Saved {} result(s) to {} (commit {:.10}{})",
+        records.len(),
+        store.path,
+        git.commit,
+        if git.dirty { ", uncommitted changes" } else { "" }
+    );
+
+    if let Some(format) = plot {
+        let all: Vec<BenchRecord> = history.iter().chain(records.iter()).cloned().collect();
+        let dir = root.join(Utf8PathBuf::try_from(report_dir.clone())?);
+        for chart in plot_day(&all, year_num as u32, day_num as u32, &dir, *format)? {
+            println!("Drew {}", chart);
+        }
+    }
+
+    let Some(Reference { description, latest }) = reference else {
+        return Ok(());
+    };
+    let compared: Vec<(&BenchRecord, Comparison)> = records
+        .iter()
+        .filter_map(|r| latest.get(&r.key()).map(|p| (r, compare_samples(p, r, threshold / 100.0))))
+        .collect();
+    if compared.is_empty() {
+        println!("\nNo saved results for {} match these variants.", description);
+        return Ok(());
+    }
+
+    let fmt_ns = |ns: u64| format!("{:.2?}", Duration::from_nanos(ns));
+    let label_width = compared.iter().map(|(r, _)| r.label.chars().count()).chain(["Variant".len()]).max().unwrap();
+
+    println!("\nCompared with {}:", description);
+    println!(
+        "Part  {:label_width$}  {:>10}  {:>10}  {:>8}  {:>6}",
+        "Variant", "Before", "After", "Change", "p"
+    );
+    for (record, cmp) in &compared {
+        let verdict = match cmp.verdict {
+            Verdict::Slower => "SLOWER",
+            Verdict::Faster => "faster",
+            Verdict::Unchanged => "",
+            Verdict::TooFewSamples => "too few samples",
+        };
+        println!(
+            "{:<4}  {:label_width$}  {:>10}  {:>10}  {:>+7.1}%  {:>6.3}  {}",
+            record.part,
+            record.label,
+            fmt_ns(cmp.before_ns),
+            fmt_ns(cmp.after_ns),
+            cmp.change * 100.0,
+            cmp.p_value,
+            verdict
+        );
+    }
+
+    let slower = compared.iter().filter(|(_, c)| c.verdict == Verdict::Slower).count();
+    if slower > 0 {
+        return Err(BenchError::Regressions(slower).into());
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+enum VariantError {
+    #[error("Please specify which day to add a variant to with `--day`.")]
+    NoDaySpecified,
+    #[error("Could not find a source file for that day. Is there a `dayN.rs` or `day_NN.rs` in the year crate?")]
+    DayNotFound,
+    #[error("Solver variants need a part. Pass `--part 1` or `--part 2`.")]
+    NoPartSpecified,
+}
+
+pub fn variant<T: BufRead, U: Write>(_readfn: fn() -> T, _writefn: fn() -> U, cli: Aoc) -> anyhow::Result<()> {
+    let Some(Commands::Variant { action }) = &cli.command else {
+        return Err(anyhow!("Invalid command given to `variant`. This should not happen."));
+    };
+
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+
     let pack = match cli.year {
         None => data.current_package().ok_or(RunError::NoYearsFound),
         Some(y) => data.get_year_map().get(&y).copied().ok_or(RunError::YearNotFound),
     }?;
+    let day_num = cli.day.ok_or(VariantError::NoDaySpecified)?;
+    let day_file = data.get_day_source_file(pack, day_num).ok_or(VariantError::DayNotFound)?;
+
+    match action {
+        VariantAction::Add { kind, slug, part } => {
+            let day = DaySource::load(&day_file)?;
+            let item_kind = match kind {
+                VariantKind::Generator => DayItemKind::Generator,
+                VariantKind::Solver => {
+                    DayItemKind::Solver(part.and_then(AocPart::from_number).ok_or(VariantError::NoPartSpecified)?)
+                }
+            };
 
-    // Figure out the selected day
-    let Some(target) = (match cli.day {
-        None => data.get_target_for_latest_day(pack),
-        Some(d) => data.get_day_map(pack).get(&d).copied(),
-    }) else {
-        return Err(RunError::NoTargetsFound.into());
+            let line = add_variant_to_day(&day, item_kind, slug)?;
+            println!("Added {kind:?} `{slug}` to {} day {} at {}:{}", day.year, day.day, day_file, line);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn migrate<T: BufRead, U: Write>(_readfn: fn() -> T, _writefn: fn() -> U, cli: Aoc) -> anyhow::Result<()> {
+    let Some(Commands::Migrate { dry_run }) = &cli.command else {
+        return Err(anyhow!("Invalid command given to `migrate`. This should not happen."));
     };
 
-    // Try to get the input for the problem if we don't have it.
-    let day_num = day_from_bin(target)?;
-    let year_num = year_from_package(pack)?;
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+    let root = data.worspace_data.workspace_root.clone();
 
-    let input_file = data.get_input_file_for_day(&year_num, &day_num);
-    if !input_file.exists() {
-        println!("Creating input file: {}", input_file);
-        let input_args = Aoc {
-            verbose: 0,
-            day: Some(day_num),
-            year: Some(year_num),
-            command: Some(Commands::Input),
-        };
-        let res = input(readfn, writefn, input_args);
-        if let Err(e) = res {
-            println!("Error while downloading input: {}", e);
+    // Legacy crates usually aren't workspace members anymore, since they can't build.
+    let mut year_dirs: Vec<(u16, Utf8PathBuf)> = data
+        .get_year_map()
+        .into_iter()
+        .map(|(y, p)| (y, p.manifest_path.parent().unwrap().to_owned()))
+        .chain(data.get_unregistered_year_dirs())
+        .filter(|(y, _)| cli.year.is_none_or(|wanted| wanted == *y))
+        .collect();
+    year_dirs.sort();
+
+    let mut migrations: Vec<Migration> = Vec::new();
+    let mut new_members: Vec<String> = Vec::new();
+    for (year, dir) in year_dirs.iter() {
+        let src_dir = dir.join("src");
+        let before = migrations.len();
+
+        let mut day_files: Vec<Utf8PathBuf> = src_dir.read_dir_utf8()?.flatten().map(|e| e.into_path()).collect();
+        day_files.sort();
+        for path in day_files.iter().filter(|p| p.extension() == Some("rs")) {
+            if path.file_name() == Some("lib.rs") {
+                migrations.extend(migrate_lib_file(path)?);
+            } else {
+                migrations.extend(migrate_day_file(path, *year as u32)?);
+            }
+        }
+        migrations.extend(find_dangling_legacy_files(&src_dir)?);
+        migrations.extend(migrate_year_manifest(&dir.join("Cargo.toml"))?);
+
+        if migrations.len() > before && data.get_unregistered_year_dirs().iter().any(|(_, d)| d == dir) {
+            new_members.push(dir.strip_prefix(&root).unwrap_or(dir).to_string());
         }
-    } else {
-        println!("File exists: {}", input_file);
     }
+    migrations.extend(migrate_workspace_manifest(&root.join("Cargo.toml"), &new_members)?);
 
-    // And now, to run the target!
-    println!("Running solutions for {}", target.name);
+    if migrations.is_empty() {
+        println!("Nothing to migrate.");
+        return Ok(());
+    }
 
-    let mut child = Command::new("cargo")
-        .arg(cmd)
-        .arg("--release")
-        .arg("--bin")
-        .arg(&target.name)
-        .current_dir(pack.manifest_path.parent().unwrap())
-        .spawn()?;
+    for migration in migrations.iter() {
+        let verb = match migration.action {
+            MigrationAction::Rewrite(_) => "Rewrite",
+            MigrationAction::Delete => "Delete",
+        };
+        println!("{verb} {}", migration.path.strip_prefix(&root).unwrap_or(&migration.path));
+        for note in migration.notes.iter() {
+            println!("    - {note}");
+        }
+        if !dry_run {
+            migration.apply()?;
+        }
+    }
 
-    child.wait()?;
+    if *dry_run {
+        println!("\nDry run, no files were changed.");
+    }
 
     Ok(())
 }
 
-pub fn benchmark<T: BufRead, U: Write>(_readfn: fn() -> T, _writefn: fn() -> U, _cli: Aoc) -> anyhow::Result<()> {
-    todo!()
+pub fn doctor<T: BufRead, U: Write>(_readfn: fn() -> T, _writefn: fn() -> U, _cli: Aoc) -> anyhow::Result<()> {
+    let data = WorkspaceMeta::load()
+        .context("Failed to load data for the current cargo workspace. Are you in a crate or workspace?")?;
+
+    let findings = diagnose(&data)?;
+    if findings.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    let count = |sev: Severity| findings.iter().filter(|f| f.severity == sev).count();
+    println!(
+        "Found {} error(s), {} warning(s) and {} note(s).",
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Note)
+    );
+
+    let mut current: Option<Severity> = None;
+    for finding in findings.iter() {
+        if current != Some(finding.severity) {
+            current = Some(finding.severity);
+            println!("\n{}", finding.severity);
+        }
+        println!("- [ ] {finding}");
+    }
+
+    Ok(())
 }
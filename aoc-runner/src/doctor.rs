@@ -0,0 +1,732 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::fs::read_to_string;
+
+use anyhow::Result;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use regex::Regex;
+use syn::{
+    parse::{ParseStream, Parser},
+    visit::Visit,
+    Attribute, Block, Expr, Item, ItemFn, ItemMod, Lit, Macro, Stmt,
+};
+use toml_edit::{DocumentMut, Item as TomlItem};
+
+use crate::{
+    codegen::{DAY_RS, YEAR_CARGO_TEMPLATE},
+    iodomain::{cargo::WorkspaceMeta, daysource::parse_aoc_args},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Something won't compile, or isn't compiled at all.
+    Error,
+    /// Compiles, but running it won't give a real answer.
+    Warning,
+    /// Worth tidying up.
+    Note,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "Errors"),
+            Severity::Warning => write!(f, "Warnings"),
+            Severity::Note => write!(f, "Notes"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Finding {
+    pub severity: Severity,
+    pub path: Utf8PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, path: &Utf8Path, line: usize, message: impl Into<String>) -> Self {
+        Finding {
+            severity,
+            path: path.to_owned(),
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            0 => write!(f, "{}: {}", self.path, self.message),
+            line => write!(f, "{}:{}: {}", self.path, line, self.message),
+        }
+    }
+}
+
+/// Runs every check over the workspace. Findings come back sorted, most severe first.
+pub fn diagnose(meta: &WorkspaceMeta) -> Result<Vec<Finding>> {
+    let root = &meta.worspace_data.workspace_root;
+    let mut findings = Vec::new();
+
+    let mut year_dirs: Vec<(u16, Utf8PathBuf, bool)> = meta
+        .get_year_map()
+        .into_iter()
+        .map(|(y, p)| (y, p.manifest_path.parent().unwrap().to_owned(), true))
+        .chain(meta.get_unregistered_year_dirs().into_iter().map(|(y, d)| (y, d, false)))
+        .collect();
+    year_dirs.sort();
+
+    let workspace_toml = root.join("Cargo.toml");
+    let workspace_text = read_to_string(&workspace_toml)?;
+    let workspace_doc = workspace_text.parse::<DocumentMut>()?;
+    findings.extend(check_workspace_deps(root, &workspace_toml, &workspace_text, &workspace_doc));
+
+    for (year, dir, member) in year_dirs.iter() {
+        if !member {
+            findings.push(Finding::new(
+                Severity::Error,
+                &workspace_toml,
+                line_of(&workspace_text, "members"),
+                format!("{} is not a workspace member, so {year} never builds", rel(root, dir)),
+            ));
+        }
+        findings.extend(check_crate_manifest(root, &dir.join("Cargo.toml"), &workspace_doc)?);
+        findings.extend(check_year_sources(meta, *year, &dir.join("src"))?);
+    }
+
+    findings.extend(check_templates(meta));
+
+    for f in findings.iter_mut() {
+        f.path = rel(root, &f.path);
+    }
+    findings.sort();
+    findings.dedup();
+
+    Ok(findings)
+}
+
+fn check_workspace_deps(root: &Utf8Path, path: &Utf8Path, text: &str, doc: &DocumentMut) -> Vec<Finding> {
+    let Some(deps) = doc["workspace"].get("dependencies").and_then(TomlItem::as_table_like) else {
+        return Vec::new();
+    };
+
+    deps.iter()
+        .filter_map(|(name, dep)| {
+            let dep_path = dep.get("path")?.as_str()?;
+            (!root.join(dep_path).exists()).then(|| {
+                Finding::new(
+                    Severity::Warning,
+                    path,
+                    key_line(text, name),
+                    format!("workspace dependency `{name}` points at `{dep_path}`, which does not exist"),
+                )
+            })
+        })
+        .collect()
+}
+
+fn check_crate_manifest(root: &Utf8Path, manifest: &Utf8Path, workspace: &DocumentMut) -> Result<Vec<Finding>> {
+    let text = read_to_string(manifest)?;
+    let doc = text.parse::<DocumentMut>()?;
+    let crate_dir = manifest.parent().unwrap_or(root);
+    let workspace_deps = workspace["workspace"].get("dependencies").and_then(TomlItem::as_table_like);
+
+    let Some(deps) = doc.get("dependencies").and_then(TomlItem::as_table_like) else {
+        return Ok(Vec::new());
+    };
+
+    let mut findings = Vec::new();
+    for (name, dep) in deps.iter() {
+        let line = key_line(&text, name);
+        let inherited = dep.get("workspace").and_then(|w| w.as_bool()).unwrap_or(false);
+        let missing_path = if inherited {
+            let Some(ws_dep) = workspace_deps.and_then(|d| d.get(name)) else {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    manifest,
+                    line,
+                    format!("`{name}` is inherited from the workspace, but the workspace doesn't define it"),
+                ));
+                continue;
+            };
+            ws_dep.get("path").and_then(|p| p.as_str()).map(|p| root.join(p))
+        } else {
+            dep.get("path").and_then(|p| p.as_str()).map(|p| crate_dir.join(p))
+        };
+
+        if let Some(dep_path) = missing_path.filter(|p| !p.exists()) {
+            findings.push(Finding::new(
+                Severity::Error,
+                manifest,
+                line,
+                format!("depends on `{name}` at `{}`, which does not exist", rel(root, &dep_path)),
+            ));
+        }
+    }
+
+    Ok(findings)
+}
+
+fn check_year_sources(meta: &WorkspaceMeta, year: u16, src_dir: &Utf8Path) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let commented_mod = Regex::new(r"^\s*//+\s*(?:pub\s+)?mod\s+(\w+)\s*;").unwrap();
+
+    let lib_path = src_dir.join("lib.rs");
+    let lib_text = read_to_string(&lib_path).unwrap_or_default();
+    let mut declared: BTreeSet<String> = BTreeSet::new();
+    if let Ok(lib) = syn::parse_file(&lib_text) {
+        declared.extend(lib.items.iter().filter_map(|i| match i {
+            Item::Mod(m) => Some(m.ident.to_string()),
+            _ => None,
+        }));
+    }
+    for (idx, line) in lib_text.lines().enumerate() {
+        if let Some(c) = commented_mod.captures(line) {
+            findings.push(Finding::new(
+                Severity::Warning,
+                &lib_path,
+                idx + 1,
+                format!("`mod {}` is commented out, so that day is never compiled or tested", &c[1]),
+            ));
+        }
+    }
+
+    let mut files: Vec<Utf8PathBuf> = src_dir.read_dir_utf8()?.flatten().map(|e| e.into_path()).collect();
+    files.sort();
+
+    let mut missing_inputs: Vec<u32> = Vec::new();
+    for path in files.iter().filter(|p| p.extension() == Some("rs")) {
+        let Some(stem) = path.file_stem() else {
+            continue;
+        };
+        let text = read_to_string(path)?;
+
+        let is_bin = meta
+            .worspace_data
+            .packages
+            .iter()
+            .flat_map(|p| p.targets.iter())
+            .any(|t| t.is_bin() && &t.src_path == path);
+        if !["lib", "main"].contains(&stem) && !declared.contains(stem) && !is_bin {
+            // Commented out declarations were already reported against lib.rs.
+            let commented_out = lib_text
+                .lines()
+                .any(|l| commented_mod.captures(l).is_some_and(|c| &c[1] == stem));
+            if !commented_out {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    path,
+                    1,
+                    "is not declared in lib.rs, so it is never compiled",
+                ));
+            }
+        }
+
+        let Ok(file) = syn::parse_file(&text) else {
+            continue;
+        };
+        for item in file.items.iter() {
+            let Item::Mod(module) = item else {
+                continue;
+            };
+            let Some(day) = aoc_day(module) else {
+                continue;
+            };
+            if !meta.get_input_file_for_day(&year, &(day as u8)).exists() {
+                missing_inputs.push(day);
+            }
+
+            findings.extend(check_placeholders(path, module));
+            findings.extend(check_tests(path, &text, &file.items, module.ident.span().start().line));
+        }
+    }
+
+    if !missing_inputs.is_empty() {
+        let input_dir = meta.get_input_folder_location().join(year.to_string());
+        findings.push(Finding::new(
+            Severity::Note,
+            &input_dir,
+            0,
+            format!(
+                "{year} has no input for day(s) {}; fetch them with `aoc input -y {year} -d <day>`",
+                missing_inputs.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")
+            ),
+        ));
+    }
+
+    Ok(findings)
+}
+
+fn aoc_day(module: &ItemMod) -> Option<u32> {
+    let attr = module.attrs.iter().find(|a| a.path().is_ident("aoc"))?;
+    parse_aoc_args(attr).ok().map(|(_, day)| day)
+}
+
+/// The placeholders among an `#[aoc]` module's functions.
+fn check_placeholders(path: &Utf8Path, module: &ItemMod) -> Vec<Finding> {
+    let Some((_, contents)) = &module.content else {
+        return Vec::new();
+    };
+    contents
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(f) => check_placeholder(path, f),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Registered generators, solvers and solutions that can't produce a real answer yet. The macro
+/// attribute can come after others, like `#[allow(..)]` or doc comments.
+fn check_placeholder(path: &Utf8Path, f: &ItemFn) -> Option<Finding> {
+    let kind = f
+        .attrs
+        .iter()
+        .filter_map(|a| a.path().get_ident().map(|i| i.to_string()))
+        .find(|k| ["generator", "solver", "solution"].contains(&k.as_str()))?;
+    let line = f.sig.ident.span().start().line;
+
+    let mut stubs = StubMacros::default();
+    stubs.visit_block(&f.block);
+    if let Some(name) = stubs.found {
+        return Some(Finding::new(
+            Severity::Warning,
+            path,
+            line,
+            format!("{kind} `{}` is a `{name}!()` stub", f.sig.ident),
+        ));
+    }
+
+    is_placeholder_body(&f.block).then(|| {
+        Finding::new(
+            Severity::Warning,
+            path,
+            line,
+            format!("{kind} `{}` returns a placeholder constant", f.sig.ident),
+        )
+    })
+}
+
+#[derive(Default)]
+struct StubMacros {
+    found: Option<String>,
+}
+
+impl<'ast> Visit<'ast> for StubMacros {
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if let Some(name) = mac.path.get_ident().map(|i| i.to_string()) {
+            if name == "todo" || name == "unimplemented" {
+                self.found.get_or_insert(name);
+            }
+        }
+    }
+}
+
+/// Bodies like `0`, `String::new()` or `Vec::new()`, which the day template starts out with. Other
+/// constants, like `42` or `"Merry Christmas"`, may well be the answer.
+fn is_placeholder_body(block: &Block) -> bool {
+    let [Stmt::Expr(expr, None)] = block.stmts.as_slice() else {
+        return false;
+    };
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_digits() == "0",
+            Lit::Float(float) => float.base10_parse::<f64>().is_ok_and(|f| f == 0.0),
+            Lit::Str(text) => text.value().is_empty(),
+            Lit::Bool(b) => !b.value,
+            _ => false,
+        },
+        Expr::Call(call) if call.args.is_empty() => matches!(
+            call.func.as_ref(),
+            Expr::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "new" || s.ident == "default")
+        ),
+        _ => false,
+    }
+}
+
+fn check_tests(path: &Utf8Path, text: &str, items: &[Item], mod_line: usize) -> Vec<Finding> {
+    let commented_test = Regex::new(r"^\s*//+\s*#\[(?:test|aoc_case)\b").unwrap();
+    let mut findings = Vec::new();
+
+    let live_tests = items
+        .iter()
+        .filter_map(|i| match i {
+            Item::Mod(m) if m.attrs.iter().any(|a| a.path().is_ident("cfg")) => m.content.as_ref(),
+            _ => None,
+        })
+        .flat_map(|(_, items)| items.iter())
+        .filter(|i| {
+            let attrs = match i {
+                Item::Fn(f) => f.attrs.clone(),
+                Item::Const(c) => c.attrs.clone(),
+                Item::Macro(m) => return m.mac.path.segments.last().is_some_and(|s| s.ident == "aoc_examples"),
+                Item::Verbatim(tokens) => leading_attrs(tokens),
+                _ => return false,
+            };
+            attrs.iter().any(|a| a.path().is_ident("test") || a.path().is_ident("aoc_case"))
+        })
+        .count();
+
+    let commented: Vec<usize> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| commented_test.is_match(l))
+        .map(|(idx, _)| idx + 1)
+        .collect();
+
+    if let Some(&first) = commented.first() {
+        findings.push(Finding::new(
+            Severity::Note,
+            path,
+            first,
+            format!("{} test(s) are commented out", commented.len()),
+        ));
+    }
+    if live_tests == 0 {
+        findings.push(Finding::new(Severity::Note, path, mod_line, "day has no tests"));
+    }
+
+    findings
+}
+
+/// The attributes in front of an item syn can't parse. A `const NAME: &str;` reading its input from
+/// `aoc_case(file = ..)` has no value, so it isn't a valid `Item::Const`.
+fn leading_attrs(tokens: &proc_macro2::TokenStream) -> Vec<Attribute> {
+    let attrs = |input: ParseStream| {
+        let attrs = input.call(Attribute::parse_outer)?;
+        input.parse::<proc_macro2::TokenStream>()?;
+        Ok(attrs)
+    };
+    attrs.parse2(tokens.clone()).unwrap_or_default()
+}
+
+/// The scaffolding templates must refer to the macro crate by the name it actually has in this workspace.
+fn check_templates(meta: &WorkspaceMeta) -> Vec<Finding> {
+    let Some(runner) = meta.worspace_data.packages.iter().find(|p| p.name == "aoc-runner") else {
+        return Vec::new();
+    };
+    let templates = runner.manifest_path.parent().unwrap().join("src").join("templates");
+    let macro_crates: Vec<String> = meta
+        .worspace_data
+        .workspace_packages()
+        .iter()
+        .filter(|p| p.targets.iter().any(|t| t.is_proc_macro()))
+        .map(|p| p.name.to_string())
+        .collect();
+    check_template_text(&templates, &macro_crates, DAY_RS, YEAR_CARGO_TEMPLATE)
+}
+
+/// Where the day template, `day_rs`, and the year manifest template, `year_cargo`, name a macro
+/// crate that isn't one of `macro_crates`.
+fn check_template_text(templates: &Utf8Path, macro_crates: &[String], day_rs: &str, year_cargo: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let uses_macros = Regex::new(r"use\s+(\w*macros\w*)::").unwrap();
+    for (idx, line) in day_rs.lines().enumerate() {
+        let Some(c) = uses_macros.captures(line) else {
+            continue;
+        };
+        if !macro_crates.iter().any(|m| m.replace('-', "_") == c[1]) {
+            findings.push(Finding::new(
+                Severity::Warning,
+                &templates.join("dayX.rs"),
+                idx + 1,
+                format!("day template imports `{}`, but the macro crate is `{}`", &c[1], macro_crates.join("`, `")),
+            ));
+        }
+    }
+
+    if let Ok(doc) = year_cargo.parse::<DocumentMut>() {
+        if let Some(deps) = doc.get("dependencies").and_then(TomlItem::as_table_like) {
+            for (name, _) in deps.iter().filter(|(n, _)| n.contains("macros")) {
+                if !macro_crates.iter().any(|m| m == name) {
+                    findings.push(Finding::new(
+                        Severity::Warning,
+                        &templates.join("year-Cargo.toml"),
+                        key_line(year_cargo, name),
+                        format!(
+                            "year template depends on `{name}`, but the macro crate is `{}`",
+                            macro_crates.join("`, `")
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+fn line_of(text: &str, needle: &str) -> usize {
+    text.lines().position(|l| l.trim_start().starts_with(needle)).map(|i| i + 1).unwrap_or(1)
+}
+
+/// The line a TOML key is set on, either as `name = ..` or dotted, as in `name.workspace = true`.
+fn key_line(text: &str, name: &str) -> usize {
+    text.lines()
+        .position(|l| {
+            l.trim_start()
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with(['=', '.']))
+        })
+        .map(|i| i + 1)
+        .unwrap_or(1)
+}
+
+fn rel(root: &Utf8Path, path: &Utf8Path) -> Utf8PathBuf {
+    path.strip_prefix(root).map(|p| p.to_owned()).unwrap_or(path.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The placeholder messages for every `#[aoc]` module in `text`.
+    fn placeholders(text: &str) -> Vec<String> {
+        let file = syn::parse_file(text).unwrap();
+        file.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Mod(module) if aoc_day(module).is_some() => Some(module),
+                _ => None,
+            })
+            .flat_map(|module| check_placeholders(Utf8Path::new("day_01.rs"), module))
+            .map(|finding| finding.message)
+            .collect()
+    }
+
+    #[test]
+    fn finished_day_has_no_placeholders() {
+        let text = indoc::indoc! {"
+            #[aoc(2023, day1)]
+            pub mod solutions {
+                #[generator(gen)]
+                pub fn input_generator(input: &str) -> Vec<u32> {
+                    input.lines().map(|line| line.parse().unwrap()).collect()
+                }
+
+                #[solver(part1, main)]
+                pub fn solve_part1(input: Vec<u32>) -> u32 {
+                    input.iter().sum()
+                }
+
+                // Helpers aren't registered, so they can return anything.
+                fn helper() -> u32 {
+                    0
+                }
+            }
+        "};
+        assert!(placeholders(text).is_empty());
+    }
+
+    #[test]
+    fn stubs_and_constants_are_placeholders() {
+        let text = indoc::indoc! {"
+            #[aoc(2023, day1)]
+            pub mod solutions {
+                #[generator(gen)]
+                pub fn input_generator(input: &str) -> Vec<u32> {
+                    todo!()
+                }
+
+                #[solver(part1, main)]
+                pub fn solve_part1(input: Vec<u32>) -> u32 {
+                    0
+                }
+
+                #[solution(part2, main)]
+                pub fn part2(input: &str) -> String {
+                    String::new()
+                }
+            }
+        "};
+        assert_eq!(
+            placeholders(text),
+            [
+                "generator `input_generator` is a `todo!()` stub",
+                "solver `solve_part1` returns a placeholder constant",
+                "solution `part2` returns a placeholder constant",
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholders_behind_other_attributes_are_found() {
+        let text = indoc::indoc! {"
+            #[aoc(2023, day1)]
+            pub mod solutions {
+                /// Not written yet.
+                #[allow(unused_variables)]
+                #[solver(part2, main)]
+                pub fn solve_part2(input: Vec<u32>) -> u32 {
+                    unimplemented!()
+                }
+            }
+        "};
+        assert_eq!(placeholders(text), ["solver `solve_part2` is a `unimplemented!()` stub"]);
+    }
+
+    #[test]
+    fn modules_without_aoc_are_skipped() {
+        let text = indoc::indoc! {"
+            pub mod helpers {
+                #[solver(part1, main)]
+                pub fn solve_part1(input: Vec<u32>) -> u32 {
+                    todo!()
+                }
+            }
+        "};
+        assert!(placeholders(text).is_empty());
+    }
+
+    #[test]
+    fn real_constant_answers_arent_placeholders() {
+        let text = indoc::indoc! {r#"
+            #[aoc(2023, day25)]
+            pub mod solutions {
+                #[solution(part1, main)]
+                pub fn part1(input: &str) -> u32 {
+                    42
+                }
+
+                #[solution(part2, main)]
+                pub fn part2(input: &str) -> &'static str {
+                    "Merry Christmas"
+                }
+
+                #[solution(part2, empty)]
+                pub fn part2_empty(input: &str) -> &'static str {
+                    ""
+                }
+            }
+        "#};
+        assert_eq!(placeholders(text), ["solution `part2_empty` returns a placeholder constant"]);
+    }
+
+    #[test]
+    fn aoc_days_are_read_like_the_macro_does() {
+        let day = |attr: &str| {
+            let module: ItemMod = syn::parse_str(&format!("{attr} mod solutions {{}}")).unwrap();
+            aoc_day(&module)
+        };
+        assert_eq!(day("#[aoc(2023, day5)]"), Some(5));
+        assert_eq!(day("#[aoc(2023, d12)]"), Some(12));
+        assert_eq!(day("#[aoc(day5)]"), None);
+        assert_eq!(day("#[cfg(test)]"), None);
+    }
+
+    /// The test findings for a day file.
+    fn test_findings(text: &str) -> Vec<String> {
+        let file = syn::parse_file(text).unwrap();
+        check_tests(Utf8Path::new("day_01.rs"), text, &file.items, 1)
+            .into_iter()
+            .map(|finding| format!("{}: {}", finding.line, finding.message))
+            .collect()
+    }
+
+    #[test]
+    fn cases_reading_files_are_tests() {
+        let text = indoc::indoc! {r#"
+            #[cfg(test)]
+            mod tests {
+                #[aoc_case(19114, file = "examples/2023/19/1.txt")]
+                const EXAMPLE: &str;
+            }
+        "#};
+        assert!(test_findings(text).is_empty());
+    }
+
+    #[test]
+    fn only_test_attributes_make_tests() {
+        let text = indoc::indoc! {r#"
+            #[cfg(test)]
+            mod tests {
+                /// Not an aoc_case, just a value-less const.
+                const EXAMPLE: &str;
+
+                // #[aoc_case(1, 2)]
+                const OTHER: &str = "";
+            }
+        "#};
+        assert_eq!(test_findings(text), ["6: 1 test(s) are commented out", "1: day has no tests"]);
+    }
+
+    #[test]
+    fn templates_must_name_the_macro_crate() {
+        let templates = Utf8Path::new("aoc-runner/src/templates");
+        let found = |crates: &[&str]| {
+            let crates: Vec<String> = crates.iter().map(|c| c.to_string()).collect();
+            check_template_text(templates, &crates, DAY_RS, YEAR_CARGO_TEMPLATE)
+                .into_iter()
+                .map(|finding| finding.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found(&["aoc-runner-macros"]),
+            [
+                "aoc-runner/src/templates/dayX.rs:1: day template imports `aoc_zen_runner_macros`, but the macro \
+                 crate is `aoc-runner-macros`",
+                "aoc-runner/src/templates/dayX.rs:39: day template imports `aoc_zen_runner_macros`, but the macro \
+                 crate is `aoc-runner-macros`",
+                "aoc-runner/src/templates/year-Cargo.toml:12: year template depends on `aoc-zen-runner-macros`, but \
+                 the macro crate is `aoc-runner-macros`",
+            ]
+        );
+        assert!(found(&["aoc-zen-runner-macros"]).is_empty());
+    }
+
+    #[test]
+    fn manifests_must_point_at_real_crates() {
+        let root = std::env::temp_dir().join(format!("aoc-doctor-{}", std::process::id()));
+        let root = Utf8PathBuf::from_path_buf(root).unwrap();
+        std::fs::create_dir_all(root.join("aoc_2023")).unwrap();
+        std::fs::create_dir_all(root.join("aoc_lib")).unwrap();
+
+        let workspace_text = indoc::indoc! {r#"
+            [workspace.dependencies]
+            aoc_lib = { path = "aoc_lib" }
+            common = { path = "common" }
+            itertools = "0.12"
+        "#};
+        let workspace_doc = workspace_text.parse::<DocumentMut>().unwrap();
+        let workspace_toml = root.join("Cargo.toml");
+        let workspace = check_workspace_deps(&root, &workspace_toml, workspace_text, &workspace_doc);
+        assert_eq!(workspace.len(), 1);
+        assert_eq!(
+            (workspace[0].severity, workspace[0].line, workspace[0].message.as_str()),
+            (Severity::Warning, 3, "workspace dependency `common` points at `common`, which does not exist")
+        );
+
+        let manifest = root.join("aoc_2023/Cargo.toml");
+        let manifest_text = indoc::indoc! {r#"
+            [dependencies]
+            aoc_lib.workspace = true
+            itertools.workspace = true
+            common.workspace = true
+            glam.workspace = true
+            helpers = { path = "../helpers" }
+            local = { path = "../aoc_lib" }
+        "#};
+        std::fs::write(&manifest, manifest_text).unwrap();
+        let findings: Vec<_> = check_crate_manifest(&root, &manifest, &workspace_doc)
+            .unwrap()
+            .into_iter()
+            .map(|finding| (finding.severity, finding.line, finding.message))
+            .collect();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            findings,
+            [
+                (Severity::Error, 4, "depends on `common` at `common`, which does not exist".to_string()),
+                (
+                    Severity::Error,
+                    5,
+                    "`glam` is inherited from the workspace, but the workspace doesn't define it".to_string()
+                ),
+                (Severity::Error, 6, "depends on `helpers` at `aoc_2023/../helpers`, which does not exist".to_string()),
+            ]
+        );
+    }
+}
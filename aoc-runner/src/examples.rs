@@ -0,0 +1,208 @@
+//! Example inputs kept under the workspace's `examples/<year>/<day>/` folder, which `aoc run
+//! --example` runs on and `aoc_examples!` turns into tests, so both check the same answers.
+//!
+//! `<name>.txt` holds the input, and `<name>.answers` beside it what each part should answer, as
+//! `#[aoc_case]` arguments one per line: `part1 = 32000000`, or just the answers in part order.
+
+use std::fs::read_to_string;
+
+use anyhow as ah;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+
+use crate::lang::PartAnswer;
+
+/// Where the example called `name` of a day lives.
+pub fn input_path(root: &Utf8Path, year: u16, day: u8, name: &str) -> Utf8PathBuf {
+    root.join("examples")
+        .join(year.to_string())
+        .join(day.to_string())
+        .join(format!("{name}.txt"))
+}
+
+/// The answers an example's input should give, if it has an answers file.
+pub fn expected_answers(input: &Utf8Path) -> ah::Result<Option<[Option<String>; 2]>> {
+    let path = input.with_extension("answers");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = read_to_string(&path)?;
+    parse_answers(&text)
+        .map(Some)
+        .map_err(|e| ah::anyhow!("In {path}: {e}"))
+}
+
+/// Each part's expected answer from an answers file. Expected errors, and the arguments that only
+/// matter to tests, like `variant` or `ignore`, aren't checked here.
+pub fn parse_answers(text: &str) -> ah::Result<[Option<String>; 2]> {
+    let mut expected = [None, None];
+    let mut positional = 0;
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    for arg in lines.flat_map(split_args) {
+        let named = arg
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .filter(|(name, _)| name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        let (slot, value) = match named {
+            Some(("part1", value)) => (0, value),
+            Some(("part2", value)) => (1, value),
+            Some(("variant" | "ignore" | "file", _)) => continue,
+            Some((name, _)) => return Err(ah::anyhow!("Unknown argument `{name}`")),
+            None if arg.starts_with("params(") => continue,
+            None if positional < 2 => {
+                positional += 1;
+                (positional - 1, arg)
+            }
+            None => return Err(ah::anyhow!("More than two answers given, at `{arg}`")),
+        };
+        if value.starts_with("Err(") {
+            continue;
+        }
+        if expected[slot].replace(answer_text(value)).is_some() {
+            return Err(ah::anyhow!("part{} is given more than once", slot + 1));
+        }
+    }
+
+    if expected.iter().all(Option::is_none) {
+        return Err(ah::anyhow!("No answers given, eg. `part1 = 42`"));
+    }
+    Ok(expected)
+}
+
+/// Splits a line on the commas between arguments, leaving those inside strings and brackets.
+fn split_args(line: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let (mut depth, mut in_string, mut escaped, mut start) = (0, false, false, 0);
+    for (idx, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                args.push(line[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(line[start..].trim());
+    args.retain(|arg| !arg.is_empty());
+    args
+}
+
+const INT_SUFFIXES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// How a solution prints an answer written as a Rust literal: strings without their quotes, and
+/// numbers without `_` separators or a type suffix.
+fn answer_text(literal: &str) -> String {
+    if let Some(text) = literal.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        return text.replace("\\\"", "\"").replace("\\\\", "\\");
+    }
+    if literal.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+        let digits: String = literal.chars().filter(|&c| c != '_').collect();
+        return INT_SUFFIXES
+            .iter()
+            .find_map(|suffix| digits.strip_suffix(suffix))
+            .unwrap_or(&digits)
+            .to_string();
+    }
+    literal.to_string()
+}
+
+/// Every printed answer that isn't the expected one for its part, as `(answer, expected)`.
+pub fn mismatches<'a>(expected: &'a [Option<String>; 2], answers: &'a [PartAnswer]) -> Vec<(&'a PartAnswer, &'a str)> {
+    answers
+        .iter()
+        .filter_map(|answer| {
+            let wanted = expected.get(usize::from(answer.part).checked_sub(1)?)?.as_deref()?;
+            (answer.answer != wanted).then_some((answer, wanted))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(part1: Option<&str>, part2: Option<&str>) -> [Option<String>; 2] {
+        [part1.map(String::from), part2.map(String::from)]
+    }
+
+    #[test]
+    fn named_and_positional_answers_parse() {
+        assert_eq!(
+            parse_answers("part1 = 32000000\n").unwrap(),
+            answers(Some("32000000"), None)
+        );
+        assert_eq!(parse_answers("part2 = 5,\n").unwrap(), answers(None, Some("5")));
+        assert_eq!(parse_answers("142, 281\n").unwrap(), answers(Some("142"), Some("281")));
+        assert_eq!(
+            parse_answers("# comment\n\n142\npart2 = 281\n").unwrap(),
+            answers(Some("142"), Some("281"))
+        );
+    }
+
+    #[test]
+    fn answers_are_written_as_the_solution_prints_them() {
+        assert_eq!(
+            parse_answers("part1 = 1_000u64\n").unwrap(),
+            answers(Some("1000"), None)
+        );
+        assert_eq!(parse_answers("part1 = -5i32\n").unwrap(), answers(Some("-5"), None));
+        assert_eq!(
+            parse_answers("\"a, b\", \"say \\\"hi\\\"\"\n").unwrap(),
+            answers(Some("a, b"), Some("say \"hi\""))
+        );
+    }
+
+    #[test]
+    fn test_only_arguments_and_expected_errors_are_skipped() {
+        let text = "part1 = 4\npart2 = Err(\"no path\")\nvariant = \"fast\"\nignore = \"slow\"\nparams(steps = 6, size = (2, 3))\n";
+        assert_eq!(parse_answers(text).unwrap(), answers(Some("4"), None));
+    }
+
+    #[test]
+    fn malformed_answers_are_errors() {
+        assert!(parse_answers("").is_err());
+        assert!(parse_answers("part3 = 1\n").is_err());
+        assert!(parse_answers("1, 2, 3\n").is_err());
+        assert!(parse_answers("part1 = 1\npart1 = 2\n").is_err());
+    }
+
+    #[test]
+    fn mismatches_compare_each_part_to_its_answer() {
+        let answer = |part, label: &str, answer: &str| PartAnswer {
+            part,
+            label: label.into(),
+            answer: answer.into(),
+            elapsed: None,
+        };
+        let printed = [answer(1, "main", "4"), answer(1, "fast", "5"), answer(2, "main", "9")];
+        let expected = answers(Some("4"), None);
+        let found = mismatches(&expected, &printed);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].0.label.as_str(), found[0].1), ("fast", "4"));
+    }
+
+    #[test]
+    fn examples_live_by_year_and_day() {
+        assert_eq!(
+            input_path(Utf8Path::new("/ws"), 2023, 20, "1"),
+            Utf8Path::new("/ws/examples/2023/20/1.txt")
+        );
+    }
+
+    #[test]
+    fn the_repo_examples_have_answers() {
+        let root = Utf8Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let expected = expected_answers(&input_path(root, 2023, 20, "1")).unwrap();
+        assert_eq!(expected, Some(answers(Some("32000000"), None)));
+        assert_eq!(expected_answers(&input_path(root, 2023, 20, "missing")).unwrap(), None);
+    }
+}
@@ -0,0 +1,1643 @@
+//! Runtime side of the `main` that `#[aoc]` generates.
+//!
+//! Every solution runs in a child process (the same binary, re-executed) so that a runaway
+//! variant is reported as `TIMEOUT` or `OOM` instead of blocking the rest of the day.
+
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    hint::black_box,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::{self, Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    alloc::{self, format_bytes, AllocStats},
+    params, trace,
+    viz::{self, DREW},
+};
+
+/// Wall-clock limit per solution, in seconds, or `none`.
+pub const TIMEOUT_VAR: &str = "AOC_TIMEOUT";
+/// CPU time limit per solution, in seconds, or `none`.
+pub const CPU_TIME_VAR: &str = "AOC_CPU_TIME";
+/// Address-space limit per solution, in bytes, or `none`.
+pub const MEMORY_VAR: &str = "AOC_MEMORY_LIMIT";
+
+/// Benchmark each solution for this many seconds instead of running it once.
+pub const BENCH_VAR: &str = "AOC_BENCH";
+/// Path to write a JSON `DayReport` to.
+pub const JSON_VAR: &str = "AOC_JSON";
+/// Run each generator once and share its output across both parts' solvers.
+pub const SHARED_VAR: &str = "AOC_SHARED";
+/// Only run this part.
+pub const PART_VAR: &str = "AOC_PART";
+/// Only run the variants with this label, or this generator or solver slug.
+pub const VARIANT_VAR: &str = "AOC_VARIANT";
+/// Also run variants tagged `slow`, in runs, tests and benchmarks.
+pub const INCLUDE_SLOW_VAR: &str = "AOC_INCLUDE_SLOW";
+/// Leave out variants tagged `experimental`, as `aoc verify` does.
+pub const SKIP_EXPERIMENTAL_VAR: &str = "AOC_SKIP_EXPERIMENTAL";
+
+/// What `run_day` prints before the slow variants of a part it skipped.
+pub const SKIPPING_SLOW: &str = "Skipping slow ";
+
+const ISOLATED_VAR: &str = "AOC_ISOLATED_SOLUTION";
+const ISOLATED_DAY_VAR: &str = "AOC_ISOLATED_DAY";
+const RESULT_MARKER: &str = "\u{1e}AOC_RESULT ";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_SAMPLES: usize = 10_000;
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+/// A `Stage` as `(kind, label, elapsed_ns, alloc)`, without the owned strings.
+type RawStage = (&'static str, &'static str, u64, Option<AllocStats>);
+
+/// Stages recorded so far. Kept allocation-free while recording, so the bookkeeping doesn't show
+/// up in the solution's own heap statistics.
+static STAGES: Mutex<Vec<RawStage>> = Mutex::new(Vec::new());
+const MAX_STAGES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub wall: Option<Duration>,
+    pub cpu: Option<Duration>,
+    pub memory: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            wall: Some(DEFAULT_TIMEOUT),
+            cpu: None,
+            memory: None,
+        }
+    }
+}
+
+impl Limits {
+    /// Reads limits from the environment, falling back to the defaults for unset variables.
+    pub fn from_env() -> Limits {
+        let defaults = Limits::default();
+        Limits {
+            wall: env_limit(TIMEOUT_VAR).map_or(defaults.wall, |secs| secs.map(Duration::from_secs_f64)),
+            cpu: env_limit(CPU_TIME_VAR).map_or(defaults.cpu, |secs| secs.map(Duration::from_secs_f64)),
+            memory: env_limit(MEMORY_VAR).map_or(defaults.memory, |bytes| bytes.map(|b| b as u64)),
+        }
+    }
+
+    /// Passes these limits on to a child process, in the format `from_env` reads.
+    pub fn apply_env(&self, cmd: &mut Command) {
+        let fmt_secs = |d: Option<Duration>| d.map_or("none".to_string(), |d| d.as_secs_f64().to_string());
+        cmd.env(TIMEOUT_VAR, fmt_secs(self.wall))
+            .env(CPU_TIME_VAR, fmt_secs(self.cpu))
+            .env(MEMORY_VAR, self.memory.map_or("none".to_string(), |m| m.to_string()));
+    }
+}
+
+/// `None` if the variable is unset, `Some(None)` if it explicitly disables the limit.
+fn env_limit(name: &str) -> Option<Option<f64>> {
+    let value = env::var(name).ok()?;
+    match value.trim() {
+        "" | "none" | "0" => Some(None),
+        v => match v.parse::<f64>() {
+            Ok(n) => Some(Some(n)),
+            Err(_) => {
+                eprintln!("Ignoring unparseable {name}={value}");
+                None
+            }
+        },
+    }
+}
+
+/// Parses byte counts like `4096`, `512M` or `2GiB`.
+pub fn parse_memory(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (value, unit) = text.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("`{text}` doesn't start with a number"))?;
+    let scale: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        other => return Err(format!("Unknown memory unit `{other}`")),
+    };
+
+    Ok(value * scale)
+}
+
+/// Everything besides the environment that changes how `run_day` runs solutions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    pub limits: Limits,
+    /// Sample each solution repeatedly for this long instead of running it once.
+    pub bench: Option<Duration>,
+    /// Count heap allocations. Only the runner looks at this, to build with `alloc-stats`.
+    pub alloc_stats: bool,
+    /// Where the day process writes its JSON report.
+    pub json: Option<PathBuf>,
+    /// Parse once per generator, timing it apart from the solvers, instead of once per variant.
+    pub shared: bool,
+    /// Puzzle parameters to override, as `(name, value)`. The day process reads these through
+    /// `params::get`, so `from_env` leaves them empty.
+    pub params: Vec<(String, String)>,
+    /// Only run this part.
+    pub part: Option<u8>,
+    /// Only run the variants matching this, as `label_matches` decides.
+    pub variant: Option<String>,
+    /// Record the frames solutions push to a `viz::Recorder`, and draw them.
+    pub viz: Option<viz::Settings>,
+    /// Where to write a Chrome trace of every generator, solver and solution span.
+    pub trace: Option<PathBuf>,
+    /// Also run variants tagged `slow`.
+    pub include_slow: bool,
+    /// Leave out variants tagged `experimental`.
+    pub skip_experimental: bool,
+}
+
+impl RunOptions {
+    pub fn from_env() -> RunOptions {
+        RunOptions {
+            limits: Limits::from_env(),
+            bench: env_limit(BENCH_VAR).flatten().map(Duration::from_secs_f64),
+            alloc_stats: alloc::ENABLED,
+            json: env::var_os(JSON_VAR).map(PathBuf::from),
+            shared: env::var_os(SHARED_VAR).is_some(),
+            params: Vec::new(),
+            part: env::var(PART_VAR).ok().and_then(|part| part.parse().ok()),
+            variant: env::var(VARIANT_VAR).ok(),
+            viz: viz::Settings::from_env(),
+            trace: env::var_os(trace::TRACE_VAR).map(PathBuf::from),
+            include_slow: env::var_os(INCLUDE_SLOW_VAR).is_some(),
+            skip_experimental: env::var_os(SKIP_EXPERIMENTAL_VAR).is_some(),
+        }
+    }
+
+    /// Whether the variant `label` of `part` is one to run. A slow variant picked by name runs
+    /// even without `include_slow`.
+    pub fn selects(&self, part: u8, label: &str, tags: VariantTags) -> bool {
+        self.part.is_none_or(|wanted| wanted == part)
+            && match self.variant.as_deref() {
+                Some(variant) => label_matches(label, variant),
+                None => !tags.slow || self.include_slow,
+            }
+            && !(tags.experimental && self.skip_experimental)
+    }
+
+    /// Passes these options on to a child process, in the format `from_env` reads.
+    pub fn apply_env(&self, cmd: &mut Command) {
+        self.limits.apply_env(cmd);
+        if let Some(bench) = self.bench {
+            cmd.env(BENCH_VAR, bench.as_secs_f64().to_string());
+        }
+        if let Some(json) = &self.json {
+            cmd.env(JSON_VAR, json);
+        }
+        if self.shared {
+            cmd.env(SHARED_VAR, "1");
+        }
+        for (name, value) in &self.params {
+            cmd.env(params::env_var(name), value);
+        }
+        if let Some(part) = self.part {
+            cmd.env(PART_VAR, part.to_string());
+        }
+        if let Some(variant) = &self.variant {
+            cmd.env(VARIANT_VAR, variant);
+        }
+        if let Some(viz) = &self.viz {
+            viz.apply_env(cmd);
+        }
+        if let Some(trace) = &self.trace {
+            cmd.env(trace::TRACE_VAR, trace);
+        }
+        if self.include_slow {
+            cmd.env(INCLUDE_SLOW_VAR, "1");
+        }
+        if self.skip_experimental {
+            cmd.env(SKIP_EXPERIMENTAL_VAR, "1");
+        }
+    }
+}
+
+/// One generator or solver call inside a composed solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stage {
+    pub kind: String,
+    pub label: String,
+    pub elapsed_ns: u64,
+    pub alloc: Option<AllocStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    /// The single run's time, or the median sample when benchmarking.
+    pub elapsed_ns: u64,
+    pub alloc: Option<AllocStats>,
+    pub stages: Vec<Stage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub samples_ns: Vec<u64>,
+}
+
+impl Measurement {
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_ns)
+    }
+}
+
+/// An `Err` returned by a generator, solver or solution, tagged with where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveError {
+    /// `generator`, `solver` or `solution`.
+    pub kind: String,
+    pub label: String,
+    pub message: String,
+}
+
+impl SolveError {
+    /// Keeps the error's message, with the cause chain for errors that print one with `{:#}`.
+    pub fn new(kind: &str, label: &str, error: impl Display) -> SolveError {
+        SolveError {
+            kind: kind.to_string(),
+            label: label.to_string(),
+            message: format!("{error:#}"),
+        }
+    }
+
+    /// A miette-style report: the failing stage, then the error message underneath.
+    pub fn diagnostic(&self) -> String {
+        let mut lines = self.message.lines();
+        let mut text = format!("  × {} `{}` failed", self.kind, self.label);
+        if let Some(first) = lines.next() {
+            text.push_str(&format!("\n  ╰─▶ {first}"));
+        }
+        for line in lines {
+            text.push_str(&format!("\n      {line}"));
+        }
+        text
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} `{}` failed: {}", self.kind, self.label, self.message)
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// A composed solution, as generated by `#[aoc]`.
+pub type Solution<R> = fn(&str) -> Result<R, SolveError>;
+
+/// How one solution's child process ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Outcome {
+    Answer { answer: String, measurement: Measurement },
+    Error(SolveError),
+    Timeout,
+    OutOfMemory,
+    Crashed { message: String },
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Answer { answer, .. } => write!(f, "{answer}"),
+            Outcome::Error(_) => write!(f, "ERROR"),
+            Outcome::Timeout => write!(f, "TIMEOUT"),
+            Outcome::OutOfMemory => write!(f, "OOM"),
+            Outcome::Crashed { message } => write!(f, "CRASHED: {message}"),
+        }
+    }
+}
+
+/// Why a day's run failed, which its `main` exits with so that scripts, and `aoc run`, can tell the
+/// cases apart. When several happen, the last listed here wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DayFailure {
+    /// An alternative's answer differs from its part's primary answer.
+    Mismatch,
+    /// A variant returned an `Err`, ran out of time or ran out of memory.
+    Failed,
+    /// A variant panicked, or its process died some other way.
+    Panicked,
+    /// There was no input to run on.
+    NoInput,
+}
+
+impl DayFailure {
+    const ALL: [DayFailure; 4] = [
+        DayFailure::Mismatch,
+        DayFailure::Failed,
+        DayFailure::Panicked,
+        DayFailure::NoInput,
+    ];
+
+    /// Clear of 1 for errors in general, 2 for usage errors and 101 for panics in `main` itself.
+    pub fn code(self) -> i32 {
+        match self {
+            DayFailure::Mismatch => 3,
+            DayFailure::Failed => 4,
+            DayFailure::Panicked => 5,
+            DayFailure::NoInput => 6,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Option<DayFailure> {
+        DayFailure::ALL.into_iter().find(|failure| failure.code() == code)
+    }
+
+    pub fn exit(self) -> ! {
+        process::exit(self.code())
+    }
+
+    fn of(outcome: &Outcome) -> Option<DayFailure> {
+        match outcome {
+            Outcome::Answer { .. } => None,
+            Outcome::Error(_) | Outcome::Timeout | Outcome::OutOfMemory => Some(DayFailure::Failed),
+            Outcome::Crashed { .. } => Some(DayFailure::Panicked),
+        }
+    }
+}
+
+impl Display for DayFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayFailure::Mismatch => write!(f, "variants disagree"),
+            DayFailure::Failed => write!(f, "a variant failed"),
+            DayFailure::Panicked => write!(f, "a variant panicked"),
+            DayFailure::NoInput => write!(f, "no input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantResult {
+    pub part: u8,
+    pub label: String,
+    pub primary: bool,
+    pub outcome: Outcome,
+}
+
+/// The JSON report written when `AOC_JSON` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayReport {
+    pub year: u32,
+    pub day: u32,
+    pub benchmark: bool,
+    pub variants: Vec<VariantResult>,
+    /// Parse times, when generators ran once for both parts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generators: Vec<GeneratorResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// An alternative that answered differently from the variant its part was checked against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mismatch {
+    pub part: u8,
+    pub label: String,
+    pub answer: String,
+    pub primary_label: String,
+    pub primary_answer: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratorResult {
+    pub label: String,
+    pub measurement: Measurement,
+}
+
+/// How a variant is tagged in its attributes, eg. `#[solver(part2, brute, slow)]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VariantTags {
+    /// Skipped unless slow variants are asked for, or it's picked by name.
+    pub slow: bool,
+    /// Left out of `aoc verify`.
+    pub experimental: bool,
+}
+
+impl VariantTags {
+    /// Whether the variant runs where variants aren't picked by name, like in tests and
+    /// benchmarks: slow ones only with `AOC_INCLUDE_SLOW` set.
+    pub fn included(&self) -> bool {
+        !self.slow || env::var_os(INCLUDE_SLOW_VAR).is_some()
+    }
+
+    /// The tags as listed after a label, eg. ` (slow, experimental)`, or nothing.
+    pub fn suffix(&self) -> String {
+        let tags: Vec<_> = [(self.slow, "slow"), (self.experimental, "experimental")]
+            .into_iter()
+            .filter_map(|(set, tag)| set.then_some(tag))
+            .collect();
+        match tags.is_empty() {
+            true => String::new(),
+            false => format!(" ({})", tags.join(", ")),
+        }
+    }
+}
+
+/// Everything `#[aoc]` generates for a day, as handed to `run_day`. Each part's variants are
+/// listed primary first.
+pub struct DaySolutions<R1: 'static, R2: 'static> {
+    pub p1_labels: &'static [&'static str],
+    pub p1_tags: &'static [VariantTags],
+    pub p1_solutions: &'static [Solution<R1>],
+    pub p2_labels: &'static [&'static str],
+    pub p2_tags: &'static [VariantTags],
+    pub p2_solutions: &'static [Solution<R2>],
+    pub shared: &'static [SharedGenerator],
+}
+
+impl<R1, R2> DaySolutions<R1, R2> {
+    /// A part's labels and tags.
+    fn variants(&self, part: u8) -> (&'static [&'static str], &'static [VariantTags]) {
+        match part {
+            1 => (self.p1_labels, self.p1_tags),
+            _ => (self.p2_labels, self.p2_tags),
+        }
+    }
+
+    /// Whether the options select the variant at `index` of `part`.
+    fn selects(&self, options: &RunOptions, part: u8, index: usize) -> bool {
+        let (labels, tags) = self.variants(part);
+        options.selects(part, labels[index], tags[index])
+    }
+}
+
+/// A generator run once, feeding every solver of either part that accepts its output. Also used
+/// for `#[solution]`s of both parts, which have nothing to parse.
+pub struct SharedGenerator {
+    pub label: &'static str,
+    /// The `(part, index)` of each composed solution this covers.
+    pub variants: &'static [(u8, usize)],
+    pub run: fn(&str, &mut SharedRun),
+}
+
+/// What one `SharedGenerator` produced: the parse, then each solver's result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedRun {
+    pub parse: Option<Result<Measurement, SolveError>>,
+    pub variants: Vec<VariantOutcome>,
+    /// The `(part, index)` of variants not selected, whose solvers aren't run.
+    #[serde(skip)]
+    pub skipped: Vec<(u8, usize)>,
+}
+
+/// A variant's part, its index in that part's solutions, and how it went.
+pub type VariantOutcome = (u8, usize, Outcome);
+
+impl SharedRun {
+    /// Runs the generator, keeping its time and heap use. `None` if it failed.
+    pub fn generate<T>(&mut self, f: impl FnOnce() -> Result<T, SolveError>) -> Option<T> {
+        let (result, measurement) = Self::measure(f);
+        match result {
+            Ok(parsed) => {
+                self.parse = Some(Ok(measurement));
+                Some(parsed)
+            }
+            Err(error) => {
+                self.parse = Some(Err(error));
+                None
+            }
+        }
+    }
+
+    /// Runs one solver on the already parsed input, timing just the solver.
+    pub fn solve<R: Display>(&mut self, part: u8, index: usize, f: impl FnOnce() -> Result<R, SolveError>) {
+        if self.skipped.contains(&(part, index)) {
+            return;
+        }
+        let (result, measurement) = Self::measure(f);
+        let outcome = match result {
+            Ok(answer) => Outcome::Answer {
+                answer: answer.to_string(),
+                measurement,
+            },
+            Err(error) => Outcome::Error(error),
+        };
+        self.variants.push((part, index, outcome));
+    }
+
+    /// Runs a solver for both parts once, giving each part the same time.
+    pub fn solve_both<R1: Display, R2: Display>(
+        &mut self,
+        p1_index: usize,
+        p2_index: usize,
+        f: impl FnOnce() -> Result<(R1, R2), SolveError>,
+    ) {
+        if self.skipped.contains(&(1, p1_index)) && self.skipped.contains(&(2, p2_index)) {
+            return;
+        }
+        let (result, measurement) = Self::measure(f);
+        let (p1, p2) = match result {
+            Ok((p1, p2)) => (
+                Outcome::Answer {
+                    answer: p1.to_string(),
+                    measurement: measurement.clone(),
+                },
+                Outcome::Answer {
+                    answer: p2.to_string(),
+                    measurement,
+                },
+            ),
+            Err(error) => (Outcome::Error(error.clone()), Outcome::Error(error)),
+        };
+        self.variants.push((1, p1_index, p1));
+        self.variants.push((2, p2_index, p2));
+    }
+
+    fn measure<T>(f: impl FnOnce() -> T) -> (T, Measurement) {
+        let start = Instant::now();
+        let (result, alloc) = alloc::measure(f);
+        let measurement = Measurement {
+            elapsed_ns: start.elapsed().as_nanos() as u64,
+            alloc,
+            ..Measurement::default()
+        };
+        (result, measurement)
+    }
+}
+
+/// Another copy of a generator's output for a solver that consumes or mutates it. Through
+/// autoref specialization, `(&Reuse(&parsed)).copy()` clones where the type is `Clone` and
+/// otherwise returns `None`, so the generated code parses again instead.
+pub struct Reuse<'a, T>(pub &'a T);
+
+pub trait ReuseClone<T> {
+    fn copy(&self) -> Option<T>;
+}
+
+impl<T: Clone> ReuseClone<T> for Reuse<'_, T> {
+    fn copy(&self) -> Option<T> {
+        Some(self.0.clone())
+    }
+}
+
+pub trait ReuseFallback<T> {
+    fn copy(&self) -> Option<T>;
+}
+
+impl<T> ReuseFallback<T> for &Reuse<'_, T> {
+    fn copy(&self) -> Option<T> {
+        None
+    }
+}
+
+/// Whether a variant's label is the one asked for: either the whole `generator / solver` label, or
+/// just its generator or solver slug.
+pub fn label_matches(label: &str, wanted: &str) -> bool {
+    label == wanted || label.split(" / ").any(|slug| slug == wanted)
+}
+
+/// Checks one solution's result against an `#[aoc_case]` expecting `Err(..)`: that it failed, and
+/// with a message containing `message` if one is given.
+pub fn expect_error<R: Display>(part: u8, label: &str, result: Result<R, SolveError>, message: Option<&str>) {
+    let error = match result {
+        Ok(answer) => panic!("Part {part} Test failed solution: {label} should have failed, but found {answer}"),
+        Err(error) => error,
+    };
+    if let Some(message) = message {
+        assert!(
+            error.message.contains(message),
+            "Part {part} Test failed solution: {label} failed with `{error}`, expected an error containing {message:?}"
+        );
+    }
+}
+
+/// Entry point for the generated `main`. Runs every solution in isolation and prints the
+/// primary answer per part, followed by how each alternative compared to it, and which of them
+/// disagreed. `main` exits with the failure's code.
+pub fn run_day<R1: Display, R2: Display>(
+    year: u32,
+    day: u32,
+    input: &str,
+    solutions: &DaySolutions<R1, R2>,
+) -> Result<(), DayFailure> {
+    run_day_with(year, day, input, solutions, &RunOptions::from_env())
+}
+
+/// `run_day` with the options given rather than read from the environment, for dispatchers that
+/// take their own arguments. Only the selected parts and variants run.
+pub fn run_day_with<R1: Display, R2: Display>(
+    year: u32,
+    day: u32,
+    input: &str,
+    solutions: &DaySolutions<R1, R2>,
+    options: &RunOptions,
+) -> Result<(), DayFailure> {
+    // A solution process reports its result through its output, not its exit code.
+    if let Some(request) = isolated_request() {
+        let _trace = trace::install(options.trace.as_deref(), &request.to_string());
+        let stem = |part: u8, label: &str| viz::file_stem(year, day, part, label);
+        match request {
+            Isolated::Solution(1, index) => run_child(
+                options,
+                solutions.p1_solutions[index],
+                input,
+                &stem(1, solutions.p1_labels[index]),
+            ),
+            Isolated::Solution(_, index) => run_child(
+                options,
+                solutions.p2_solutions[index],
+                input,
+                &stem(2, solutions.p2_labels[index]),
+            ),
+            Isolated::Generator(index) => run_shared_child(options, solutions, index, input),
+        }
+        return Ok(());
+    }
+
+    println!("## AOC {}, Day {} ----------", year, day);
+    if input.is_empty() {
+        println!("No input found.");
+        return Err(DayFailure::NoInput);
+    }
+
+    let mut shared = HashMap::new();
+    let mut generators = Vec::new();
+    if options.shared {
+        for (index, generator) in solutions.shared.iter().enumerate() {
+            let wanted = generator
+                .variants
+                .iter()
+                .any(|&(part, index)| solutions.selects(options, part, index));
+            if !wanted {
+                continue;
+            }
+            let (parse, outcomes) = run_shared((year, day), index, generator, options);
+            if let Some(measurement) = parse {
+                println!("Generator {} ({:?})", generator.label, measurement.elapsed());
+                print_details(&measurement);
+                generators.push(GeneratorResult {
+                    label: generator.label.to_string(),
+                    measurement,
+                });
+            }
+            shared.extend(
+                outcomes
+                    .into_iter()
+                    .map(|(part, index, outcome)| ((part, index), outcome)),
+            );
+        }
+    }
+    // Anything a shared generator didn't cover, like `#[solution]`s, runs on its own.
+    let mut outcome = |part: u8, index: usize| {
+        shared
+            .remove(&(part, index))
+            .unwrap_or_else(|| run_isolated((year, day), Isolated::Solution(part, index), options))
+    };
+
+    let mut variants = run_part(1, solutions, options, &mut outcome);
+    variants.extend(run_part(2, solutions, options, &mut outcome));
+    let mismatches = mismatches(&variants);
+    print_mismatches(&mismatches);
+    let failure = variants
+        .iter()
+        .filter_map(|variant| DayFailure::of(&variant.outcome))
+        .chain((!mismatches.is_empty()).then_some(DayFailure::Mismatch))
+        .max();
+
+    if let Some(path) = &options.json {
+        let report = DayReport {
+            year,
+            day,
+            benchmark: options.bench.is_some(),
+            variants,
+            generators,
+            mismatches,
+        };
+        let written = serde_json::to_string_pretty(&report)
+            .map_err(io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = written {
+            eprintln!("Could not write report to {}: {e}", path.display());
+        }
+    }
+    if let Some(path) = &options.trace {
+        write_trace(path, solutions);
+    }
+
+    failure.map_or(Ok(()), Err)
+}
+
+/// Merges the trace parts the solution processes wrote, one trace process per variant in the
+/// order they're listed, then the shared generators.
+fn write_trace<R1, R2>(path: &Path, solutions: &DaySolutions<R1, R2>) {
+    let variants = |part: u8, labels: &'static [&'static str]| {
+        labels.iter().enumerate().map(move |(index, label)| {
+            (
+                Isolated::Solution(part, index).to_string(),
+                format!("part {part}: {label}"),
+            )
+        })
+    };
+    let generators = solutions.shared.iter().enumerate().map(|(index, generator)| {
+        (
+            Isolated::Generator(index).to_string(),
+            format!("shared: {}", generator.label),
+        )
+    });
+    let requests: Vec<_> = variants(1, solutions.p1_labels)
+        .chain(variants(2, solutions.p2_labels))
+        .chain(generators)
+        .collect();
+
+    match trace::merge(path, &requests) {
+        Ok(()) => println!("{}{}", trace::WROTE, path.display()),
+        Err(e) => eprintln!("Could not write trace to {}: {e}", path.display()),
+    }
+}
+
+/// The alternatives whose answer differs from the first variant run for their part, when that
+/// one has an answer to compare with.
+fn mismatches(variants: &[VariantResult]) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    for part in [1, 2] {
+        let mut results = variants.iter().filter(|variant| variant.part == part);
+        let Some(primary) = results.next() else {
+            continue;
+        };
+        let Outcome::Answer {
+            answer: primary_answer, ..
+        } = &primary.outcome
+        else {
+            continue;
+        };
+        for variant in results {
+            if let Outcome::Answer { answer, .. } = &variant.outcome {
+                if answer != primary_answer {
+                    mismatches.push(Mismatch {
+                        part,
+                        label: variant.label.clone(),
+                        answer: answer.clone(),
+                        primary_label: primary.label.clone(),
+                        primary_answer: primary_answer.clone(),
+                    });
+                }
+            }
+        }
+    }
+    mismatches
+}
+
+/// One line per disagreeing alternative, after both parts have run.
+fn print_mismatches(mismatches: &[Mismatch]) {
+    if mismatches.is_empty() {
+        return;
+    }
+    println!(
+        "❌ {} variant(s) disagree with their part's primary answer:",
+        mismatches.len()
+    );
+    for mismatch in mismatches {
+        println!(
+            "    part {}: `{}` answered {}, primary `{}` answered {}",
+            mismatch.part, mismatch.label, mismatch.answer, mismatch.primary_label, mismatch.primary_answer
+        );
+    }
+}
+
+/// Runs one shared generator in its own process. When the generator fails or the process dies,
+/// every variant it covers gets that as its outcome.
+fn run_shared(
+    day: (u32, u32),
+    index: usize,
+    generator: &SharedGenerator,
+    options: &RunOptions,
+) -> (Option<Measurement>, Vec<VariantOutcome>) {
+    let run = spawn_isolated(day, Isolated::Generator(index), options)
+        .and_then(|ended| classify::<SharedRun>(&ended, &options.limits));
+    let (parse, outcomes) = match run {
+        Ok(SharedRun {
+            parse: Some(Ok(measurement)),
+            variants,
+            ..
+        }) => (Some(measurement), variants),
+        Ok(SharedRun {
+            parse: Some(Err(error)),
+            ..
+        }) => (None, with_outcome(generator, Outcome::Error(error))),
+        // Nothing to parse for a `#[solution]` answering both parts.
+        Ok(SharedRun {
+            parse: None, variants, ..
+        }) => (None, variants),
+        // One solver crashing or running out of time takes down everything sharing its input.
+        Err(failure) => (None, with_outcome(generator, failure)),
+    };
+
+    (parse, outcomes)
+}
+
+fn with_outcome(generator: &SharedGenerator, outcome: Outcome) -> Vec<VariantOutcome> {
+    generator
+        .variants
+        .iter()
+        .map(|&(part, index)| (part, index, outcome.clone()))
+        .collect()
+}
+
+/// Runs the selected variants of one part. The first is reported as the part's answer and the rest
+/// are checked against it. Variants only left out for being slow are mentioned first.
+fn run_part<R1, R2>(
+    part: u8,
+    solutions: &DaySolutions<R1, R2>,
+    options: &RunOptions,
+    outcome: &mut impl FnMut(u8, usize) -> Outcome,
+) -> Vec<VariantResult> {
+    let (labels, tags) = solutions.variants(part);
+    let slow: Vec<_> = labels
+        .iter()
+        .zip(tags)
+        .filter(|&(label, &tags)| {
+            !options.selects(part, label, tags) && options.selects(part, label, VariantTags { slow: false, ..tags })
+        })
+        .map(|(label, _)| format!("`{label}`"))
+        .collect();
+    if !slow.is_empty() {
+        println!(
+            "{SKIPPING_SLOW}part {part} variant(s) {}, run with --include-slow to include them",
+            slow.join(", ")
+        );
+    }
+
+    let mut selected = labels
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| solutions.selects(options, part, index));
+    let Some((primary_idx, primary_label)) = selected.next() else {
+        return Vec::new();
+    };
+    let alternatives: Vec<_> = selected.collect();
+
+    let primary = outcome(part, primary_idx);
+    match &primary {
+        Outcome::Answer { answer, measurement } => {
+            println!(
+                "Part {part}, {primary_label} Solution ({:?}): {answer}",
+                measurement.elapsed()
+            );
+            print_details(measurement);
+        }
+        Outcome::Error(error) => {
+            println!("Part {part}, {primary_label} Solution: {primary}");
+            println!("{}", error.diagnostic());
+        }
+        other => println!("Part {part}, {primary_label} Solution: {other}"),
+    }
+
+    let mut results = Vec::new();
+    if !alternatives.is_empty() {
+        println!("Checking alternative Part {part} solutions...");
+        for (idx, label) in alternatives {
+            let outcome = outcome(part, idx);
+            match (&outcome, &primary) {
+                (Outcome::Answer { answer, measurement }, Outcome::Answer { answer: expected, .. })
+                    if answer == expected =>
+                {
+                    println!("✅ {label} ({:?})", measurement.elapsed());
+                    print_details(measurement);
+                }
+                (Outcome::Answer { answer, measurement }, _) => {
+                    println!("Solver {label} found {answer}");
+                    print_details(measurement);
+                }
+                (Outcome::Error(error), _) => {
+                    println!("Solver {label}: {outcome}");
+                    println!("{}", error.diagnostic());
+                }
+                (other, _) => println!("Solver {label}: {other}"),
+            }
+            results.push(VariantResult {
+                part,
+                label: label.to_string(),
+                primary: false,
+                outcome,
+            });
+        }
+        println!();
+    }
+
+    results.insert(
+        0,
+        VariantResult {
+            part,
+            label: primary_label.to_string(),
+            primary: primary_idx == 0,
+            outcome: primary,
+        },
+    );
+    results
+}
+
+/// Indented lines under an answer with sample and heap statistics, when there are any.
+fn print_details(measurement: &Measurement) {
+    if measurement.alloc.is_none() && measurement.samples_ns.is_empty() {
+        return;
+    }
+
+    if let (Some(&min), Some(&max)) = (measurement.samples_ns.first(), measurement.samples_ns.last()) {
+        let mean = measurement.samples_ns.iter().sum::<u64>() / measurement.samples_ns.len() as u64;
+        println!(
+            "    {} samples: min {:?}, mean {:?}, max {:?}",
+            measurement.samples_ns.len(),
+            Duration::from_nanos(min),
+            Duration::from_nanos(mean),
+            Duration::from_nanos(max)
+        );
+    }
+    if let Some(alloc) = &measurement.alloc {
+        println!("    heap: {}", format_alloc(alloc));
+    }
+    for stage in measurement.stages.iter() {
+        let elapsed = Duration::from_nanos(stage.elapsed_ns);
+        match &stage.alloc {
+            Some(alloc) => println!(
+                "    {} {} ({elapsed:?}): {}",
+                stage.kind,
+                stage.label,
+                format_alloc(alloc)
+            ),
+            None => println!("    {} {} ({elapsed:?})", stage.kind, stage.label),
+        }
+    }
+}
+
+fn format_alloc(alloc: &AllocStats) -> String {
+    format!(
+        "peak {}, {} over {} allocations",
+        format_bytes(alloc.peak_bytes),
+        format_bytes(alloc.total_bytes),
+        alloc.allocations
+    )
+}
+
+/// Times (and with `alloc-stats`, measures heap use of) one generator or solver call. The
+/// composed solutions `#[aoc]` generates wrap each of their calls in this.
+pub fn stage<T>(kind: &'static str, label: &'static str, f: impl FnOnce() -> T) -> T {
+    if !RECORDING.load(Ordering::Relaxed) {
+        return f();
+    }
+
+    let start = Instant::now();
+    let (result, alloc) = alloc::measure(f);
+    let elapsed_ns = start.elapsed().as_nanos() as u64;
+    let mut stages = STAGES.lock().unwrap();
+    if stages.len() < stages.capacity() {
+        stages.push((kind, label, elapsed_ns, alloc));
+    }
+
+    result
+}
+
+/// What a re-executed day binary should run instead of the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Isolated {
+    /// One variant of one part.
+    Solution(u8, usize),
+    /// One shared generator and all its solvers.
+    Generator(usize),
+}
+
+impl Display for Isolated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Isolated::Solution(part, index) => write!(f, "{part}:{index}"),
+            Isolated::Generator(index) => write!(f, "gen:{index}"),
+        }
+    }
+}
+
+/// The `(year, day)` a solution process was started for, so a binary holding several days can
+/// hand the request to the right one.
+pub fn isolated_day() -> Option<(u32, u32)> {
+    let (year, day) = env::var(ISOLATED_DAY_VAR)
+        .ok()?
+        .split_once('/')
+        .map(|(y, d)| (y.parse(), d.parse()))?;
+    Some((year.ok()?, day.ok()?))
+}
+
+fn isolated_request() -> Option<Isolated> {
+    let request = env::var(ISOLATED_VAR).ok()?;
+    match request.split_once(':')? {
+        ("gen", index) => Some(Isolated::Generator(index.parse().ok()?)),
+        (part, index) => Some(Isolated::Solution(part.parse().ok()?, index.parse().ok()?)),
+    }
+}
+
+/// Runs one solution and prints its outcome for the parent. Under `aoc viz`, also draws what it
+/// recorded to a file named `viz_stem`.
+fn run_child<R: Display>(options: &RunOptions, solution: Solution<R>, input: &str, viz_stem: &str) {
+    apply_rlimits(&options.limits);
+
+    // Only the first run records stages, heap use and frames; benchmark samples just time.
+    STAGES.lock().unwrap().reserve_exact(MAX_STAGES);
+    if options.viz.is_some() {
+        viz::start();
+    }
+    RECORDING.store(true, Ordering::Relaxed);
+    let start = Instant::now();
+    let (answer, alloc) = alloc::measure(|| solution(input));
+    let elapsed_ns = start.elapsed().as_nanos() as u64;
+    RECORDING.store(false, Ordering::Relaxed);
+    if let Some(settings) = &options.viz {
+        let frames = viz::finish();
+        if !frames.is_empty() {
+            match viz::render(&frames, settings, viz_stem) {
+                Ok(path) => println!("{DREW}{} frame(s) to {}", frames.len(), path.display()),
+                Err(e) => eprintln!("Could not draw {viz_stem}: {e:#}"),
+            }
+        }
+    }
+
+    let outcome = match answer {
+        Ok(answer) => {
+            let mut measurement = Measurement {
+                elapsed_ns,
+                alloc,
+                stages: STAGES
+                    .lock()
+                    .unwrap()
+                    .drain(..)
+                    .map(|(kind, label, elapsed_ns, alloc)| Stage {
+                        kind: kind.to_string(),
+                        label: label.to_string(),
+                        elapsed_ns,
+                        alloc,
+                    })
+                    .collect(),
+                samples_ns: Vec::new(),
+            };
+
+            if let Some(budget) = options.bench {
+                let mut samples = vec![elapsed_ns];
+                let bench_start = Instant::now();
+                while bench_start.elapsed() < budget && samples.len() < MAX_SAMPLES {
+                    let start = Instant::now();
+                    let _ = black_box(solution(black_box(input)));
+                    samples.push(start.elapsed().as_nanos() as u64);
+                }
+                samples.sort_unstable();
+                measurement.elapsed_ns = samples[samples.len() / 2];
+                measurement.samples_ns = samples;
+            }
+
+            Outcome::Answer {
+                answer: answer.to_string(),
+                measurement,
+            }
+        }
+        // Nothing to benchmark when the solution fails.
+        Err(error) => Outcome::Error(error),
+    };
+
+    let mut stdout = io::stdout().lock();
+    let json = serde_json::to_string(&outcome).unwrap_or_default();
+    let _ = write!(stdout, "{RESULT_MARKER}{json}");
+    let _ = stdout.flush();
+}
+
+fn run_shared_child<R1, R2>(options: &RunOptions, solutions: &DaySolutions<R1, R2>, index: usize, input: &str) {
+    apply_rlimits(&options.limits);
+
+    let generator = &solutions.shared[index];
+    let mut run = SharedRun {
+        skipped: generator
+            .variants
+            .iter()
+            .copied()
+            .filter(|&(part, index)| !solutions.selects(options, part, index))
+            .collect(),
+        ..SharedRun::default()
+    };
+    (generator.run)(input, &mut run);
+
+    let mut stdout = io::stdout().lock();
+    let json = serde_json::to_string(&run).unwrap_or_default();
+    let _ = write!(stdout, "{RESULT_MARKER}{json}");
+    let _ = stdout.flush();
+}
+
+#[cfg(unix)]
+fn apply_rlimits(limits: &Limits) {
+    // SIGXCPU arrives at the soft limit; the hard limit is a SIGKILL backstop.
+    if let Some(cpu) = limits.cpu {
+        let secs = cpu_limit_secs(cpu) as libc::rlim_t;
+        let limit = libc::rlimit {
+            rlim_cur: secs,
+            rlim_max: secs + 1,
+        };
+        unsafe { libc::setrlimit(libc::RLIMIT_CPU, &limit) };
+    }
+    if let Some(memory) = limits.memory {
+        let limit = libc::rlimit {
+            rlim_cur: memory as libc::rlim_t,
+            rlim_max: memory as libc::rlim_t,
+        };
+        unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) };
+    }
+}
+
+#[cfg(not(unix))]
+fn apply_rlimits(_limits: &Limits) {}
+
+/// The whole seconds of CPU time `RLIMIT_CPU` is set to for a `cpu` limit.
+fn cpu_limit_secs(cpu: Duration) -> u64 {
+    cpu.as_secs().max(1)
+}
+
+/// Re-executes the current binary to run a single solution, enforcing the wall-clock limit here
+/// and the CPU and memory limits in the child.
+fn run_isolated(day: (u32, u32), request: Isolated, options: &RunOptions) -> Outcome {
+    spawn_isolated(day, request, options)
+        .and_then(|ended| classify(&ended, &options.limits))
+        .unwrap_or_else(|failure| failure)
+}
+
+/// How a solution process ended, with what it takes to tell which limit stopped it.
+struct Ended {
+    /// `None` when it was killed for running past the wall-clock limit.
+    status: Option<ExitStatus>,
+    /// The CPU time it used, where the platform reports it.
+    cpu: Option<Duration>,
+    stdout: String,
+    stderr: String,
+}
+
+/// How the child ended, or `Err` when it couldn't be run or waited for.
+fn spawn_isolated((year, day): (u32, u32), request: Isolated, options: &RunOptions) -> Result<Ended, Outcome> {
+    let spawned = env::current_exe().and_then(|exe| {
+        let mut cmd = Command::new(exe);
+        // A dispatcher needs its arguments again, for anything like an input file that isn't baked in.
+        cmd.args(env::args_os().skip(1));
+        options.apply_env(&mut cmd);
+        cmd.env(ISOLATED_VAR, request.to_string())
+            .env(ISOLATED_DAY_VAR, format!("{year}/{day}"))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+    });
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            return Err(Outcome::Crashed {
+                message: format!("could not start solution process: {e}"),
+            })
+        }
+    };
+
+    // Drain both pipes while waiting, so chatty solvers can't block on a full pipe.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let start = Instant::now();
+    let mut poll = Duration::from_millis(1);
+    let (status, cpu) = loop {
+        match try_wait(&mut child) {
+            Ok(Some((status, cpu))) => break (Some(status), cpu),
+            Ok(None) if options.limits.wall.is_some_and(|wall| start.elapsed() >= wall) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break (None, None);
+            }
+            Ok(None) => {
+                thread::sleep(poll);
+                poll = (poll * 2).min(Duration::from_millis(50));
+            }
+            Err(e) => {
+                return Err(Outcome::Crashed {
+                    message: format!("could not wait for solution process: {e}"),
+                })
+            }
+        }
+    };
+
+    Ok(Ended {
+        status,
+        cpu,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Like `Child::try_wait`, but along with the CPU time the child used.
+#[cfg(unix)]
+fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    match unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, libc::WNOHANG, &mut usage) } {
+        0 => Ok(None),
+        -1 => match io::Error::last_os_error() {
+            e if e.kind() == io::ErrorKind::Interrupted => Ok(None),
+            e => Err(e),
+        },
+        _ => {
+            let time = |t: libc::timeval| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000);
+            let cpu = time(usage.ru_utime) + time(usage.ru_stime);
+            Ok(Some((ExitStatus::from_raw(status), Some(cpu))))
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    Ok(child.try_wait()?.map(|status| (status, None)))
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut text);
+        }
+        text
+    })
+}
+
+/// Reads the result the child printed, or works out from its exit why there wasn't one. Running out
+/// of time or memory is only reported when the limit for it is what stopped the child.
+fn classify<T: DeserializeOwned>(ended: &Ended, limits: &Limits) -> Result<T, Outcome> {
+    let Ended {
+        status,
+        cpu,
+        stdout,
+        stderr,
+    } = ended;
+    // Killed here for running past the wall-clock limit.
+    let Some(status) = status else {
+        return Err(Outcome::Timeout);
+    };
+    if status.success() {
+        let Some((noise, result)) = stdout.rsplit_once(RESULT_MARKER) else {
+            return Err(Outcome::Crashed {
+                message: "solution process exited without an answer".to_string(),
+            });
+        };
+        // Pass the solver's own printing through.
+        print!("{noise}");
+        eprint!("{stderr}");
+
+        return serde_json::from_str(result).map_err(|e| Outcome::Crashed {
+            message: format!("unreadable result from solution process: {e}"),
+        });
+    }
+
+    // An allocation refused under the memory limit aborts with this.
+    if stderr.contains("memory allocation of") {
+        return Err(Outcome::OutOfMemory);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        let cpu_limit_hit = limits
+            .cpu
+            .zip(*cpu)
+            .is_some_and(|(limit, used)| used >= Duration::from_secs(cpu_limit_secs(limit)));
+        match status.signal() {
+            Some(libc::SIGXCPU) => return Err(Outcome::Timeout),
+            // The CPU limit's hard limit, past the soft one's SIGXCPU. Any other SIGKILL, like the
+            // kernel's OOM killer, is reported as the crash it is.
+            Some(libc::SIGKILL) if cpu_limit_hit => return Err(Outcome::Timeout),
+            _ => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = (cpu, limits);
+
+    Err(Outcome::Crashed {
+        message: panic_message(stderr).unwrap_or_else(|| status.to_string()),
+    })
+}
+
+fn panic_message(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    lines.by_ref().find(|l| l.contains("panicked at"))?;
+    lines.next().map(str::to_string)
+}
+
+/// How one variant fared, as read back from the generated `main`'s report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariantStatus {
+    Primary(String),
+    Agrees,
+    Differs(String),
+    /// The solution returned an `Err`, with its message.
+    Errored(String),
+    Timeout,
+    OutOfMemory,
+    Crashed(String),
+}
+
+impl VariantStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, VariantStatus::Primary(_) | VariantStatus::Agrees)
+    }
+}
+
+impl Display for VariantStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariantStatus::Primary(answer) => write!(f, "primary: {answer}"),
+            VariantStatus::Agrees => write!(f, "OK"),
+            VariantStatus::Differs(answer) => write!(f, "MISMATCH: found {answer}"),
+            VariantStatus::Errored(msg) => write!(f, "ERROR: {msg}"),
+            VariantStatus::Timeout => write!(f, "TIMEOUT"),
+            VariantStatus::OutOfMemory => write!(f, "OOM"),
+            VariantStatus::Crashed(msg) => write!(f, "CRASHED: {msg}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantReport {
+    pub part: u8,
+    pub label: String,
+    pub elapsed: Option<Duration>,
+    pub status: VariantStatus,
+}
+
+impl VariantReport {
+    /// Every variant in a day's JSON report, each alternative checked against the first variant run
+    /// for its part.
+    pub fn from_day_report(report: &DayReport) -> Vec<VariantReport> {
+        let mut reports = Vec::new();
+        for part in [1, 2] {
+            let mut variants = report.variants.iter().filter(|variant| variant.part == part);
+            let Some(primary) = variants.next() else {
+                continue;
+            };
+            let expected = match &primary.outcome {
+                Outcome::Answer { answer, .. } => Some(answer),
+                _ => None,
+            };
+            reports.push(VariantReport::new(primary, VariantStatus::Primary));
+            for variant in variants {
+                reports.push(VariantReport::new(variant, |answer| match Some(&answer) == expected {
+                    true => VariantStatus::Agrees,
+                    false => VariantStatus::Differs(answer),
+                }));
+            }
+        }
+        reports
+    }
+
+    /// A variant's report, with `answered` giving the status of an answer.
+    fn new(variant: &VariantResult, answered: impl FnOnce(String) -> VariantStatus) -> VariantReport {
+        let (elapsed, status) = match &variant.outcome {
+            Outcome::Answer { answer, measurement } => (Some(measurement.elapsed()), answered(answer.clone())),
+            Outcome::Error(error) => (None, VariantStatus::Errored(error.to_string())),
+            Outcome::Timeout => (None, VariantStatus::Timeout),
+            Outcome::OutOfMemory => (None, VariantStatus::OutOfMemory),
+            Outcome::Crashed { message } => (None, VariantStatus::Crashed(message.clone())),
+        };
+        VariantReport {
+            part: variant.part,
+            label: variant.label.clone(),
+            elapsed,
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(part: u8, label: &str, outcome: Outcome) -> VariantResult {
+        VariantResult {
+            part,
+            label: label.to_string(),
+            primary: false,
+            outcome,
+        }
+    }
+
+    fn answer(answer: &str, elapsed_ns: u64) -> Outcome {
+        Outcome::Answer {
+            answer: answer.to_string(),
+            measurement: Measurement {
+                elapsed_ns,
+                ..Measurement::default()
+            },
+        }
+    }
+
+    fn report(variants: Vec<VariantResult>) -> DayReport {
+        DayReport {
+            year: 2023,
+            day: 1,
+            benchmark: false,
+            variants,
+            generators: Vec::new(),
+            mismatches: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn alternatives_are_checked_against_their_parts_first_variant() {
+        let report = report(vec![
+            variant(1, "gen / main", answer("42", 1_000)),
+            variant(1, "gen / fast", answer("42", 500)),
+            variant(1, "gen / wrong", answer("41", 500)),
+            variant(2, "main", answer("TIMEOUT", 2_000)),
+            variant(2, "alt", answer("TIMEOUT", 2_000)),
+        ]);
+        let statuses: Vec<_> = VariantReport::from_day_report(&report)
+            .into_iter()
+            .map(|report| (report.part, report.label, report.elapsed, report.status))
+            .collect();
+        let nanos = |ns| Some(Duration::from_nanos(ns));
+        assert_eq!(
+            statuses,
+            [
+                (1, "gen / main".into(), nanos(1_000), VariantStatus::Primary("42".into())),
+                (1, "gen / fast".into(), nanos(500), VariantStatus::Agrees),
+                (1, "gen / wrong".into(), nanos(500), VariantStatus::Differs("41".into())),
+                // An answer that looks like a failure is still an answer.
+                (2, "main".into(), nanos(2_000), VariantStatus::Primary("TIMEOUT".into())),
+                (2, "alt".into(), nanos(2_000), VariantStatus::Agrees),
+            ]
+        );
+    }
+
+    #[test]
+    fn experimental_variants_are_skipped_when_asked() {
+        let experimental = VariantTags { slow: false, experimental: true };
+        assert!(RunOptions::default().selects(1, "gen / wip", experimental));
+
+        let skipping = RunOptions { skip_experimental: true, ..RunOptions::default() };
+        assert!(!skipping.selects(1, "gen / wip", experimental));
+        assert!(skipping.selects(1, "gen / main", VariantTags::default()));
+        // Even when picked by name.
+        let picked = RunOptions { variant: Some("wip".into()), ..skipping };
+        assert!(!picked.selects(1, "gen / wip", experimental));
+    }
+
+    #[test]
+    fn slow_variants_run_when_asked_or_picked() {
+        let slow = VariantTags { slow: true, experimental: false };
+        assert!(!RunOptions::default().selects(2, "brute_force", slow));
+        let including = RunOptions { include_slow: true, ..RunOptions::default() };
+        assert!(including.selects(2, "brute_force", slow));
+        let picked = RunOptions { variant: Some("brute_force".into()), ..RunOptions::default() };
+        assert!(picked.selects(2, "brute_force", slow));
+        let other_part = RunOptions { part: Some(1), ..picked };
+        assert!(!other_part.selects(2, "brute_force", slow));
+    }
+
+    #[test]
+    fn expected_errors_pass_on_a_matching_error() {
+        let error = || Err::<u32, _>(SolveError::new("generator", "gen", "unexpected input at `1, 2`"));
+        expect_error(1, "gen / main", error(), None);
+        expect_error(1, "gen / main", error(), Some("unexpected input"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Part 2 Test failed solution: gen / main should have failed, but found 42")]
+    fn expected_errors_fail_on_an_answer() {
+        expect_error(2, "gen / main", Ok::<_, SolveError>(42), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected an error containing \"no path\"")]
+    fn expected_errors_fail_on_another_error() {
+        let error = SolveError::new("solver", "main", "out of range");
+        expect_error(1, "gen / main", Err::<u32, _>(error), Some("no path"));
+    }
+
+    #[test]
+    fn failures_keep_their_outcome() {
+        let error = SolveError::new("solver", "main", "no path found");
+        let report = report(vec![
+            variant(1, "main", Outcome::Error(error)),
+            variant(1, "alt", answer("42", 1)),
+            variant(2, "main", Outcome::Timeout),
+            variant(2, "alt", Outcome::OutOfMemory),
+            variant(2, "bad", Outcome::Crashed { message: "index out of bounds".into() }),
+        ]);
+        let statuses: Vec<_> = VariantReport::from_day_report(&report)
+            .into_iter()
+            .map(|report| report.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                VariantStatus::Errored("solver `main` failed: no path found".into()),
+                // Nothing to agree with.
+                VariantStatus::Differs("42".into()),
+                VariantStatus::Timeout,
+                VariantStatus::OutOfMemory,
+                VariantStatus::Crashed("index out of bounds".into()),
+            ]
+        );
+        assert!(statuses.iter().all(|status| !status.is_ok()));
+    }
+
+    #[cfg(unix)]
+    mod classify {
+        use std::os::unix::process::ExitStatusExt;
+
+        use super::*;
+
+        fn killed_by(signal: i32) -> Option<ExitStatus> {
+            Some(ExitStatus::from_raw(signal))
+        }
+
+        fn exited_with(code: i32) -> Option<ExitStatus> {
+            Some(ExitStatus::from_raw(code << 8))
+        }
+
+        fn ended(status: Option<ExitStatus>, cpu: Option<Duration>, stdout: &str, stderr: &str) -> Ended {
+            Ended {
+                status,
+                cpu,
+                stdout: stdout.to_string(),
+                stderr: stderr.to_string(),
+            }
+        }
+
+        fn limits(cpu: Option<u64>, memory: Option<u64>) -> Limits {
+            Limits {
+                wall: Some(Duration::from_secs(10)),
+                cpu: cpu.map(Duration::from_secs),
+                memory,
+            }
+        }
+
+        fn outcome(ended: &Ended, limits: &Limits) -> Outcome {
+            classify::<u32>(ended, limits).unwrap_err()
+        }
+
+        #[test]
+        fn result_is_read_after_the_solutions_output() {
+            let ended = ended(exited_with(0), None, &format!("debug\n{RESULT_MARKER}42"), "");
+            assert_eq!(classify::<u32>(&ended, &limits(None, None)), Ok(42));
+        }
+
+        #[test]
+        fn wall_clock_kill_is_a_timeout() {
+            let ended = ended(None, None, "", "");
+            assert_eq!(outcome(&ended, &limits(None, None)), Outcome::Timeout);
+        }
+
+        #[test]
+        fn cpu_soft_limit_is_a_timeout() {
+            let ended = ended(killed_by(libc::SIGXCPU), Some(Duration::from_secs(2)), "", "");
+            assert_eq!(outcome(&ended, &limits(Some(2), None)), Outcome::Timeout);
+        }
+
+        #[test]
+        fn cpu_hard_limit_kill_is_a_timeout() {
+            let ended = ended(killed_by(libc::SIGKILL), Some(Duration::from_millis(3_010)), "", "");
+            assert_eq!(outcome(&ended, &limits(Some(2), Some(1 << 30))), Outcome::Timeout);
+        }
+
+        #[test]
+        fn other_kills_are_crashes() {
+            let crashed = |outcome| matches!(outcome, Outcome::Crashed { .. });
+            // Well under the CPU limit.
+            let under = ended(killed_by(libc::SIGKILL), Some(Duration::from_millis(100)), "", "");
+            assert!(crashed(outcome(&under, &limits(Some(2), None))));
+            // No CPU limit to have hit, even with a memory limit.
+            let unlimited = ended(killed_by(libc::SIGKILL), Some(Duration::from_secs(5)), "", "");
+            assert!(crashed(outcome(&unlimited, &limits(None, Some(1 << 30)))));
+        }
+
+        #[test]
+        fn failed_allocation_is_out_of_memory() {
+            let stderr = "memory allocation of 1073741824 bytes failed\n";
+            let ended = ended(killed_by(libc::SIGABRT), Some(Duration::from_millis(10)), "", stderr);
+            assert_eq!(outcome(&ended, &limits(None, Some(1 << 20))), Outcome::OutOfMemory);
+        }
+
+        #[test]
+        fn panics_are_crashes_with_their_message() {
+            let stderr = "\nthread 'main' panicked at src/day_01.rs:10:5:\nno solution found\n";
+            let ended = ended(exited_with(101), Some(Duration::ZERO), "", stderr);
+            let message = "no solution found".to_string();
+            assert_eq!(outcome(&ended, &limits(None, None)), Outcome::Crashed { message });
+        }
+    }
+}
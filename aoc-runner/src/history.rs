@@ -0,0 +1,398 @@
+//! Local benchmark history, and regression checks against earlier commits or named baselines.
+
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, read_to_string, OpenOptions},
+    io::Write,
+    process::Command,
+};
+
+use anyhow as ah;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use chrono::Utc;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    alloc::AllocStats,
+    harness::{DayReport, Outcome},
+};
+
+/// Stored samples are thinned to this many evenly spaced quantiles to keep the history small.
+const STORED_SAMPLES: usize = 500;
+/// Two-sided p-value below which a change counts as real.
+const SIGNIFICANCE: f64 = 0.05;
+/// The normal approximation in `mann_whitney_p` is meaningless below this many samples a side.
+const MIN_SAMPLES: usize = 8;
+
+/// One benchmarked variant, as stored in the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub commit: String,
+    /// Whether the working tree had uncommitted changes.
+    pub dirty: bool,
+    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<String>,
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub label: String,
+    /// Whether this is the variant `aoc run` reports as the day's answer.
+    #[serde(default)]
+    pub primary: bool,
+    pub median_ns: u64,
+    pub samples_ns: Vec<u64>,
+    pub alloc: Option<AllocStats>,
+}
+
+impl BenchRecord {
+    pub fn key(&self) -> RecordKey<'_> {
+        (self.year, self.day, self.part, &self.label)
+    }
+
+    /// Records for every variant in `report` which produced an answer.
+    pub fn from_report(report: &DayReport, commit: &GitState, baseline: Option<&str>) -> Vec<BenchRecord> {
+        let date = Utc::now().to_rfc3339();
+        report
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                let Outcome::Answer { measurement, .. } = &variant.outcome else {
+                    return None;
+                };
+                Some(BenchRecord {
+                    commit: commit.commit.clone(),
+                    dirty: commit.dirty,
+                    date: date.clone(),
+                    baseline: baseline.map(str::to_string),
+                    year: report.year,
+                    day: report.day,
+                    part: variant.part,
+                    label: variant.label.clone(),
+                    primary: variant.primary,
+                    median_ns: measurement.elapsed_ns,
+                    samples_ns: thin_samples(&measurement.samples_ns),
+                    alloc: measurement.alloc,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Evenly spaced quantiles of already-sorted samples.
+fn thin_samples(samples: &[u64]) -> Vec<u64> {
+    if samples.len() <= STORED_SAMPLES {
+        return samples.to_vec();
+    }
+    (0..STORED_SAMPLES)
+        .map(|i| samples[i * (samples.len() - 1) / (STORED_SAMPLES - 1)])
+        .collect()
+}
+
+/// Append-only JSON lines file of `BenchRecord`s.
+pub struct HistoryStore {
+    pub path: Utf8PathBuf,
+}
+
+impl HistoryStore {
+    pub fn for_workspace(root: &Utf8Path) -> HistoryStore {
+        HistoryStore {
+            path: root.join(".aoc").join("bench-history.jsonl"),
+        }
+    }
+
+    pub fn load(&self) -> ah::Result<Vec<BenchRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        read_to_string(&self.path)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| serde_json::from_str(line).map_err(|e| ah::anyhow!("{}:{}: {}", self.path, idx + 1, e)))
+            .collect()
+    }
+
+    pub fn append(&self, records: &[BenchRecord]) -> ah::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        for record in records {
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct GitState {
+    pub commit: String,
+    pub dirty: bool,
+}
+
+impl GitState {
+    /// The checked-out commit, or `unknown` outside a git repository.
+    pub fn current(root: &Utf8Path) -> GitState {
+        let commit = resolve_rev(root, "HEAD").unwrap_or_else(|| "unknown".to_string());
+        let dirty = Command::new("git")
+            .args(["status", "--porcelain", "--untracked-files=no"])
+            .current_dir(root)
+            .output()
+            .is_ok_and(|out| !out.stdout.is_empty());
+
+        GitState { commit, dirty }
+    }
+}
+
+/// Full commit hash for a revision, eg. `HEAD~3` or `main`.
+pub fn resolve_rev(root: &Utf8Path, rev: &str) -> Option<String> {
+    let out = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{rev}^{{commit}}"))
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Identifies a variant across runs: year, day, part and label.
+pub type RecordKey<'r> = (u32, u32, u8, &'r str);
+
+/// Saved results to compare a new run against.
+pub struct Reference<'r> {
+    pub description: String,
+    pub latest: HashMap<RecordKey<'r>, &'r BenchRecord>,
+}
+
+/// The most recent record per variant from a named baseline, or else from a git revision.
+pub fn select_reference<'r>(records: &'r [BenchRecord], root: &Utf8Path, reference: &str) -> ah::Result<Reference<'r>> {
+    let is_baseline = records.iter().any(|r| r.baseline.as_deref() == Some(reference));
+    let commit = match is_baseline {
+        true => None,
+        false => Some(resolve_rev(root, reference).ok_or(ah::anyhow!(
+            "`{reference}` is neither a saved baseline nor a git revision"
+        ))?),
+    };
+    let description = match &commit {
+        None => format!("baseline `{reference}`"),
+        Some(commit) => format!("commit {commit:.10}"),
+    };
+
+    // History is append-only, so later lines are newer.
+    let mut latest = HashMap::new();
+    for record in records.iter().filter(|r| match &commit {
+        None => r.baseline.as_deref() == Some(reference),
+        Some(commit) => &r.commit == commit,
+    }) {
+        latest.insert(record.key(), record);
+    }
+
+    Ok(Reference { description, latest })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Slower,
+    Faster,
+    Unchanged,
+    TooFewSamples,
+}
+
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub before_ns: u64,
+    pub after_ns: u64,
+    /// Relative change of the median, eg. `0.25` for 25% slower.
+    pub change: f64,
+    pub p_value: f64,
+    pub verdict: Verdict,
+}
+
+/// Compares two sample sets. A change only counts when it is both statistically significant
+/// and larger than `threshold` (a fraction, eg. `0.05`).
+pub fn compare_samples(before: &BenchRecord, after: &BenchRecord, threshold: f64) -> Comparison {
+    let change = after.median_ns as f64 / before.median_ns.max(1) as f64 - 1.0;
+    let p_value = mann_whitney_p(&before.samples_ns, &after.samples_ns);
+    let verdict = if before.samples_ns.len().min(after.samples_ns.len()) < MIN_SAMPLES {
+        Verdict::TooFewSamples
+    } else if p_value >= SIGNIFICANCE || change.abs() < threshold {
+        Verdict::Unchanged
+    } else if change > 0.0 {
+        Verdict::Slower
+    } else {
+        Verdict::Faster
+    };
+
+    Comparison {
+        before_ns: before.median_ns,
+        after_ns: after.median_ns,
+        change,
+        p_value,
+        verdict,
+    }
+}
+
+/// Two-sided p-value of the Mann-Whitney U test, using the normal approximation with a tie
+/// correction. Timing samples are rarely normal, which rules out a t-test.
+pub fn mann_whitney_p(a: &[u64], b: &[u64]) -> f64 {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    if a.is_empty() || b.is_empty() {
+        return 1.0;
+    }
+
+    let mut all: Vec<(u64, bool)> = a
+        .iter()
+        .map(|&v| (v, true))
+        .chain(b.iter().map(|&v| (v, false)))
+        .collect();
+    all.sort_unstable_by_key(|&(v, _)| v);
+
+    // Average ranks over ties, accumulating the tie correction term as we go.
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        let ties = (j - i) as f64;
+        let rank = (i + j + 1) as f64 / 2.0;
+        rank_sum_a += rank * all[i..j].iter().filter(|(_, from_a)| *from_a).count() as f64;
+        tie_term += ties.powi(3) - ties;
+        i = j;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let n = n1 + n2;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return 1.0;
+    }
+
+    let z = (u - mean).abs() / variance.sqrt();
+    (2.0 * (1.0 - normal_cdf(z))).clamp(0.0, 1.0)
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz & Stegun 7.1.26, accurate to about 1e-7.
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(samples_ns: Vec<u64>) -> BenchRecord {
+        let mut sorted = samples_ns.clone();
+        sorted.sort_unstable();
+        BenchRecord {
+            commit: "0123456789".to_string(),
+            dirty: false,
+            date: String::new(),
+            baseline: None,
+            year: 2023,
+            day: 1,
+            part: 1,
+            label: "main".to_string(),
+            primary: true,
+            median_ns: sorted.get(sorted.len() / 2).copied().unwrap_or(0),
+            samples_ns,
+            alloc: None,
+        }
+    }
+
+    /// `n` samples spread evenly around `centre`, by up to 5% either side.
+    fn samples(centre: u64, n: u64) -> Vec<u64> {
+        (0..n).map(|i| centre - centre / 20 + centre / 10 * i / n).collect()
+    }
+
+    #[test]
+    fn erf_matches_known_values() {
+        for (x, expected) in [
+            (0.0, 0.0),
+            (0.5, 0.5204998778),
+            (1.0, 0.8427007929),
+            (2.0, 0.9953222650),
+            (-1.0, -0.8427007929),
+        ] {
+            assert!((erf(x) - expected).abs() < 1e-6, "erf({x}) = {}", erf(x));
+        }
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-9);
+        assert!((normal_cdf(1.959964) - 0.975).abs() < 1e-6);
+    }
+
+    #[test]
+    fn identical_samples_are_not_significant() {
+        let a = samples(1_000, 50);
+        assert!((mann_whitney_p(&a, &a) - 1.0).abs() < 1e-6);
+        // All tied, so there's no variance to test at all.
+        assert_eq!(mann_whitney_p(&[5; 10], &[5; 10]), 1.0);
+    }
+
+    #[test]
+    fn shifted_samples_are_significant() {
+        let p = mann_whitney_p(&samples(1_000, 50), &samples(1_200, 50));
+        assert!(p < SIGNIFICANCE, "p = {p}");
+        // U = 0 for three samples a side: z = 4.5 / sqrt(5.25).
+        assert!((mann_whitney_p(&[1, 2, 3], &[4, 5, 6]) - 0.0495).abs() < 1e-3);
+    }
+
+    #[test]
+    fn empty_samples_are_not_significant() {
+        assert_eq!(mann_whitney_p(&[], &samples(1_000, 10)), 1.0);
+        assert_eq!(mann_whitney_p(&samples(1_000, 10), &[]), 1.0);
+    }
+
+    #[test]
+    fn too_few_samples_are_not_judged() {
+        let before = record(samples(1_000, MIN_SAMPLES as u64 - 1));
+        let after = record(samples(2_000, 50));
+        let comparison = compare_samples(&before, &after, 0.05);
+        assert_eq!(comparison.verdict, Verdict::TooFewSamples);
+        assert!(comparison.change > 0.9);
+    }
+
+    #[test]
+    fn significant_changes_past_the_threshold_count() {
+        let (before, after) = (record(samples(1_000, 50)), record(samples(1_300, 50)));
+        assert_eq!(compare_samples(&before, &after, 0.05).verdict, Verdict::Slower);
+        assert_eq!(compare_samples(&after, &before, 0.05).verdict, Verdict::Faster);
+    }
+
+    #[test]
+    fn significant_changes_under_the_threshold_do_not() {
+        let (before, after) = (record(samples(1_000, 500)), record(samples(1_030, 500)));
+        let comparison = compare_samples(&before, &after, 0.05);
+        assert!(comparison.p_value < SIGNIFICANCE, "p = {}", comparison.p_value);
+        assert_eq!(comparison.verdict, Verdict::Unchanged);
+        assert_eq!(compare_samples(&before, &after, 0.01).verdict, Verdict::Slower);
+    }
+
+    #[test]
+    fn large_changes_that_are_noise_do_not() {
+        // Medians far apart, but the samples overlap almost entirely.
+        let before = record(vec![10, 10, 10, 10, 10, 1000, 1000, 1000, 1000]);
+        let after = record(vec![10, 10, 10, 10, 1000, 1000, 1000, 1000, 1000]);
+        let comparison = compare_samples(&before, &after, 0.05);
+        assert!(comparison.change > 1.0);
+        assert!(comparison.p_value >= SIGNIFICANCE);
+        assert_eq!(comparison.verdict, Verdict::Unchanged);
+    }
+}
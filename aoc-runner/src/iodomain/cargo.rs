@@ -1,12 +1,15 @@
 use std::{
     collections::HashMap,
-    env,
+    env, fs,
     path::{Path, PathBuf},
 };
 
 use ah::Ok;
 use anyhow as ah;
-use cargo_metadata::{camino::Utf8PathBuf, Metadata, Package, PackageId, Target};
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    Metadata, Package, PackageId, Target,
+};
 use regex::Regex;
 
 fn curr_pack_raw<'b>(meta: &'b Metadata, curr_dir: &Path) -> Option<&'b Package> {
@@ -70,6 +73,9 @@ pub fn day_from_bin(bin: &Target) -> ah::Result<u8> {
     Ok(day_num)
 }
 
+/// Overrides where day inputs live. A relative path is taken from the workspace root.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
 pub struct WorkspaceMeta {
     pub current_directory: PathBuf,
     pub worspace_data: Metadata,
@@ -111,6 +117,57 @@ impl WorkspaceMeta {
         day_map_raw(curr_package)
     }
 
+    /// Finds the source file for a day, either a `dayN` binary target or a `day_NN.rs` module beside the lib.
+    pub fn get_day_source_file(&self, curr_package: &Package, day: u8) -> Option<Utf8PathBuf> {
+        if let Some(target) = self.get_day_map(curr_package).get(&day) {
+            return Some(target.src_path.clone());
+        }
+
+        let day_filter: Regex = Regex::new(r"^d(?:ay)?_?(\d{1,2})$").unwrap();
+        let lib = curr_package.targets.iter().find(|t| t.is_lib())?;
+        let src_dir = lib.src_path.parent()?;
+
+        src_dir.read_dir_utf8().ok()?.flatten().map(|e| e.into_path()).find(|p| {
+            p.extension() == Some("rs")
+                && p.file_stem()
+                    .and_then(|stem| day_filter.captures(stem))
+                    .and_then(|c| c.get(1)?.as_str().parse::<u8>().ok())
+                    == Some(day)
+        })
+    }
+
+    /// Day modules the lib declares, like `pub mod day_05;`, by day. A crate's dispatcher binary
+    /// runs these through `aoc_runner::registry`.
+    pub fn get_lib_day_modules(&self, curr_package: &Package) -> HashMap<u8, String> {
+        let day_filter: Regex = Regex::new(r"^d(?:ay)?_?(\d{1,2})$").unwrap();
+        let Some(lib) = curr_package.targets.iter().find(|t| t.is_lib()) else {
+            return HashMap::new();
+        };
+        let Some(file) = fs::read_to_string(&lib.src_path).ok().and_then(|text| syn::parse_file(&text).ok()) else {
+            return HashMap::new();
+        };
+
+        file.items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Mod(m) => Some(m.ident.to_string()),
+                _ => None,
+            })
+            .filter_map(|name| {
+                let day = day_filter.captures(&name)?.get(1)?.as_str().parse::<u8>().ok()?;
+                Some((day, name))
+            })
+            .collect()
+    }
+
+    /// The binary named after the package, which dispatches to the lib's days.
+    pub fn get_dispatcher_target<'a>(&'a self, curr_package: &'a Package) -> Option<&'a Target> {
+        curr_package
+            .targets
+            .iter()
+            .find(|t| t.is_bin() && t.name == curr_package.name)
+    }
+
     pub fn get_year_map<'a>(&'a self) -> HashMap<u16, &'a Package> {
         let year_filter = Regex::new(r"(\d{4})$").unwrap();
         let mut year_map: HashMap<u16, &Package> = HashMap::new();
@@ -131,9 +188,43 @@ impl WorkspaceMeta {
         year_map
     }
 
+    /// Year crate directories under the workspace root which aren't workspace members, eg. ones
+    /// that were dropped because they no longer build.
+    pub fn get_unregistered_year_dirs(&self) -> Vec<(u16, Utf8PathBuf)> {
+        let year_filter = Regex::new(r"(\d{4})$").unwrap();
+        let root = &self.worspace_data.workspace_root;
+        let member_dirs: Vec<&Utf8Path> = self
+            .worspace_data
+            .workspace_packages()
+            .into_iter()
+            .filter_map(|p| p.manifest_path.parent())
+            .collect();
+
+        let Result::Ok(entries) = root.read_dir_utf8() else {
+            return Vec::new();
+        };
+        let mut dirs: Vec<(u16, Utf8PathBuf)> = entries
+            .flatten()
+            .map(|e| e.into_path())
+            .filter(|p| p.join("Cargo.toml").exists() && !member_dirs.contains(&p.as_path()))
+            .filter_map(|p| {
+                let year = year_filter.captures(p.file_name()?)?.get(1)?.as_str().parse().ok()?;
+                Some((year, p))
+            })
+            .collect();
+        dirs.sort();
+
+        dirs
+    }
+
+    /// `input/` in the workspace root, unless `AOC_INPUT_DIR` names another folder, as it does for
+    /// the `#[aoc]` macro embedding the inputs.
     pub fn get_input_folder_location(&self) -> Utf8PathBuf {
         let mut dir = self.worspace_data.workspace_root.clone();
-        dir.push("input");
+        match env::var(INPUT_DIR_VAR) {
+            Result::Ok(input_dir) => dir.push(input_dir),
+            Result::Err(_) => dir.push("input"),
+        }
 
         dir
     }
@@ -0,0 +1,241 @@
+use std::fs::read_to_string;
+
+use anyhow as ah;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use proc_macro2::LineColumn;
+use syn::{spanned::Spanned, Attribute, Ident, Item, ItemFn, LitInt, ReturnType, Token};
+
+/// Which macro attribute a function inside an `#[aoc]` module carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayItemKind {
+    Generator,
+    Solver(AocPart),
+    Solution(AocPart),
+}
+
+/// Runner-side mirror of the macro crate's `AocPart`: which part a solver or solution answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AocPart {
+    Part1,
+    Part2,
+    /// Both answers at once, returned as a `(part1, part2)` tuple.
+    Both,
+}
+
+impl AocPart {
+    /// Part `1` or `2`, as the CLI takes it.
+    pub fn from_number(part: u8) -> Option<AocPart> {
+        match part {
+            1 => Some(AocPart::Part1),
+            2 => Some(AocPart::Part2),
+            _ => None,
+        }
+    }
+
+    /// The part as written in a `#[solver]` or `#[solution]` attribute.
+    pub fn flag(self) -> &'static str {
+        match self {
+            AocPart::Part1 => "part1",
+            AocPart::Part2 => "part2",
+            AocPart::Both => "both",
+        }
+    }
+}
+
+/// Runner-side mirror of the macro crate's `AocGeneratorData` / `AocSolverData` / `AocSolutionData`,
+/// keeping source text and locations instead of syntax trees.
+#[derive(Debug, Clone)]
+pub struct DayItem {
+    pub kind: DayItemKind,
+    pub display_slug: String,
+    pub fn_name: String,
+    /// Solver argument type. `None` for generators and solutions.
+    pub input_type: Option<String>,
+    /// Generator output type, or solver/solution answer type.
+    pub output_type: Option<String>,
+    /// Location of the first attribute on the function.
+    pub start: LineColumn,
+    /// Location just past the function's closing brace.
+    pub end: LineColumn,
+}
+
+/// A day file which contains an `#[aoc(year, dayN)]` module.
+pub struct DaySource {
+    pub path: Utf8PathBuf,
+    pub text: String,
+    pub year: u32,
+    pub day: u32,
+    pub mod_name: String,
+    /// Location of the module's closing brace.
+    pub mod_close: LineColumn,
+    pub items: Vec<DayItem>,
+}
+
+impl DaySource {
+    pub fn load(path: &Utf8Path) -> ah::Result<DaySource> {
+        let text = read_to_string(path)?;
+        Self::parse(path, text)
+    }
+
+    pub fn parse(path: &Utf8Path, text: String) -> ah::Result<DaySource> {
+        let file = syn::parse_file(&text).map_err(|e| {
+            let loc = e.span().start();
+            ah::anyhow!("{}:{}:{}: {}", path, loc.line, loc.column + 1, e)
+        })?;
+
+        for item in file.items.iter() {
+            let Item::Mod(module) = item else {
+                continue;
+            };
+            let Some(aoc_attr) = module.attrs.iter().find(|a| a.path().is_ident("aoc")) else {
+                continue;
+            };
+            let (year, day) = parse_aoc_args(aoc_attr)?;
+            let Some((brace, contents)) = &module.content else {
+                continue;
+            };
+
+            let mut items = Vec::new();
+            for mod_item in contents.iter() {
+                let Item::Fn(fn_data) = mod_item else {
+                    continue;
+                };
+                for attr in fn_data.attrs.iter() {
+                    if let Some(data) = DayItem::from_attr(attr, fn_data, &text)? {
+                        items.push(data);
+                    }
+                }
+            }
+
+            return Ok(DaySource {
+                path: path.to_owned(),
+                year,
+                day,
+                mod_name: module.ident.to_string(),
+                mod_close: brace.span.close().start(),
+                items,
+                text,
+            });
+        }
+
+        Err(ah::anyhow!("No `#[aoc(year, day)]` module found in {}", path))
+    }
+
+    pub fn generators(&self) -> impl Iterator<Item = &DayItem> {
+        self.items.iter().filter(|i| i.kind == DayItemKind::Generator)
+    }
+
+    pub fn solvers(&self) -> impl Iterator<Item = &DayItem> {
+        self.items.iter().filter(|i| matches!(i.kind, DayItemKind::Solver(_)))
+    }
+
+    pub fn part_items(&self, part: AocPart) -> impl Iterator<Item = &DayItem> {
+        self.items
+            .iter()
+            .filter(move |i| matches!(i.kind, DayItemKind::Solver(p) | DayItemKind::Solution(p) if p == part))
+    }
+
+    /// Byte offset into `text` for a span location.
+    pub fn offset_of(&self, loc: LineColumn) -> usize {
+        offset_of(&self.text, loc)
+    }
+
+    /// Leading whitespace of the line `loc` sits on.
+    pub fn indent_of(&self, loc: LineColumn) -> &str {
+        let line = self.text.lines().nth(loc.line - 1).unwrap_or_default();
+        &line[..line.len() - line.trim_start().len()]
+    }
+}
+
+impl DayItem {
+    fn from_attr(attr: &Attribute, source: &ItemFn, text: &str) -> ah::Result<Option<DayItem>> {
+        // Anything after the slug, like `params(..)`, doesn't matter here.
+        let (kind, display_slug) = match attr.path().get_ident().map(|id| id.to_string()).as_deref() {
+            Some("generator") => {
+                let slug = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                    let slug: Ident = input.parse()?;
+                    input.parse::<proc_macro2::TokenStream>()?;
+                    Ok(slug)
+                })?;
+                (DayItemKind::Generator, slug.to_string())
+            }
+            Some("solver") => {
+                let (part, slug) = parse_part_and_slug(attr)?;
+                (DayItemKind::Solver(part), slug)
+            }
+            Some("solution") => {
+                let (part, slug) = parse_part_and_slug(attr)?;
+                (DayItemKind::Solution(part), slug)
+            }
+            _ => return Ok(None),
+        };
+
+        let input_type = match (kind, source.sig.inputs.first()) {
+            (DayItemKind::Solver(_), Some(syn::FnArg::Typed(arg))) => Some(source_text(text, arg.ty.span())),
+            _ => None,
+        };
+        let output_type = match &source.sig.output {
+            ReturnType::Type(_, ty) => Some(source_text(text, ty.span())),
+            ReturnType::Default => None,
+        };
+
+        let start = source.attrs.first().map(|a| a.pound_token.span.start()).unwrap_or(source.sig.span().start());
+
+        Ok(Some(DayItem {
+            kind,
+            display_slug,
+            fn_name: source.sig.ident.to_string(),
+            input_type,
+            output_type,
+            start,
+            end: source.block.brace_token.span.close().end(),
+        }))
+    }
+}
+
+/// The year and day in `#[aoc(2023, day5)]`.
+pub fn parse_aoc_args(attr: &Attribute) -> ah::Result<(u32, u32)> {
+    let (year, day) = attr.parse_args_with(|input: syn::parse::ParseStream| {
+        let year: LitInt = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let day: Ident = input.parse()?;
+        Ok((year, day))
+    })?;
+
+    let day_part = day.to_string();
+    let day_part = day_part.strip_prefix("day").unwrap_or(&day_part);
+    let day_part = day_part.strip_prefix("d").unwrap_or(day_part);
+
+    Ok((year.base10_parse()?, day_part.parse()?))
+}
+
+/// The part and slug that start a `#[solver]` or `#[solution]` attribute.
+fn parse_part_and_slug(attr: &Attribute) -> ah::Result<(AocPart, String)> {
+    let (part, slug) = attr.parse_args_with(|input: syn::parse::ParseStream| {
+        let part: Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let slug: Ident = input.parse()?;
+        input.parse::<proc_macro2::TokenStream>()?;
+        Ok((part, slug))
+    })?;
+    Ok((part_from_ident(&part)?, slug.to_string()))
+}
+
+fn part_from_ident(ident: &Ident) -> ah::Result<AocPart> {
+    match ident.to_string().as_str() {
+        "part1" | "Part1" | "p1" | "P1" => Ok(AocPart::Part1),
+        "part2" | "Part2" | "p2" | "P2" => Ok(AocPart::Part2),
+        "both" | "Both" => Ok(AocPart::Both),
+        other => Err(ah::anyhow!("Unrecognized part indicator `{other}`")),
+    }
+}
+
+pub fn source_text(text: &str, span: proc_macro2::Span) -> String {
+    text[offset_of(text, span.start())..offset_of(text, span.end())].to_string()
+}
+
+pub fn offset_of(text: &str, loc: LineColumn) -> usize {
+    let line_start: usize = text.split_inclusive('\n').take(loc.line - 1).map(str::len).sum();
+    let column: usize = text[line_start..].chars().take(loc.column).map(char::len_utf8).sum();
+    line_start + column
+}
@@ -1,2 +1,3 @@
 pub mod cargo;
 pub mod credentials;
+pub mod daysource;
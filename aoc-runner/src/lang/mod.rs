@@ -0,0 +1,180 @@
+use std::{
+    process::{Command, ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
+
+use anyhow as ah;
+use cargo_metadata::camino::Utf8Path;
+use regex::Regex;
+
+use crate::{cli::Lang, harness::RunOptions, iodomain::cargo::WorkspaceMeta};
+
+pub mod python;
+pub mod rust;
+
+pub use python::PythonBackend;
+pub use rust::RustBackend;
+
+/// Captured result of running one day's solutions.
+pub struct RunOutput {
+    pub stdout: String,
+    pub status: ExitStatus,
+    /// Wall time of the whole subprocess, including any build or interpreter startup.
+    pub wall: Duration,
+}
+
+/// One answer line, as printed by every backend:
+/// `Part 1, {label} Solution ({elapsed}): {answer}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartAnswer {
+    pub part: u8,
+    pub label: String,
+    pub answer: String,
+    pub elapsed: Option<Duration>,
+}
+
+/// A language that solutions can be written in, and how to find and run them.
+pub trait Language {
+    fn name(&self) -> &'static str;
+
+    /// Describes where this backend looks for solutions, for "nothing found" errors.
+    fn layout_hint(&self) -> &'static str;
+
+    /// Days with a runnable solution for the given year, in ascending order.
+    fn discover(&self, meta: &WorkspaceMeta, year: u16) -> Vec<u8>;
+
+    /// Runs both parts of a day against `input`, with `options` applied to each solution. Stderr
+    /// is left attached to the terminal.
+    fn run(&self, meta: &WorkspaceMeta, year: u16, day: u8, input: &Utf8Path, options: &RunOptions)
+        -> ah::Result<RunOutput>;
+
+    fn parse_answers(&self, output: &RunOutput) -> ah::Result<Vec<PartAnswer>> {
+        parse_answer_lines(&output.stdout)
+    }
+}
+
+pub fn backend(lang: Lang) -> Box<dyn Language> {
+    match lang {
+        Lang::Rs => Box::new(RustBackend),
+        Lang::Py => Box::new(PythonBackend),
+    }
+}
+
+pub fn all_backends() -> Vec<Box<dyn Language>> {
+    vec![Box::new(RustBackend), Box::new(PythonBackend)]
+}
+
+/// Runs a command to completion with stdout captured, timing it.
+pub(crate) fn run_captured(cmd: &mut Command) -> ah::Result<RunOutput> {
+    let start = Instant::now();
+    let output = cmd.stdout(Stdio::piped()).stderr(Stdio::inherit()).output()?;
+
+    Ok(RunOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        status: output.status,
+        wall: start.elapsed(),
+    })
+}
+
+/// Every answer line in `stdout`. Other lines, like headers or what solutions print themselves,
+/// are skipped, but a line starting like an answer that doesn't parse as one is an error.
+pub fn parse_answer_lines(stdout: &str) -> ah::Result<Vec<PartAnswer>> {
+    let answer_start = Regex::new(r"^Part \d, ").unwrap();
+    let line_filter = Regex::new(r"^Part (\d), (.*?) Solution(?: \(([^)]*)\))?: (.*)$").unwrap();
+
+    stdout
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| answer_start.is_match(line))
+        .map(|line| {
+            let captures = line_filter
+                .captures(line)
+                .ok_or_else(|| ah::anyhow!("Malformed answer line `{line}`"))?;
+            Ok(PartAnswer {
+                part: captures[1].parse()?,
+                label: captures[2].to_string(),
+                elapsed: captures
+                    .get(3)
+                    .map(|m| parse_duration(m.as_str()))
+                    .transpose()
+                    .map_err(|e| ah::anyhow!("{e} in `{line}`"))?,
+                answer: captures[4].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parses durations in `Duration`'s `Debug` format, eg. `1.5ms`, `830ns` or `2.000341s`.
+pub fn parse_duration(text: &str) -> ah::Result<Duration> {
+    let invalid = || ah::anyhow!("Invalid duration `{text}`");
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).ok_or_else(invalid)?;
+    let (value, unit) = text.split_at(split);
+    let value: f64 = value.parse().map_err(|_| invalid())?;
+    let scale = match unit {
+        "ns" => 1e-9,
+        "µs" | "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.0,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_secs_f64(value * scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_in_each_unit() {
+        assert_eq!(parse_duration("830ns").unwrap(), Duration::from_nanos(830));
+        assert_eq!(parse_duration("12.5µs").unwrap(), Duration::from_nanos(12_500));
+        assert_eq!(parse_duration("12.5us").unwrap(), Duration::from_nanos(12_500));
+        assert_eq!(parse_duration("1.5ms").unwrap(), Duration::from_micros(1_500));
+        assert_eq!(parse_duration("2.000341s").unwrap(), Duration::from_micros(2_000_341));
+    }
+
+    #[test]
+    fn malformed_durations() {
+        for text in ["", "ms", "12", "1.2.3ms", "5min", "-1s"] {
+            assert!(parse_duration(text).is_err(), "`{text}` parsed");
+        }
+    }
+
+    #[test]
+    fn answer_lines_among_other_output() {
+        let stdout = "## AOC 2021, Day 15 ----------\n\
+            Part 1, part1 Solution (1.250ms): 40\n\
+            debug output from a solution\n\
+            Part 2, gen / main Solution: 315  \n";
+        assert_eq!(
+            parse_answer_lines(stdout).unwrap(),
+            [
+                PartAnswer {
+                    part: 1,
+                    label: "part1".into(),
+                    answer: "40".into(),
+                    elapsed: Some(Duration::from_micros(1_250)),
+                },
+                PartAnswer {
+                    part: 2,
+                    label: "gen / main".into(),
+                    answer: "315".into(),
+                    elapsed: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_answer_lines() {
+        assert_eq!(parse_answer_lines("## AOC 2021, Day 15 ----------\nNo input found.\n").unwrap(), []);
+        assert_eq!(parse_answer_lines("").unwrap(), []);
+    }
+
+    #[test]
+    fn malformed_answer_lines_are_errors() {
+        assert!(parse_answer_lines("Part 1, part1 Solved: 40").is_err());
+        assert!(parse_answer_lines("Part 1, part1 Solution (soon): 40").is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use std::{env, process::Command};
+
+use anyhow as ah;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use regex::Regex;
+
+use super::{run_captured, Language, RunOutput};
+use crate::{harness::RunOptions, iodomain::cargo::WorkspaceMeta};
+
+const PY_HARNESS: &str = include_str!("../templates/py-harness.py");
+
+/// Runs `py_aoc/year_YYYY/dayNN.py` modules through a small harness which times their
+/// `part1`/`part2` functions (or `solve`, for days which only have that).
+///
+/// The harness honours the same `AOC_TIMEOUT`/`AOC_CPU_TIME`/`AOC_MEMORY_LIMIT` variables as
+/// the Rust harness, but not benchmarking, heap accounting or JSON reports. The interpreter
+/// defaults to `python3` and can be overridden with `AOC_PYTHON`.
+pub struct PythonBackend;
+
+impl PythonBackend {
+    fn year_dir(meta: &WorkspaceMeta, year: u16) -> Utf8PathBuf {
+        let mut dir = meta.worspace_data.workspace_root.clone();
+        dir.push("py_aoc");
+        dir.push(format!("year_{year}"));
+
+        dir
+    }
+
+    /// The days of a year directory, from its zero-padded `dayNN.py` modules, which are the ones
+    /// `run` imports.
+    fn days_in(dir: &Utf8Path) -> Vec<u8> {
+        let day_filter: Regex = Regex::new(r"^day(\d{2})\.py$").unwrap();
+        let Ok(entries) = dir.read_dir_utf8() else {
+            return Vec::new();
+        };
+
+        let mut days: Vec<u8> = entries
+            .flatten()
+            .filter_map(|e| day_filter.captures(e.file_name())?.get(1)?.as_str().parse().ok())
+            .collect();
+        days.sort();
+
+        days
+    }
+
+    fn module_name(day: u8) -> String {
+        format!("day{day:02}")
+    }
+}
+
+impl Language for PythonBackend {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn layout_hint(&self) -> &'static str {
+        "zero-padded modules named similar to `py_aoc/year_2021/day05.py`"
+    }
+
+    fn discover(&self, meta: &WorkspaceMeta, year: u16) -> Vec<u8> {
+        Self::days_in(&Self::year_dir(meta, year))
+    }
+
+    fn run(
+        &self,
+        meta: &WorkspaceMeta,
+        year: u16,
+        day: u8,
+        input: &Utf8Path,
+        options: &RunOptions,
+    ) -> ah::Result<RunOutput> {
+        let interpreter = env::var("AOC_PYTHON").unwrap_or_else(|_| "python3".to_string());
+
+        let mut cmd = Command::new(&interpreter);
+        cmd.arg("-c")
+            .arg(PY_HARNESS)
+            .arg(format!("py_aoc.year_{year}.{}", Self::module_name(day)))
+            .arg(input)
+            .arg(year.to_string())
+            .arg(day.to_string())
+            .current_dir(&meta.worspace_data.workspace_root);
+        options.limits.apply_env(&mut cmd);
+
+        run_captured(&mut cmd).map_err(|e| ah::anyhow!("Could not start `{interpreter}`: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{process::ExitStatus, time::Duration};
+
+    use super::*;
+    use crate::lang::PartAnswer;
+
+    fn output(stdout: &str) -> RunOutput {
+        RunOutput {
+            stdout: stdout.to_string(),
+            status: ExitStatus::default(),
+            wall: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn harness_output_parses() {
+        let stdout = "## AOC 2021, Day 15 ----------\n\
+            Part 1, part1 Solution (830ns): 40\n\
+            Part 2, part2 Solution: TIMEOUT\n";
+        let answers = PythonBackend.parse_answers(&output(stdout)).unwrap();
+        assert_eq!(
+            answers,
+            [
+                PartAnswer {
+                    part: 1,
+                    label: "part1".into(),
+                    answer: "40".into(),
+                    elapsed: Some(Duration::from_nanos(830)),
+                },
+                PartAnswer {
+                    part: 2,
+                    label: "part2".into(),
+                    answer: "TIMEOUT".into(),
+                    elapsed: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn harness_elapsed_units_parse() {
+        let stdout = "Part 1, solve Solution (12.500µs): 1\n\
+            Part 1, solve Solution (1.500ms): 1\n\
+            Part 1, solve Solution (2.000s): 1\n";
+        let elapsed: Vec<_> = PythonBackend
+            .parse_answers(&output(stdout))
+            .unwrap()
+            .into_iter()
+            .map(|answer| answer.elapsed.unwrap())
+            .collect();
+        assert_eq!(
+            elapsed,
+            [Duration::from_nanos(12_500), Duration::from_micros(1_500), Duration::from_secs(2)]
+        );
+    }
+
+    #[test]
+    fn discovered_days_are_the_modules_run_imports() {
+        let dir = env::temp_dir().join(format!("aoc-py-{}", std::process::id()));
+        let dir = Utf8PathBuf::from_path_buf(dir).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["__init__.py", "day01.py", "day7.py", "day15.py", "day15_slow.py", "notes.txt"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let days = PythonBackend::days_in(&dir);
+        assert_eq!(days, [1, 15]);
+        for day in days {
+            let module = dir.join(format!("{}.py", PythonBackend::module_name(day)));
+            assert!(module.exists(), "{module}");
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn no_input_has_no_answers() {
+        let stdout = "## AOC 2021, Day 15 ----------\nNo input found.\n";
+        assert_eq!(PythonBackend.parse_answers(&output(stdout)).unwrap(), []);
+    }
+}
@@ -0,0 +1,89 @@
+use std::process::Command;
+
+use anyhow as ah;
+use cargo_metadata::camino::Utf8Path;
+
+use super::{run_captured, Language, RunOutput};
+use crate::{harness::RunOptions, iodomain::cargo::WorkspaceMeta};
+
+/// Runs `dayN` binary targets of the year's crate with `cargo run --release`, or for days that are
+/// modules of the lib, the binary named after the crate with the day as its argument.
+///
+/// The `#[aoc]` macro embeds `input/YEAR/DAY.txt` at compile time, so the input path is only
+/// used to check that the file the binary will see is the one being asked for. Heap accounting
+/// rebuilds the binary with `aoc-runner/alloc-stats` enabled.
+pub struct RustBackend;
+
+impl Language for RustBackend {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn layout_hint(&self) -> &'static str {
+        "binaries in your Cargo.toml named similar to `day15`, or `day_15` modules in a lib with a binary named after the crate"
+    }
+
+    fn discover(&self, meta: &WorkspaceMeta, year: u16) -> Vec<u8> {
+        let Some(pack) = meta.get_year_map().get(&year).copied() else {
+            return Vec::new();
+        };
+        let mut days: Vec<u8> = meta.get_day_map(pack).into_keys().collect();
+        if meta.get_dispatcher_target(pack).is_some() {
+            days.extend(meta.get_lib_day_modules(pack).into_keys());
+        }
+        days.sort();
+        days.dedup();
+
+        days
+    }
+
+    fn run(
+        &self,
+        meta: &WorkspaceMeta,
+        year: u16,
+        day: u8,
+        input: &Utf8Path,
+        options: &RunOptions,
+    ) -> ah::Result<RunOutput> {
+        let year_map = meta.get_year_map();
+        let pack = year_map
+            .get(&year)
+            .copied()
+            .ok_or(ah::anyhow!("No crate for {year} in this workspace"))?;
+        let day_map = meta.get_day_map(pack);
+        let (target, day_arg) = match day_map.get(&day) {
+            Some(target) => (*target, None),
+            None => meta
+                .get_dispatcher_target(pack)
+                .filter(|_| meta.get_lib_day_modules(pack).contains_key(&day))
+                .map(|target| (target, Some(day.to_string())))
+                .ok_or(ah::anyhow!("No binary target for day {day} in {}", pack.name))?,
+        };
+
+        // Only the dispatcher takes `--input`; day binaries run on the input compiled into them.
+        if day_arg.is_none() && input != meta.get_input_file_for_day(&year, &day) {
+            return Err(ah::anyhow!(
+                "Rust day binaries embed their input at compile time and can only run against {}",
+                meta.get_input_file_for_day(&year, &day)
+            ));
+        }
+
+        let mut cmd = Command::new("cargo");
+        cmd.arg("run")
+            .arg("--release")
+            .arg("--bin")
+            .arg(&target.name)
+            .current_dir(pack.manifest_path.parent().unwrap());
+        if options.alloc_stats {
+            cmd.arg("--features").arg("aoc-runner/alloc-stats");
+        }
+        // The dispatcher reads the file itself, so an input downloaded after the crate was built is
+        // still picked up.
+        if let Some(day_arg) = day_arg {
+            cmd.arg("--").arg(day_arg).arg("--input").arg(input);
+        }
+        options.apply_env(&mut cmd);
+
+        run_captured(&mut cmd)
+    }
+}
@@ -1,4 +1,21 @@
+pub mod alloc;
+pub mod bench;
 pub mod cli;
 pub mod codegen;
 pub mod commands;
+pub mod doctor;
+pub mod examples;
+pub mod harness;
+pub mod history;
 pub mod iodomain;
+pub mod lang;
+pub mod migrate;
+pub mod params;
+pub mod plot;
+pub mod property;
+pub mod registry;
+pub mod trace;
+pub mod viz;
+
+// For the spans `#[aoc]` generates.
+pub use tracing;
@@ -3,6 +3,7 @@
 use std::io;
 use std::io::BufRead;
 use std::io::Write;
+use std::process::ExitCode;
 
 use aoc_runner::cli::*;
 use aoc_runner::commands as cmds;
@@ -16,16 +17,30 @@ fn stdout_wrapper() -> impl Write {
     io::stdout().lock()
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> ExitCode {
     let Cli::Aoc(cli) = Cli::parse();
 
-    match &cli.command {
+    let result = match &cli.command {
         Some(Commands::Login) => cmds::login(stdin_wrapper, stdout_wrapper, cli),
         Some(Commands::Input) => cmds::input(stdin_wrapper, stdout_wrapper, cli),
         Some(Commands::Prep) => cmds::prepare(stdin_wrapper, stdout_wrapper, cli),
-        Some(Commands::Run) => cmds::run(stdin_wrapper, stdout_wrapper, cli, "run"),
-        Some(Commands::Test) => cmds::run(stdin_wrapper, stdout_wrapper, cli, "test"),
-        Some(Commands::Bench) => cmds::benchmark(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Run { .. }) => cmds::run(stdin_wrapper, stdout_wrapper, cli, "run"),
+        Some(Commands::Compare { .. }) => cmds::compare(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Verify { .. }) => cmds::verify(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Test { .. }) => cmds::run(stdin_wrapper, stdout_wrapper, cli, "test"),
+        Some(Commands::Bench { .. }) => cmds::benchmark(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Viz { .. }) => cmds::visualize(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Doctor) => cmds::doctor(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Migrate { .. }) => cmds::migrate(stdin_wrapper, stdout_wrapper, cli),
+        Some(Commands::Variant { .. }) => cmds::variant(stdin_wrapper, stdout_wrapper, cli),
         None => cmds::run(stdin_wrapper, stdout_wrapper, cli, "run"),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            ExitCode::from(cmds::exit_code(&error))
+        }
     }
 }
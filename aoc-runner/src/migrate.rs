@@ -0,0 +1,982 @@
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, remove_file, write};
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr, GenericArgument, Item, ItemFn, ItemMod, Lit, LitInt, LitStr, Local, Pat, PathArguments, ReturnType, Stmt,
+    Token, Type, UseTree,
+};
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item as TomlItem};
+
+use crate::iodomain::daysource::{offset_of, source_text};
+
+/// The const a migrated test module checks the real input's known answers against.
+const PUZZLE_INPUT_CASE: &str = "PUZZLE_INPUT";
+
+/// Crates the legacy day formats were built on, neither of which exist in this workspace anymore.
+const LEGACY_CRATES: [&str; 2] = ["common", "framework"];
+
+pub enum MigrationAction {
+    Rewrite(String),
+    Delete,
+}
+
+/// A pending change to one file, along with anything the user should double check by hand.
+pub struct Migration {
+    pub path: Utf8PathBuf,
+    pub action: MigrationAction,
+    pub notes: Vec<String>,
+}
+
+impl Migration {
+    pub fn apply(&self) -> Result<()> {
+        match &self.action {
+            MigrationAction::Rewrite(text) => write(&self.path, text.as_bytes())?,
+            MigrationAction::Delete => remove_file(&self.path)?,
+        }
+        Ok(())
+    }
+}
+
+/// Byte-range replacements against the original text, applied all at once so spans stay valid.
+#[derive(Default)]
+struct Edits(Vec<(usize, usize, String)>);
+
+impl Edits {
+    fn replace(&mut self, start: usize, end: usize, text: impl Into<String>) {
+        self.0.push((start, end, text.into()));
+    }
+
+    fn replace_span(&mut self, source: &str, span: Span, text: impl Into<String>) {
+        self.replace(offset_of(source, span.start()), offset_of(source, span.end()), text);
+    }
+
+    /// Applies and removes every edit inside `range`, returning the edited slice.
+    fn take_range(&mut self, source: &str, range: Range<usize>) -> String {
+        let (mut inside, outside): (Vec<_>, Vec<_>) = self
+            .0
+            .drain(..)
+            .partition(|(s, e, _)| *s >= range.start && *e <= range.end);
+        self.0 = outside;
+        inside.sort_by_key(|(s, _, _)| *s);
+
+        let mut out = String::new();
+        let mut cursor = range.start;
+        for (s, e, t) in inside {
+            out.push_str(&source[cursor..s]);
+            out.push_str(&t);
+            cursor = e;
+        }
+        out.push_str(&source[cursor..range.end]);
+        out
+    }
+}
+
+/// Rewrites a `common::solution!` day into the `#[aoc(year, dayN)] mod solutions` form.
+/// Returns `None` if the file isn't a legacy day.
+pub fn migrate_day_file(path: &Utf8Path, year: u32) -> Result<Option<Migration>> {
+    let text = read_to_string(path)?;
+    migrate_day_text(path, &text, year)
+}
+
+fn migrate_day_text(path: &Utf8Path, text: &str, year: u32) -> Result<Option<Migration>> {
+    let file = syn::parse_file(text).map_err(|e| anyhow!("{}: {}", path, e))?;
+
+    let Some((sol_item, title, day)) = file.items.iter().find_map(legacy_solution_macro) else {
+        return Ok(None);
+    };
+
+    let mut edits = Edits::default();
+    let mut notes = Vec::new();
+
+    let mut import_added = false;
+    for item in file.items.iter() {
+        if let Item::Use(u) = item {
+            if uses_legacy_crate(&u.tree) {
+                let (s, e) = whole_lines(text, item.span(), false);
+                let import = if import_added { "" } else { "use aoc_runner_macros::{aoc, solution};\n" };
+                edits.replace(s, e, import);
+                import_added = true;
+            }
+        }
+    }
+    if !import_added {
+        edits.replace(0, 0, "use aoc_runner_macros::{aoc, solution};\n");
+    }
+
+    let (s, e) = whole_lines(text, sol_item.span(), true);
+    edits.replace(s, e, "");
+
+    let parts: Vec<(u8, &ItemFn)> = file
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            Item::Fn(f) => part_number(&f.sig.ident.to_string()).map(|p| (p, f)),
+            _ => None,
+        })
+        .collect();
+    let (Some((_, first)), Some((_, last))) = (parts.first(), parts.last()) else {
+        return Err(anyhow!("{}: no `part_1` / `part_2` functions to migrate", path));
+    };
+
+    for (part, f) in parts.iter() {
+        migrate_part_fn(text, f, *part, &mut edits, &mut notes).map_err(|e| anyhow!("{}: {}", path, e))?;
+    }
+
+    let (range_start, _) = whole_lines(text, first.span(), false);
+    let (_, range_end) = whole_lines(text, last.span(), false);
+    let inner = edits.take_range(text, range_start..range_end);
+    let module = format!(
+        "// Day {day}: {title}\n#[aoc({year}, day{day})]\npub mod solutions {{\n    use super::*;\n\n{}}}\n",
+        indent(&inner, "    ")
+    );
+    edits.replace(range_start, range_end, module);
+
+    for item in file.items.iter() {
+        if let Item::Mod(m) = item {
+            if is_test_mod(m) {
+                migrate_test_mod(text, m, year, day, &mut edits, &mut notes);
+            }
+        }
+    }
+
+    let new_text = edits.take_range(text, 0..text.len());
+    Ok(Some(Migration {
+        path: path.to_owned(),
+        action: MigrationAction::Rewrite(new_text),
+        notes,
+    }))
+}
+
+/// Turns a `register_year!` lib.rs into the plain module list the other year crates use.
+pub fn migrate_lib_file(path: &Utf8Path) -> Result<Option<Migration>> {
+    let text = read_to_string(path)?;
+    let file = syn::parse_file(&text).map_err(|e| anyhow!("{}: {}", path, e))?;
+
+    let registers = file
+        .items
+        .iter()
+        .any(|i| matches!(i, Item::Macro(m) if m.mac.path.is_ident("register_year")));
+    if !registers {
+        return Ok(None);
+    }
+
+    let mut new_text = "// Import all day modules\n".to_string();
+    for item in file.items.iter() {
+        if let Item::Mod(m) = item {
+            if m.content.is_none() {
+                new_text.push_str(&format!("pub mod {};\n", m.ident));
+            }
+        }
+    }
+
+    Ok(Some(Migration {
+        path: path.to_owned(),
+        action: MigrationAction::Rewrite(new_text),
+        notes: Vec::new(),
+    }))
+}
+
+/// Source files that lib.rs never declares and that only re-export the legacy crates, like old preludes.
+pub fn find_dangling_legacy_files(src_dir: &Utf8Path) -> Result<Vec<Migration>> {
+    let lib_text = read_to_string(src_dir.join("lib.rs")).unwrap_or_default();
+    let lib = syn::parse_file(&lib_text).map_err(|e| anyhow!("{}/lib.rs: {}", src_dir, e))?;
+    let declared: Vec<String> = lib
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            Item::Mod(m) => Some(m.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let mut found = Vec::new();
+    for entry in src_dir.read_dir_utf8()?.flatten() {
+        let path = entry.into_path();
+        let Some(stem) = path.file_stem() else {
+            continue;
+        };
+        if path.extension() != Some("rs") || ["lib", "main"].contains(&stem) || declared.iter().any(|d| d == stem) {
+            continue;
+        }
+
+        let Ok(file) = syn::parse_file(&read_to_string(&path)?) else {
+            continue;
+        };
+        let legacy_only = !file.items.is_empty()
+            && file.items.iter().all(|i| matches!(i, Item::Use(_)))
+            && file.items.iter().any(|i| matches!(i, Item::Use(u) if uses_legacy_crate(&u.tree)));
+        if legacy_only {
+            found.push(Migration {
+                notes: vec![format!("`{stem}` is not declared in lib.rs and only re-exports a missing crate")],
+                path,
+                action: MigrationAction::Delete,
+            });
+        }
+    }
+
+    Ok(found)
+}
+
+/// Swaps a year crate's `common` dependency for the runner and macro crates.
+pub fn migrate_year_manifest(manifest: &Utf8Path) -> Result<Option<Migration>> {
+    let Some(text) = migrate_year_manifest_text(&read_to_string(manifest)?)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(Migration {
+        path: manifest.to_owned(),
+        action: MigrationAction::Rewrite(text),
+        notes: Vec::new(),
+    }))
+}
+
+fn migrate_year_manifest_text(text: &str) -> Result<Option<String>> {
+    let mut doc = text.parse::<DocumentMut>()?;
+    let Some(deps) = doc.get_mut("dependencies").and_then(TomlItem::as_table_mut) else {
+        return Ok(None);
+    };
+    if deps.remove("common").is_none() {
+        return Ok(None);
+    }
+
+    for dep in ["aoc-runner", "aoc-runner-macros"] {
+        if !deps.contains_key(dep) {
+            let mut path = InlineTable::new();
+            path.insert("path", format!("../{dep}").into());
+            deps.insert(dep, value(path));
+        }
+    }
+
+    Ok(Some(doc.to_string()))
+}
+
+/// Registers migrated crates as workspace members and drops workspace dependencies on missing paths.
+pub fn migrate_workspace_manifest(manifest: &Utf8Path, new_members: &[String]) -> Result<Option<Migration>> {
+    let root = manifest.parent().ok_or(anyhow!("Workspace manifest has no parent directory"))?;
+    let text = read_to_string(manifest)?;
+    let Some((text, notes)) = migrate_workspace_manifest_text(root, &text, new_members)
+        .map_err(|e| anyhow!("{manifest}: {e}"))?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(Migration {
+        path: manifest.to_owned(),
+        action: MigrationAction::Rewrite(text),
+        notes,
+    }))
+}
+
+/// The rewritten workspace manifest, with notes on what changed, for a workspace rooted at `root`.
+fn migrate_workspace_manifest_text(
+    root: &Utf8Path,
+    text: &str,
+    new_members: &[String],
+) -> Result<Option<(String, Vec<String>)>> {
+    let mut doc = text.parse::<DocumentMut>()?;
+    let mut notes = Vec::new();
+
+    let members: &mut Array = doc
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("members"))
+        .and_then(TomlItem::as_array_mut)
+        .ok_or(anyhow!("no `workspace.members` array to add migrated crates to"))?;
+    for member in new_members {
+        if !members.iter().any(|m| m.as_str() == Some(member)) {
+            // Keep one-member-per-line layouts intact.
+            let mut entry = toml_edit::Value::from(member.as_str());
+            if let Some(last) = members.iter_mut().last() {
+                let decor = last.decor().clone();
+                last.decor_mut().set_suffix("");
+                *entry.decor_mut() = decor;
+            }
+            members.push_formatted(entry);
+            notes.push(format!("added `{member}` to workspace members"));
+        }
+    }
+
+    if let Some(deps) = doc["workspace"].get_mut("dependencies").and_then(TomlItem::as_table_like_mut) {
+        for name in LEGACY_CRATES {
+            let missing = deps
+                .get(name)
+                .and_then(|d| d.get("path"))
+                .and_then(|p| p.as_str())
+                .is_some_and(|p| !root.join(p).exists());
+            if missing {
+                deps.remove(name);
+                notes.push(format!("removed workspace dependency `{name}`, its path does not exist"));
+            }
+        }
+    }
+
+    if notes.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some((doc.to_string(), notes)))
+}
+
+fn legacy_solution_macro(item: &Item) -> Option<(&Item, String, u32)> {
+    let Item::Macro(m) = item else {
+        return None;
+    };
+    if m.mac.path.segments.last()?.ident != "solution" {
+        return None;
+    }
+    let (title, day) = m
+        .mac
+        .parse_body_with(|input: syn::parse::ParseStream| {
+            let title: LitStr = input.parse()?;
+            input.parse::<Token![,]>()?;
+            let day: LitInt = input.parse()?;
+            Ok((title.value(), day.base10_parse::<u32>()?))
+        })
+        .ok()?;
+    Some((item, title, day))
+}
+
+fn migrate_part_fn(text: &str, f: &ItemFn, part: u8, edits: &mut Edits, notes: &mut Vec<String>) -> Result<()> {
+    let (line_start, _) = whole_lines(text, f.span(), false);
+    edits.replace(line_start, line_start, format!("#[solution(part{part}, main)]\n"));
+    if matches!(f.vis, syn::Visibility::Inherited) {
+        let sig_start = offset_of(text, f.sig.span().start());
+        edits.replace(sig_start, sig_start, "pub ");
+    }
+
+    // The `Result` around the answer stays, since solutions can fail, so only the answer changes.
+    let ReturnType::Type(_, ty) = &f.sig.output else {
+        return Ok(());
+    };
+    let Some(answer) = result_answer_type(ty) else {
+        return Ok(());
+    };
+    if source_text(text, answer.span()).trim() != "Answer" {
+        return Ok(());
+    }
+    let answer_type = answer_type(text, f).ok_or_else(|| {
+        anyhow!(
+            "can't tell what type `{}` answers with; give the value it returns a type, eg. `let answer: u64 = ..;`, and migrate again",
+            f.sig.ident
+        )
+    })?;
+    let ty_text: String = source_text(text, ty.span()).split_whitespace().collect();
+    edits.replace_span(text, answer.span(), &answer_type);
+    notes.push(format!(
+        "`{}` returned `{ty_text}`; now returns `{}`",
+        f.sig.ident,
+        ty_text.replace("Answer", &answer_type)
+    ));
+    Ok(())
+}
+
+/// The type `common::Answer` was converted from in a part fn, read off the values it returns with
+/// `Ok(..)`. Only what the source spells out is used: annotated bindings, typed literals, casts,
+/// turbofishes and methods with a known result, like `count()` or `to_string()`.
+fn answer_type(text: &str, f: &ItemFn) -> Option<String> {
+    let mut fn_body = FnBody::default();
+    fn_body.visit_block(&f.block);
+    if let Some(Stmt::Expr(tail, None)) = f.block.stmts.last() {
+        fn_body.returns.push(tail);
+    }
+
+    fn_body.returns.iter().find_map(|expr| {
+        let Expr::Call(call) = expr else {
+            return None;
+        };
+        if !is_ok_call(expr) {
+            return None;
+        }
+        value_type(text, &call.args[0], &fn_body.lets, 0)
+    })
+}
+
+/// The `let`s and `return`s of a fn body, leaving out those of closures and nested items.
+#[derive(Default)]
+struct FnBody<'a> {
+    lets: Vec<&'a Local>,
+    returns: Vec<&'a Expr>,
+}
+
+impl<'a> Visit<'a> for FnBody<'a> {
+    fn visit_local(&mut self, local: &'a Local) {
+        self.lets.push(local);
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_return(&mut self, ret: &'a syn::ExprReturn) {
+        self.returns.extend(ret.expr.as_deref());
+        visit::visit_expr_return(self, ret);
+    }
+
+    fn visit_expr_closure(&mut self, _: &'a syn::ExprClosure) {}
+
+    fn visit_item(&mut self, _: &'a Item) {}
+}
+
+/// How far `value_type` follows bindings, so `let a = b; let b = a;` style shadowing can't loop.
+const MAX_BINDING_DEPTH: usize = 8;
+
+fn value_type(text: &str, expr: &Expr, lets: &[&Local], depth: usize) -> Option<String> {
+    let ty = |ty: &Type| Some(source_text(text, ty.span()).split_whitespace().collect());
+    match expr {
+        Expr::Paren(paren) => value_type(text, &paren.expr, lets, depth),
+        Expr::Try(try_expr) => value_type(text, &try_expr.expr, lets, depth).map(|t| unwrapped(&t)),
+        Expr::Cast(cast) => ty(&cast.ty),
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) if !int.suffix().is_empty() => Some(int.suffix().to_string()),
+            Lit::Str(_) => Some("String".to_string()),
+            _ => None,
+        },
+        Expr::Macro(mac) if mac.mac.path.is_ident("format") => Some("String".to_string()),
+        Expr::MethodCall(call) => {
+            let method = call.method.to_string();
+            let turbofish = call.turbofish.as_ref().and_then(|turbofish| match turbofish.args.first()? {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            });
+            match (method.as_str(), turbofish) {
+                ("sum" | "product" | "parse", Some(t)) => ty(t),
+                ("into", _) => value_type(text, &call.receiver, lets, depth),
+                ("count" | "len", _) => Some("usize".to_string()),
+                ("to_string", _) => Some("String".to_string()),
+                ("unwrap" | "expect" | "unwrap_or_default", _) => {
+                    value_type(text, &call.receiver, lets, depth).map(|t| unwrapped(&t))
+                }
+                ("unwrap_or", _) => value_type(text, &call.receiver, lets, depth)
+                    .map(|t| unwrapped(&t))
+                    .or_else(|| value_type(text, call.args.first()?, lets, depth)),
+                _ => None,
+            }
+        }
+        Expr::Path(path) if depth < MAX_BINDING_DEPTH => {
+            let name = path.path.get_ident()?;
+            let local = lets.iter().rev().find(|local| binding(&local.pat).is_some_and(|(id, _)| id == name))?;
+            match binding(&local.pat) {
+                Some((_, Some(annotated))) => ty(annotated),
+                _ => value_type(text, &local.init.as_ref()?.expr, lets, depth + 1),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A `let` pattern's name, and its type if it's annotated.
+fn binding(pat: &Pat) -> Option<(&syn::Ident, Option<&Type>)> {
+    match pat {
+        Pat::Ident(ident) => Some((&ident.ident, None)),
+        Pat::Type(typed) => binding(&typed.pat).map(|(ident, _)| (ident, Some(typed.ty.as_ref()))),
+        _ => None,
+    }
+}
+
+/// The `T` of an `Option<T>` or `Result<T, E>`, for unwrapping methods called on one.
+fn unwrapped(ty: &str) -> String {
+    ["Option<", "Result<"]
+        .iter()
+        .find_map(|wrapper| ty.strip_prefix(wrapper)?.strip_suffix('>'))
+        .map(|inner| inner.split(',').next().unwrap_or(inner).to_string())
+        .unwrap_or_else(|| ty.to_string())
+}
+
+/// The `T` in a return type spelled `Result<T>` or `Result<T, E>`, through any path like `miette::`.
+fn result_answer_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(answer) => Some(answer),
+        _ => None,
+    }
+}
+
+fn is_ok_call(expr: &Expr) -> bool {
+    let Expr::Call(call) = expr else {
+        return false;
+    };
+    matches!(call.func.as_ref(), Expr::Path(func) if func.path.is_ident("Ok")) && call.args.len() == 1
+}
+
+fn migrate_test_mod(text: &str, m: &ItemMod, year: u32, day: u32, edits: &mut Edits, notes: &mut Vec<String>) {
+    let Some((brace, items)) = &m.content else {
+        return;
+    };
+
+    // const name -> ([p1, p2] expected, test fns asserting against it)
+    let mut cases: BTreeMap<String, ([Option<String>; 2], Vec<&ItemFn>)> = BTreeMap::new();
+    let (mut puzzle, mut puzzle_fns): ([Option<String>; 2], Vec<&ItemFn>) = Default::default();
+    let mut kept: Vec<&ItemFn> = Vec::new();
+    let mut legacy_imports: Vec<(usize, usize)> = Vec::new();
+
+    for item in items.iter() {
+        match item {
+            Item::Use(u) if uses_legacy_crate(&u.tree) => {
+                legacy_imports.push(whole_lines(text, item.span(), false));
+            }
+            Item::Fn(f) if f.attrs.iter().any(|a| a.path().is_ident("test")) => {
+                let ignored = f.attrs.iter().any(|a| a.path().is_ident("ignore"));
+                match part_assertion(text, f) {
+                    Some((CaseInput::Const(name), part, expected)) if !ignored => {
+                        let case = cases.entry(name).or_default();
+                        case.0[part as usize - 1] = Some(expected);
+                        case.1.push(f);
+                    }
+                    Some((CaseInput::PuzzleInput, part, expected)) => {
+                        puzzle[part as usize - 1] = Some(expected);
+                        puzzle_fns.push(f);
+                    }
+                    _ if source_text(text, f.span()).contains("load_raw") => {
+                        let (s, e) = whole_lines(text, f.span(), true);
+                        edits.replace(s, e, "");
+                        notes.push(format!(
+                            "dropped real-input test `{}`; `load_raw` came from the missing `common` crate, and its expected answer couldn't be read",
+                            f.sig.ident
+                        ));
+                    }
+                    _ => kept.push(f),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut case_added = false;
+    for item in items.iter() {
+        let Item::Const(c) = item else {
+            continue;
+        };
+        let Some(([p1, p2], fns)) = cases.remove(&c.ident.to_string()) else {
+            continue;
+        };
+        let Some(p1) = p1 else {
+            // Part 2 only examples can't be expressed as an `aoc_case` yet.
+            kept.extend(fns);
+            continue;
+        };
+
+        let args = match p2 {
+            Some(p2) => format!("{p1}, {p2}"),
+            None => p1,
+        };
+        let (line_start, _) = whole_lines(text, item.span(), false);
+        let indent = &text[line_start..offset_of(text, item.span().start())];
+        edits.replace(line_start, line_start, format!("{indent}#[aoc_case({args})]\n"));
+        for f in fns {
+            let (s, e) = whole_lines(text, f.span(), true);
+            edits.replace(s, e, "");
+        }
+        case_added = true;
+    }
+    kept.extend(cases.into_values().flat_map(|(_, fns)| fns));
+
+    // The real input's answers become one ignored case, replacing the first test that checked them.
+    if let Some((first, rest)) = puzzle_fns.split_first() {
+        let mut args = match puzzle {
+            [Some(p1), Some(p2)] => format!("{p1}, {p2}"),
+            [Some(p1), None] => p1,
+            [None, Some(p2)] => format!("part2 = {p2}"),
+            [None, None] => unreachable!("every puzzle input test expects an answer"),
+        };
+        args.push_str(&format!(", ignore = \"needs input/{year}/{day}.txt\""));
+        let (line_start, line_end) = whole_lines(text, first.span(), false);
+        let indent = &text[line_start..offset_of(text, first.span().start())];
+        edits.replace(
+            line_start,
+            line_end,
+            format!("{indent}#[aoc_case({args})]\n{indent}const {PUZZLE_INPUT_CASE}: &str = super::AOC_RAW_INPUT;\n"),
+        );
+        for f in rest {
+            let (s, e) = whole_lines(text, f.span(), true);
+            edits.replace(s, e, "");
+        }
+        let names: Vec<_> = puzzle_fns.iter().map(|f| format!("`{}`", f.sig.ident)).collect();
+        notes.push(format!(
+            "moved the real-input answers of {} to `{PUZZLE_INPUT_CASE}`, checked by `cargo test -- --ignored`",
+            names.join(", ")
+        ));
+        case_added = true;
+    }
+
+    for f in kept {
+        let start = offset_of(text, f.span().start());
+        let body = source_text(text, f.span());
+        for (idx, _) in body.match_indices("super::part_") {
+            edits.replace(start + idx, start + idx + "super::".len(), "super::solutions::");
+        }
+        notes.push(format!("kept hand-written test `{}`", f.sig.ident));
+    }
+
+    // The first legacy import makes room for the `aoc_case` import, if one is needed.
+    let import = if case_added { "    use aoc_runner_macros::aoc_case;\n" } else { "" };
+    match legacy_imports.split_first() {
+        Some((&(s, e), rest)) => {
+            edits.replace(s, e, import);
+            for &(s, e) in rest {
+                edits.replace(s, e, "");
+            }
+        }
+        None if case_added => {
+            let open = offset_of(text, brace.span.open().end());
+            let after_open = text[open..].find('\n').map(|i| open + i + 1).unwrap_or(open);
+            edits.replace(after_open, after_open, import);
+        }
+        None => {}
+    }
+}
+
+/// What a legacy test ran a part on.
+enum CaseInput {
+    /// An example const in the test module.
+    Const(String),
+    /// The real input, read by `load_raw`.
+    PuzzleInput,
+}
+
+/// Matches `assert_eq!(super::part_N(CONST)?, expected.into())` style example tests, and
+/// `let input = load_raw(year, day)?;` followed by the same for the real input.
+fn part_assertion(text: &str, f: &ItemFn) -> Option<(CaseInput, u8, String)> {
+    let mut found = None;
+    let mut puzzle_input = None;
+    for stmt in f.block.stmts.iter() {
+        let mac = match stmt {
+            Stmt::Macro(m) => &m.mac,
+            Stmt::Expr(Expr::Macro(m), _) => &m.mac,
+            Stmt::Expr(tail, None) if is_ok_call(tail) => continue,
+            Stmt::Local(local) if puzzle_input.is_none() && source_text(text, local.span()).contains("load_raw") => {
+                let syn::Pat::Ident(name) = &local.pat else {
+                    return None;
+                };
+                puzzle_input = Some(name.ident.to_string());
+                continue;
+            }
+            _ => return None,
+        };
+        if !mac.path.is_ident("assert_eq") || found.is_some() {
+            return None;
+        }
+        let args = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated).ok()?;
+        let (left, right) = (args.first()?, args.iter().nth(1)?);
+
+        let left = match left {
+            Expr::Try(t) => t.expr.as_ref(),
+            other => other,
+        };
+        let Expr::Call(call) = left else {
+            return None;
+        };
+        let Expr::Path(func) = call.func.as_ref() else {
+            return None;
+        };
+        let part = part_number(&func.path.segments.last()?.ident.to_string())?;
+        let input = match input_ident(call.args.first()?)? {
+            name if puzzle_input.as_ref() == Some(&name) => CaseInput::PuzzleInput,
+            name => CaseInput::Const(name),
+        };
+
+        let right = match right {
+            Expr::MethodCall(mc) if mc.method == "into" && mc.args.is_empty() => mc.receiver.as_ref(),
+            other => other,
+        };
+        found = Some((input, part, source_text(text, right.span())));
+    }
+    found
+}
+
+/// The variable a part is called on, as in `CONST`, `&input` or `input.as_str()`.
+fn input_ident(arg: &Expr) -> Option<String> {
+    match arg {
+        Expr::Path(path) => Some(path.path.get_ident()?.to_string()),
+        Expr::Reference(reference) => input_ident(&reference.expr),
+        Expr::MethodCall(call) if call.method == "as_str" && call.args.is_empty() => input_ident(&call.receiver),
+        _ => None,
+    }
+}
+
+fn part_number(name: &str) -> Option<u8> {
+    match name {
+        "part_1" | "part1" => Some(1),
+        "part_2" | "part2" => Some(2),
+        _ => None,
+    }
+}
+
+fn is_test_mod(m: &ItemMod) -> bool {
+    m.attrs
+        .iter()
+        .any(|a| a.path().is_ident("cfg") && a.parse_args::<syn::Ident>().is_ok_and(|i| i == "test"))
+}
+
+fn uses_legacy_crate(tree: &UseTree) -> bool {
+    let first = match tree {
+        UseTree::Path(p) => &p.ident,
+        UseTree::Name(n) => &n.ident,
+        UseTree::Rename(r) => &r.ident,
+        _ => return false,
+    };
+    LEGACY_CRATES.iter().any(|c| first == c)
+}
+
+/// Expands a span to whole lines, including the trailing newline and optionally one blank line above.
+fn whole_lines(text: &str, span: Span, swallow_blank: bool) -> (usize, usize) {
+    let (start, end) = (offset_of(text, span.start()), offset_of(text, span.end()));
+    let mut line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[end..].find('\n').map(|i| end + i + 1).unwrap_or(text.len());
+
+    if swallow_blank && line_start > 0 {
+        let prev_start = text[..line_start - 1].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        if text[prev_start..line_start].trim().is_empty() {
+            line_start = prev_start;
+        }
+    }
+
+    (line_start, line_end)
+}
+
+fn indent(text: &str, indent: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|l| if l.trim().is_empty() { l.trim_start_matches([' ', '\t']).to_string() } else { format!("{indent}{l}") })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_DAY: &str = r#"use common::{solution, Answer};
+use itertools::Itertools;
+
+solution!("Report Repair", 1);
+
+fn parse(data: &str) -> miette::Result<Vec<u32>> {
+    data.lines().map(|line| line.parse().map_err(|e| miette::miette!("{e}"))).collect()
+}
+
+fn part_1(input: &str) -> miette::Result<Answer> {
+    let data = parse(input)?;
+    let result: Option<u32> = data.iter().combinations(2).find(|p| p[0] + p[1] == 2020).map(|p| p[0] * p[1]);
+    Ok(result.unwrap_or(0).into())
+}
+
+fn part_2(input: &str) -> miette::Result<Answer> {
+    let data = parse(input)?;
+    if data.len() < 3 {
+        return Ok(0.into());
+    }
+    let result = data.iter().combinations(3).find(|t| t[0] + t[1] + t[2] == 2020).map(|t| t[0] * t[1] * t[2]);
+    Ok(result.unwrap_or(0u32).into())
+}
+
+#[cfg(test)]
+mod test {
+    use common::load_raw;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        1721
+        979
+    "};
+
+    #[test]
+    fn part_1_example() -> miette::Result<()> {
+        assert_eq!(super::part_1(EXAMPLE)?, 514579.into());
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_example() -> miette::Result<()> {
+        assert_eq!(super::part_2(EXAMPLE)?, 241861950.into());
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_edge() {
+        assert!(super::part_2("1").is_ok());
+    }
+
+    #[test]
+    #[ignore]
+    fn part_1() -> miette::Result<()> {
+        let input = load_raw(2020, 1)?;
+        assert_eq!(super::part_1(input.as_str())?, 55776.into());
+        Ok(())
+    }
+
+    #[test]
+    #[ignore]
+    fn part_2() -> miette::Result<()> {
+        let input = load_raw(2020, 1)?;
+        assert_eq!(super::part_2(input.as_str())?, 223162626.into());
+        Ok(())
+    }
+}
+"#;
+
+    fn migrate_day(text: &str) -> (String, Vec<String>) {
+        let migration = migrate_day_text(Utf8Path::new("day01.rs"), text, 2020).unwrap().unwrap();
+        let MigrationAction::Rewrite(text) = migration.action else {
+            panic!("Day files are rewritten, not deleted");
+        };
+        (text, migration.notes)
+    }
+
+    #[test]
+    fn part_fns_move_into_an_aoc_module_keeping_their_results() {
+        let (text, notes) = migrate_day(LEGACY_DAY);
+        let expected = r#"use aoc_runner_macros::{aoc, solution};
+use itertools::Itertools;
+
+fn parse(data: &str) -> miette::Result<Vec<u32>> {
+    data.lines().map(|line| line.parse().map_err(|e| miette::miette!("{e}"))).collect()
+}
+
+// Day 1: Report Repair
+#[aoc(2020, day1)]
+pub mod solutions {
+    use super::*;
+
+    #[solution(part1, main)]
+    pub fn part_1(input: &str) -> miette::Result<u32> {
+        let data = parse(input)?;
+        let result: Option<u32> = data.iter().combinations(2).find(|p| p[0] + p[1] == 2020).map(|p| p[0] * p[1]);
+        Ok(result.unwrap_or(0).into())
+    }
+
+    #[solution(part2, main)]
+    pub fn part_2(input: &str) -> miette::Result<u32> {
+        let data = parse(input)?;
+        if data.len() < 3 {
+            return Ok(0.into());
+        }
+        let result = data.iter().combinations(3).find(|t| t[0] + t[1] + t[2] == 2020).map(|t| t[0] * t[1] * t[2]);
+        Ok(result.unwrap_or(0u32).into())
+    }
+}
+"#;
+        assert_eq!(&text[..expected.len()], expected);
+        assert!(notes.contains(&"`part_1` returned `miette::Result<Answer>`; now returns `miette::Result<u32>`".into()));
+    }
+
+    /// The legacy day with its part fns swapped for `parts`.
+    fn with_parts(parts: &str) -> String {
+        let start = LEGACY_DAY.find("fn part_1").unwrap();
+        let end = LEGACY_DAY.find("#[cfg(test)]").unwrap();
+        format!("{}{parts}\n{}", &LEGACY_DAY[..start], &LEGACY_DAY[end..])
+    }
+
+    #[test]
+    fn answer_types_are_read_off_returned_values() {
+        let (text, _) = migrate_day(&with_parts(
+            r#"fn part_1(input: &str) -> miette::Result<Answer> {
+    let best = input.lines().map(|line| line.len()).max();
+    Ok(format!("{best:?}").into())
+}
+
+fn part_2(input: &str) -> miette::Result<Answer> {
+    let total = input.lines().map(|line| line.parse::<i64>().unwrap()).sum::<i64>();
+    let lines = input.lines().count();
+    if lines == 0 {
+        return Ok(lines.into());
+    }
+    Ok(total.into())
+}
+"#,
+        ));
+        assert!(text.contains("pub fn part_1(input: &str) -> miette::Result<String> {"), "{text}");
+        assert!(text.contains("pub fn part_2(input: &str) -> miette::Result<usize> {"), "{text}");
+    }
+
+    #[test]
+    fn unknown_answer_types_are_an_error_naming_the_part() {
+        let text = with_parts(
+            r#"fn part_1(input: &str) -> miette::Result<Answer> {
+    Ok(input.len().into())
+}
+
+fn part_2(input: &str) -> miette::Result<Answer> {
+    let answer = input.lines().map(|line| line.parse().unwrap()).fold(0, |a, b| a ^ b);
+    Ok(answer.into())
+}
+"#,
+        );
+        let error = migrate_day_text(Utf8Path::new("day01.rs"), &text, 2020).err().unwrap();
+        assert!(error.to_string().starts_with("day01.rs: can't tell what type `part_2` answers with"), "{error}");
+    }
+
+    #[test]
+    fn tests_become_cases_keeping_real_input_answers() {
+        let (text, notes) = migrate_day(LEGACY_DAY);
+        let expected = r#"#[cfg(test)]
+mod test {
+    use aoc_runner_macros::aoc_case;
+    use indoc::indoc;
+
+    #[aoc_case(514579, 241861950)]
+    const EXAMPLE: &str = indoc! {"
+        1721
+        979
+    "};
+
+    #[test]
+    fn part_2_edge() {
+        assert!(super::solutions::part_2("1").is_ok());
+    }
+
+    #[aoc_case(55776, 223162626, ignore = "needs input/2020/1.txt")]
+    const PUZZLE_INPUT: &str = super::AOC_RAW_INPUT;
+}
+"#;
+        assert_eq!(&text[text.find("#[cfg(test)]").unwrap()..], expected);
+        assert!(notes.contains(&"kept hand-written test `part_2_edge`".into()));
+        assert!(notes.iter().any(|note| note.starts_with("moved the real-input answers of `part_1`, `part_2`")));
+    }
+
+    #[test]
+    fn files_without_the_legacy_macro_are_left_alone() {
+        let text = "#[aoc(2020, day1)]\npub mod solutions {}\n";
+        assert!(migrate_day_text(Utf8Path::new("day01.rs"), text, 2020).unwrap().is_none());
+    }
+
+    #[test]
+    fn year_manifest_swaps_common_for_the_runner() {
+        let text = "[package]\nname = \"aoc_2020\"\n\n[dependencies]\ncommon = { workspace = true }\nitertools = { workspace = true }\n";
+        let expected = "[package]\nname = \"aoc_2020\"\n\n[dependencies]\nitertools = { workspace = true }\naoc-runner = { path = \"../aoc-runner\" }\naoc-runner-macros = { path = \"../aoc-runner-macros\" }\n";
+        assert_eq!(migrate_year_manifest_text(text).unwrap().as_deref(), Some(expected));
+        assert_eq!(migrate_year_manifest_text(expected).unwrap(), None);
+    }
+
+    #[test]
+    fn workspace_manifest_adds_members_and_drops_missing_legacy_crates() {
+        let root = Utf8Path::new("/nonexistent-workspace");
+        let text = "[workspace]\nmembers=[\n   \"aoc_2022\",\n   \"aoc_2021\"\n]\n\n[workspace.dependencies]\ncommon = { path = \"common\" }\nahash = \"0.8\"\n";
+        let expected = "[workspace]\nmembers=[\n   \"aoc_2022\",\n   \"aoc_2021\",\n   \"aoc_2020\"\n]\n\n[workspace.dependencies]\nahash = \"0.8\"\n";
+        let (text, notes) = migrate_workspace_manifest_text(root, text, &["aoc_2020".into()]).unwrap().unwrap();
+        assert_eq!(text, expected);
+        assert_eq!(
+            notes,
+            [
+                "added `aoc_2020` to workspace members",
+                "removed workspace dependency `common`, its path does not exist"
+            ]
+        );
+        assert!(migrate_workspace_manifest_text(root, expected, &["aoc_2020".into()]).unwrap().is_none());
+    }
+
+    #[test]
+    fn workspace_manifest_without_members_is_an_error() {
+        let result = migrate_workspace_manifest_text(Utf8Path::new("."), "[package]\nname = \"x\"\n", &[]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Named puzzle parameters, for constants that differ between the examples and the real input.
+//!
+//! A generator, solver or solution declares them as `params(name = default)` in its attribute and
+//! takes them as extra arguments of the same names. The default is for the real input. `aoc run
+//! --param name=value` overrides it through an `AOC_PARAM_<NAME>` variable of the day process, and
+//! `#[aoc_case(params(name = value))]` overrides it for one test's thread, with a value of the
+//! parameter's declared type.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    env,
+    ops::{Range, RangeInclusive},
+    rc::Rc,
+};
+
+const PARAM_VAR_PREFIX: &str = "AOC_PARAM_";
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<&'static str, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// The environment variable that sets a parameter for a day process.
+pub fn env_var(name: &str) -> String {
+    format!("{PARAM_VAR_PREFIX}{}", name.to_ascii_uppercase())
+}
+
+/// Splits a `name=value` command line argument.
+pub fn parse_assignment(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("`{text}` isn't of the form `name=value`")),
+    }
+}
+
+/// The value of a parameter: this thread's override, else the process's, else `default`.
+/// Panics if the value given doesn't parse as the parameter's type.
+pub fn get<T: Param + Clone + 'static>(name: &str, default: impl FnOnce() -> T) -> T {
+    if let Some(value) = OVERRIDES.with(|overrides| overrides.borrow().get(name).cloned()) {
+        return value
+            .downcast_ref::<T>()
+            .cloned()
+            .unwrap_or_else(|| panic!("parameter `{name}` is overridden with a value of another type"));
+    }
+    match env::var(env_var(name)) {
+        Err(_) => default(),
+        Ok(text) => T::parse_param(&text)
+            .unwrap_or_else(|| panic!("parameter `{name}` can't be parsed from `{text}`")),
+    }
+}
+
+/// One parameter's value for a test. `#[aoc]` generates a function making each of a day's
+/// parameters, taking the type it's declared with, for `#[aoc_case]` to call.
+pub struct Override {
+    name: &'static str,
+    value: Rc<dyn Any>,
+}
+
+impl Override {
+    pub fn new<T: Param + 'static>(name: &'static str, value: T) -> Override {
+        Override { name, value: Rc::new(value) }
+    }
+}
+
+/// Runs `f` with parameters overridden on this thread, as `#[aoc_case]` does for its solutions.
+pub fn with_overrides<R>(overrides: Vec<Override>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Vec<(&'static str, Option<Rc<dyn Any>>)>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OVERRIDES.with(|current| {
+                let mut current = current.borrow_mut();
+                for (name, previous) in self.0.drain(..).rev() {
+                    match previous {
+                        Some(value) => current.insert(name, value),
+                        None => current.remove(&name),
+                    };
+                }
+            });
+        }
+    }
+
+    let _restore = OVERRIDES.with(|current| {
+        let mut current = current.borrow_mut();
+        Restore(
+            overrides
+                .into_iter()
+                .map(|Override { name, value }| (name, current.insert(name, value)))
+                .collect(),
+        )
+    });
+    f()
+}
+
+/// A type a parameter can be given as from the command line.
+pub trait Param: Sized {
+    fn parse_param(text: &str) -> Option<Self>;
+}
+
+/// Numbers may be written as in Rust source, eg. `1_000_000` or `- 3`.
+macro_rules! number_params {
+    ($($ty:ty),*) => {
+        $(impl Param for $ty {
+            fn parse_param(text: &str) -> Option<Self> {
+                let digits: String = text.chars().filter(|c| *c != '_' && !c.is_whitespace()).collect();
+                digits.parse().ok()
+            }
+        })*
+    };
+}
+
+number_params!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl Param for bool {
+    fn parse_param(text: &str) -> Option<Self> {
+        text.trim().parse().ok()
+    }
+}
+
+/// Surrounding quotes are dropped, so `"abc"` and `abc` are the same.
+impl Param for String {
+    fn parse_param(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let unquoted = text.strip_prefix('"').and_then(|t| t.strip_suffix('"'));
+        Some(unquoted.unwrap_or(text).to_string())
+    }
+}
+
+impl<T: Param> Param for RangeInclusive<T> {
+    fn parse_param(text: &str) -> Option<Self> {
+        let (start, end) = text.split_once("..=")?;
+        Some(T::parse_param(start)?..=T::parse_param(end)?)
+    }
+}
+
+impl<T: Param> Param for Range<T> {
+    fn parse_param(text: &str) -> Option<Self> {
+        if text.contains("..=") {
+            return None;
+        }
+        let (start, end) = text.split_once("..")?;
+        Some(T::parse_param(start)?..T::parse_param(end)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_parse_as_written_in_source() {
+        assert_eq!(u64::parse_param("1_000_000"), Some(1_000_000));
+        assert_eq!(i32::parse_param("- 3"), Some(-3));
+        assert_eq!(f64::parse_param("2e14"), Some(2e14));
+        assert_eq!(u8::parse_param("256"), None);
+        assert_eq!(usize::parse_param("ten"), None);
+    }
+
+    #[test]
+    fn strings_bools_and_ranges_parse() {
+        assert_eq!(String::parse_param(" \"abc\" "), Some("abc".to_string()));
+        assert_eq!(String::parse_param("abc"), Some("abc".to_string()));
+        assert_eq!(bool::parse_param("true"), Some(true));
+        assert_eq!(RangeInclusive::<f64>::parse_param("7.0..=27"), Some(7.0..=27.0));
+        assert_eq!(Range::<u32>::parse_param("1..1_000"), Some(1..1000));
+        assert_eq!(Range::<u32>::parse_param("1..=10"), None);
+        assert_eq!(RangeInclusive::<u32>::parse_param("1..10"), None);
+    }
+
+    #[test]
+    fn assignments_split_on_the_first_equals() {
+        assert_eq!(parse_assignment(" steps = 6 "), Ok(("steps".to_string(), "6".to_string())));
+        assert_eq!(parse_assignment("range=7..=27"), Ok(("range".to_string(), "7..=27".to_string())));
+        assert!(parse_assignment("=6").is_err());
+        assert!(parse_assignment("steps").is_err());
+        assert_eq!(env_var("max_coord"), "AOC_PARAM_MAX_COORD");
+    }
+
+    #[test]
+    fn overrides_beat_the_environment_which_beats_the_default() {
+        // Only this test uses this parameter, so setting its variable can't affect another.
+        let name = "precedence_test_steps";
+        assert_eq!(get(name, || 64u32), 64);
+        env::set_var(env_var(name), "1_000");
+        assert_eq!(get(name, || 64u32), 1000);
+        let overridden = with_overrides(vec![Override::new(name, 6u32)], || get(name, || 64u32));
+        assert_eq!(overridden, 6);
+        assert_eq!(get(name, || 64u32), 1000);
+        env::remove_var(env_var(name));
+        assert_eq!(get(name, || 64u32), 64);
+    }
+
+    #[test]
+    fn overrides_are_restored_after_nesting_and_panics() {
+        let steps = || get("steps", || 64u32);
+        let nested = with_overrides(vec![Override::new("steps", 6u32)], || {
+            let inner = with_overrides(vec![Override::new("steps", 10u32), Override::new("steps", 12u32)], steps);
+            (inner, steps())
+        });
+        assert_eq!(nested, (12, 6));
+        assert_eq!(steps(), 64);
+
+        let panicked = std::panic::catch_unwind(|| with_overrides(vec![Override::new("steps", 6u32)], || panic!()));
+        assert!(panicked.is_err());
+        assert_eq!(steps(), 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "parameter `steps` is overridden with a value of another type")]
+    fn overrides_of_another_type_panic() {
+        with_overrides(vec![Override::new("steps", 6i64)], || get("steps", || 64u32));
+    }
+}
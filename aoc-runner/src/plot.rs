@@ -0,0 +1,417 @@
+//! Benchmark charts drawn from the saved history: runtime per day for a year, the variants of one
+//! day side by side, and how each variant's time has moved across runs.
+
+use std::{collections::BTreeMap, fs::create_dir_all, ops::Range, time::Duration};
+
+use anyhow as ah;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use plotters::{coord::Shift, prelude::*};
+
+use crate::{cli::PlotFormat, history::BenchRecord};
+
+const SIZE: (u32, u32) = (1024, 600);
+const FONT: &str = "sans-serif";
+
+/// Something that can be drawn onto either backend.
+trait Chart {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>>;
+}
+
+fn render(chart: &impl Chart, path: &Utf8Path, format: PlotFormat) -> ah::Result<()> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+
+    match format {
+        PlotFormat::Svg => {
+            let root = SVGBackend::new(path, SIZE).into_drawing_area();
+            chart.draw(&root)?;
+            root.present()?;
+        }
+        PlotFormat::Png => {
+            let root = BitMapBackend::new(path, SIZE).into_drawing_area();
+            chart.draw(&root)?;
+            root.present()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws every chart for `year` and `day` into `dir`, returning the files written.
+pub fn plot_day(
+    records: &[BenchRecord],
+    year: u32,
+    day: u32,
+    dir: &Utf8Path,
+    format: PlotFormat,
+) -> ah::Result<Vec<Utf8PathBuf>> {
+    let ext = format.extension();
+    let dir = dir.join(year.to_string());
+    let mut written = Vec::new();
+
+    if let Some(chart) = YearChart::from_history(records, year) {
+        let path = dir.join(format!("runtime.{ext}"));
+        render(&chart, &path, format)?;
+        written.push(path);
+    }
+    if let Some(chart) = VariantChart::from_history(records, year, day) {
+        let path = dir.join(format!("day{day:02}-variants.{ext}"));
+        render(&chart, &path, format)?;
+        written.push(path);
+    }
+    if let Some(chart) = HistoryChart::from_history(records, year, day) {
+        let path = dir.join(format!("day{day:02}-history.{ext}"));
+        render(&chart, &path, format)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Later records replace earlier ones, so this keeps the latest result per variant.
+fn latest<'r>(records: impl Iterator<Item = &'r BenchRecord>) -> Vec<&'r BenchRecord> {
+    let mut by_key = BTreeMap::new();
+    for record in records {
+        by_key.insert((record.day, record.part, record.label.as_str()), record);
+    }
+    by_key.into_values().collect()
+}
+
+/// Bounds for a log axis holding `times`, with some room either side.
+fn log_range(times: impl Iterator<Item = u64>) -> Range<f64> {
+    let (min, max) = times.fold((u64::MAX, 1), |(min, max), t| (min.min(t), max.max(t)));
+    let min = min.clamp(1, max);
+
+    (min as f64 / 2.0)..(max as f64 * 2.0)
+}
+
+fn fmt_ns(ns: &f64) -> String {
+    format!("{:.0?}", Duration::from_nanos(*ns as u64))
+}
+
+/// The index at `x`, for category axes drawn on a continuous scale. Only whole positions are labelled.
+fn category_index(x: f64) -> Option<usize> {
+    ((x - x.round()).abs() < 1e-6 && x >= 0.0).then_some(x.round() as usize)
+}
+
+fn category_label(labels: &[String], x: f64) -> String {
+    category_index(x)
+        .and_then(|idx| labels.get(idx).cloned())
+        .unwrap_or_default()
+}
+
+/// Runtime of each day's primary solutions, as bars on a log scale.
+struct YearChart {
+    year: u32,
+    days: Vec<(u32, [Option<u64>; 2])>,
+}
+
+impl YearChart {
+    fn from_history(records: &[BenchRecord], year: u32) -> Option<YearChart> {
+        let mut days: BTreeMap<u32, [Option<u64>; 2]> = BTreeMap::new();
+        for record in latest(records.iter().filter(|r| r.year == year && r.primary)) {
+            if let Some(slot) = days.entry(record.day).or_default().get_mut(record.part as usize - 1) {
+                *slot = Some(record.median_ns);
+            }
+        }
+
+        (!days.is_empty()).then(|| YearChart {
+            year,
+            days: days.into_iter().collect(),
+        })
+    }
+}
+
+impl Chart for YearChart {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        root.fill(&WHITE)?;
+        let range = log_range(self.days.iter().flat_map(|(_, parts)| parts.iter().flatten().copied()));
+        let floor = range.start;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(format!("Advent of Code {}: runtime per day", self.year), (FONT, 28))
+            .margin(16)
+            .x_label_area_size(40)
+            .y_label_area_size(80)
+            .build_cartesian_2d(0.5f64..25.5f64, range.log_scale())?;
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(26)
+            .x_label_formatter(&|x| category_index(*x).map(|d| d.to_string()).unwrap_or_default())
+            .x_desc("Day")
+            .y_label_formatter(&fmt_ns)
+            .y_desc("Time")
+            .draw()?;
+
+        for part in 0..2 {
+            let color = Palette99::pick(part);
+            chart
+                .draw_series(self.days.iter().filter_map(|(day, parts)| {
+                    let x = *day as f64 - 0.4 + 0.4 * part as f64;
+                    parts[part].map(|ns| Rectangle::new([(x, floor), (x + 0.4, ns as f64)], color.filled()))
+                }))?
+                .label(format!("Part {}", part + 1))
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+        }
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .border_style(BLACK)
+            .background_style(WHITE.mix(0.8))
+            .draw()
+    }
+}
+
+/// The latest time of every variant for one day, as horizontal bars.
+struct VariantChart {
+    year: u32,
+    day: u32,
+    variants: Vec<(u8, String, u64)>,
+}
+
+impl VariantChart {
+    fn from_history(records: &[BenchRecord], year: u32, day: u32) -> Option<VariantChart> {
+        let variants: Vec<(u8, String, u64)> = latest(records.iter().filter(|r| r.year == year && r.day == day))
+            .into_iter()
+            .map(|r| {
+                let label = if r.primary {
+                    format!("{} (primary)", r.label)
+                } else {
+                    r.label.clone()
+                };
+                (r.part, label, r.median_ns)
+            })
+            .collect();
+
+        (!variants.is_empty()).then_some(VariantChart { year, day, variants })
+    }
+}
+
+impl Chart for VariantChart {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        root.fill(&WHITE)?;
+        let labels: Vec<String> = self
+            .variants
+            .iter()
+            .map(|(part, label, _)| format!("P{part} {label}"))
+            .collect();
+        let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u32 * 8 + 16;
+        let range = log_range(self.variants.iter().map(|(_, _, ns)| *ns));
+        let floor = range.start;
+        let count = self.variants.len() as f64;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(format!("{} day {}: variants", self.year, self.day), (FONT, 28))
+            .margin(16)
+            .x_label_area_size(40)
+            .y_label_area_size(label_width)
+            .build_cartesian_2d(range.log_scale(), -0.5f64..count - 0.5)?;
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .y_labels(self.variants.len() + 1)
+            .y_label_formatter(&|y| category_label(&labels, *y))
+            .x_label_formatter(&fmt_ns)
+            .x_desc("Time")
+            .draw()?;
+
+        chart.draw_series(self.variants.iter().enumerate().map(|(idx, (part, _, ns))| {
+            let y = idx as f64;
+            let color = Palette99::pick(*part as usize - 1);
+            Rectangle::new([(floor, y - 0.35), (*ns as f64, y + 0.35)], color.filled())
+        }))?;
+
+        Ok(())
+    }
+}
+
+/// Each variant's median time across the saved runs of one day.
+struct HistoryChart {
+    year: u32,
+    day: u32,
+    runs: Vec<String>,
+    series: Vec<(String, Vec<(f64, f64)>)>,
+}
+
+impl HistoryChart {
+    fn from_history(records: &[BenchRecord], year: u32, day: u32) -> Option<HistoryChart> {
+        // Records from one `aoc bench` invocation share a date, which makes it the run's identity.
+        let mut runs: Vec<(&str, &str)> = Vec::new();
+        let mut series: BTreeMap<(u8, &str), Vec<(f64, f64)>> = BTreeMap::new();
+        for record in records.iter().filter(|r| r.year == year && r.day == day) {
+            if runs.last().map(|(date, _)| *date) != Some(record.date.as_str()) {
+                runs.push((&record.date, &record.commit));
+            }
+            let x = (runs.len() - 1) as f64;
+            series
+                .entry((record.part, &record.label))
+                .or_default()
+                .push((x, record.median_ns as f64));
+        }
+
+        (!runs.is_empty()).then(|| HistoryChart {
+            year,
+            day,
+            runs: runs
+                .into_iter()
+                .map(|(date, commit)| format!("{:.7} {}", commit, date.get(..10).unwrap_or(date)))
+                .collect(),
+            series: series
+                .into_iter()
+                .map(|((part, label), points)| (format!("P{part} {label}"), points))
+                .collect(),
+        })
+    }
+}
+
+impl Chart for HistoryChart {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        root.fill(&WHITE)?;
+        let range = log_range(
+            self.series
+                .iter()
+                .flat_map(|(_, points)| points.iter().map(|(_, ns)| *ns as u64)),
+        );
+        let last = self.runs.len() as f64 - 1.0;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(format!("{} day {}: history", self.year, self.day), (FONT, 28))
+            .margin(16)
+            .x_label_area_size(40)
+            .y_label_area_size(80)
+            .build_cartesian_2d(-0.5f64..last + 0.5, range.log_scale())?;
+        chart
+            .configure_mesh()
+            .x_labels(self.runs.len().min(12) + 1)
+            .x_label_formatter(&|x| category_label(&self.runs, *x))
+            .x_desc("Run")
+            .y_label_formatter(&fmt_ns)
+            .y_desc("Time")
+            .draw()?;
+
+        for (idx, (name, points)) in self.series.iter().enumerate() {
+            let color = Palette99::pick(idx).to_rgba();
+            chart
+                .draw_series(LineSeries::new(points.iter().copied(), color.stroke_width(2)))?
+                .label(name)
+                .legend(move |(x, y)| PathElement::new([(x, y), (x + 16, y)], color.stroke_width(2)));
+            chart.draw_series(points.iter().map(|&point| Circle::new(point, 3, color.filled())))?;
+        }
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .border_style(BLACK)
+            .background_style(WHITE.mix(0.8))
+            .draw()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(date: &str, day: u32, part: u8, label: &str, median_ns: u64) -> BenchRecord {
+        BenchRecord {
+            commit: "0123456789".to_string(),
+            dirty: false,
+            date: date.to_string(),
+            baseline: None,
+            year: 2023,
+            day,
+            part,
+            label: label.to_string(),
+            primary: label == "main",
+            median_ns,
+            samples_ns: vec![median_ns],
+            alloc: None,
+        }
+    }
+
+    fn history() -> Vec<BenchRecord> {
+        vec![
+            record("2023-12-01T10:00:00Z", 1, 1, "main", 400),
+            record("2023-12-01T10:00:00Z", 1, 1, "fast", 300),
+            record("2023-12-01T10:00:00Z", 2, 2, "main", 9_000),
+            record("2023-12-02T10:00:00Z", 1, 1, "main", 200),
+            record("2023-12-02T10:00:00Z", 1, 2, "main", 700),
+        ]
+    }
+
+    #[test]
+    fn latest_keeps_the_last_record_of_each_variant() {
+        let records = history();
+        let kept: Vec<_> = latest(records.iter())
+            .into_iter()
+            .map(|r| (r.day, r.part, r.label.as_str(), r.median_ns))
+            .collect();
+        assert_eq!(kept, [(1, 1, "fast", 300), (1, 1, "main", 200), (1, 2, "main", 700), (2, 2, "main", 9_000)]);
+    }
+
+    #[test]
+    fn log_range_leaves_room_around_the_times() {
+        assert_eq!(log_range([40, 10, 1_000].into_iter()), 5.0..2_000.0);
+        assert_eq!(log_range([300].into_iter()), 150.0..600.0);
+        assert_eq!(log_range([8, 8, 8].into_iter()), 4.0..16.0);
+        // A log axis can't start at zero.
+        assert_eq!(log_range([0, 0].into_iter()), 0.5..2.0);
+        assert_eq!(log_range(std::iter::empty()), 0.5..2.0);
+    }
+
+    #[test]
+    fn the_year_chart_has_each_days_latest_primary_times() {
+        let chart = YearChart::from_history(&history(), 2023).unwrap();
+        assert_eq!(chart.days, [(1, [Some(200), Some(700)]), (2, [None, Some(9_000)])]);
+        assert!(YearChart::from_history(&history(), 2022).is_none());
+    }
+
+    #[test]
+    fn the_variant_chart_marks_the_primary_variant() {
+        let chart = VariantChart::from_history(&history(), 2023, 1).unwrap();
+        let variants: Vec<_> = chart.variants.iter().map(|(part, label, ns)| (*part, label.as_str(), *ns)).collect();
+        assert_eq!(variants, [(1, "fast", 300), (1, "main (primary)", 200), (2, "main (primary)", 700)]);
+        assert!(VariantChart::from_history(&history(), 2023, 3).is_none());
+    }
+
+    #[test]
+    fn the_history_chart_has_a_point_per_run_and_variant() {
+        let chart = HistoryChart::from_history(&history(), 2023, 1).unwrap();
+        assert_eq!(chart.runs, ["0123456 2023-12-01", "0123456 2023-12-02"]);
+        let series: Vec<_> = chart.series.iter().map(|(name, points)| (name.as_str(), points.as_slice())).collect();
+        assert_eq!(
+            series,
+            [
+                ("P1 fast", &[(0.0, 300.0)][..]),
+                ("P1 main", &[(0.0, 400.0), (1.0, 200.0)][..]),
+                ("P2 main", &[(1.0, 700.0)][..]),
+            ]
+        );
+        assert!(HistoryChart::from_history(&history(), 2022, 1).is_none());
+    }
+
+    #[test]
+    fn plot_day_writes_each_chart_with_data() {
+        let dir = std::env::temp_dir().join(format!("aoc-plot-{}", std::process::id()));
+        let dir = Utf8PathBuf::try_from(dir).unwrap();
+        let written = plot_day(&history(), 2023, 2, &dir, PlotFormat::Svg).unwrap();
+        let names: Vec<_> = written.iter().filter_map(|path| path.file_name()).collect();
+        assert_eq!(names, ["runtime.svg", "day02-variants.svg", "day02-history.svg"]);
+        assert!(written.iter().all(|path| path.starts_with(dir.join("2023")) && path.exists()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,328 @@
+//! Differential testing of a day's variants on generated inputs.
+//!
+//! `#[aoc_property]` marks a `fn(&mut Rng, usize) -> String` in a day's tests that writes a random
+//! puzzle input of roughly the given size. The test it generates runs every variant of each part
+//! on inputs of growing size and fails on the first one they disagree on, after shrinking it: first
+//! to the smallest size that still disagrees, then by dropping lines.
+
+use std::{
+    cell::Cell,
+    fmt::Write,
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use crate::registry::{DayEntry, ErasedSolution};
+
+/// Seeds tried at each smaller size while shrinking.
+const SHRINK_SEEDS: u64 = 64;
+
+thread_local! {
+    /// Set while this thread runs variants whose panics are caught and reported as outcomes.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// A small, seeded random source for input generators, so every case can be reproduced.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    /// SplitMix64.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn between(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let span = range.end() - range.start();
+        match span.checked_add(1) {
+            Some(len) => range.start() + self.next_u64() % len,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `len`, which must not be zero.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.between(0..=len as u64 - 1) as usize
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 <= p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+}
+
+/// How many inputs to try, and how large they get.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub cases: u32,
+    pub max_size: usize,
+    /// Only compare this part's variants.
+    pub part: Option<u8>,
+}
+
+/// What one variant made of an input: its answer, or why it had none.
+type Outcome = Result<String, String>;
+
+/// Entry point for the generated property tests. Panics with the shrunk input and every variant's
+/// outcome on it if the variants of a part disagree.
+pub fn check(day: &DayEntry, generate: fn(&mut Rng, usize) -> String, config: Config) {
+    let parts: Vec<u8> = [1, 2]
+        .into_iter()
+        .filter(|&part| config.part.is_none_or(|wanted| wanted == part))
+        .filter(|&part| day.solutions(part).len() > 1)
+        .collect();
+    assert!(
+        !parts.is_empty(),
+        "Day {} has no part with two or more variants to compare",
+        day.day
+    );
+
+    let cases = config.cases.max(1);
+    for case in 0..cases {
+        let seed = u64::from(case);
+        let size = 1 + case as usize * config.max_size.saturating_sub(1) / cases as usize;
+        let input = generate(&mut Rng::new(seed), size);
+        for &part in &parts {
+            let outcomes = run_variants(day.solutions(part), &input);
+            if disagree(&outcomes) {
+                let failing = failing(&outcomes);
+                let still_fails = |candidate: &str| {
+                    let outcomes = run_variants(day.solutions(part), candidate);
+                    disagree(&outcomes) && failing_within(&outcomes, &failing)
+                };
+                let (size, input) = shrink_size(generate, size, input, &still_fails);
+                let input = shrink_lines(input, &still_fails);
+                panic!("{}", report(day, part, seed, size, &input));
+            }
+        }
+    }
+}
+
+fn run_variants(solutions: &[ErasedSolution], input: &str) -> Vec<Outcome> {
+    QUIET_HOOK.call_once(quiet_panics);
+    QUIET.with(|quiet| quiet.set(true));
+    let outcomes = solutions
+        .iter()
+        .map(
+            |solution| match panic::catch_unwind(AssertUnwindSafe(|| solution(input))) {
+                Ok(Ok(answer)) => Ok(answer),
+                Ok(Err(error)) => Err(error.to_string()),
+                Err(payload) => Err(panic_message(payload.as_ref())),
+            },
+        )
+        .collect();
+    QUIET.with(|quiet| quiet.set(false));
+    outcomes
+}
+
+/// Keeps the panic hook from printing the panics of variants on this thread, which shrinking can
+/// cause dozens of. Other threads, eg. other tests, still print theirs.
+fn quiet_panics() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !QUIET.with(Cell::get) {
+            previous(info);
+        }
+    }));
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    format!("panicked: {message}")
+}
+
+/// Variants disagree when their answers differ, or some answer an input that others reject.
+/// Inputs every variant rejects don't count.
+fn disagree(outcomes: &[Outcome]) -> bool {
+    let mut answers = outcomes.iter().map(|outcome| outcome.as_ref().ok());
+    let first = answers.next().flatten();
+    answers.any(|answer| answer != first)
+}
+
+fn failing(outcomes: &[Outcome]) -> Vec<usize> {
+    (0..outcomes.len()).filter(|&i| outcomes[i].is_err()).collect()
+}
+
+/// Whether only variants that already failed still do, so shrinking doesn't drift towards inputs
+/// that are just malformed.
+fn failing_within(outcomes: &[Outcome], failing: &[usize]) -> bool {
+    (0..outcomes.len()).all(|i| outcomes[i].is_ok() || failing.contains(&i))
+}
+
+/// The smallest size, and the input at it, where some seed still disagrees.
+fn shrink_size(
+    generate: fn(&mut Rng, usize) -> String,
+    size: usize,
+    input: String,
+    still_fails: &dyn Fn(&str) -> bool,
+) -> (usize, String) {
+    for smaller in 1..size {
+        for seed in 0..SHRINK_SEEDS {
+            let candidate = generate(&mut Rng::new(seed), smaller);
+            if still_fails(&candidate) {
+                return (smaller, candidate);
+            }
+        }
+    }
+    (size, input)
+}
+
+/// Drops runs of lines, halving the run length down to single lines, for as long as that keeps
+/// the input failing.
+fn shrink_lines(input: String, still_fails: &dyn Fn(&str) -> bool) -> String {
+    let trailing = if input.ends_with('\n') { "\n" } else { "" };
+    let mut lines: Vec<&str> = input.lines().collect();
+    let join = |lines: &[&str]| format!("{}{trailing}", lines.join("\n"));
+
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        let mut removed = false;
+        while start + chunk <= lines.len() && lines.len() > chunk {
+            let candidate: Vec<&str> = lines[..start].iter().chain(&lines[start + chunk..]).copied().collect();
+            if still_fails(&join(&candidate)) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if !removed {
+            chunk /= 2;
+        }
+    }
+
+    join(&lines)
+}
+
+fn report(day: &DayEntry, part: u8, seed: u64, size: usize, input: &str) -> String {
+    let mut report = format!(
+        "Part {part} variants of {} day {} disagree on a generated input (seed {seed}, size {size}):\n{input}",
+        day.year, day.day
+    );
+    if !input.ends_with('\n') {
+        report.push('\n');
+    }
+    for (label, outcome) in day.labels(part).iter().zip(run_variants(day.solutions(part), input)) {
+        let _ = match outcome {
+            Ok(answer) => writeln!(report, "  {label}: {answer}"),
+            Err(error) => writeln!(report, "  {label}: {error}"),
+        };
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::{DayFailure, RunOptions, SolveError};
+
+    /// One number from 0 to 9 per line, `size` lines.
+    fn digits(rng: &mut Rng, size: usize) -> String {
+        (0..size).map(|_| format!("{}\n", rng.between(0..=9))).collect()
+    }
+
+    fn numbers(input: &str) -> Result<Vec<u64>, SolveError> {
+        input
+            .lines()
+            .map(|line| line.parse().map_err(|error| SolveError::new("solution", "", error)))
+            .collect()
+    }
+
+    fn sum(input: &str) -> Result<String, SolveError> {
+        Ok(numbers(input)?.iter().sum::<u64>().to_string())
+    }
+
+    /// Forgets every 7.
+    fn sum_without_sevens(input: &str) -> Result<String, SolveError> {
+        Ok(numbers(input)?.iter().filter(|&&n| n != 7).sum::<u64>().to_string())
+    }
+
+    fn run(_: &str, _: &RunOptions) -> Result<(), DayFailure> {
+        Ok(())
+    }
+
+    fn day(p1_solutions: &'static [ErasedSolution]) -> DayEntry {
+        DayEntry {
+            year: 2099,
+            day: 1,
+            input: "",
+            p1_labels: &["sum", "other"],
+            p1_tags: &[],
+            p1_solutions,
+            p2_labels: &[],
+            p2_tags: &[],
+            p2_solutions: &[],
+            run,
+            bench_cases: None,
+        }
+    }
+
+    fn config() -> Config {
+        Config {
+            cases: 20,
+            max_size: 40,
+            part: None,
+        }
+    }
+
+    fn failure(day: &DayEntry) -> Option<String> {
+        panic::catch_unwind(AssertUnwindSafe(|| check(day, digits, config())))
+            .err()
+            .map(|payload| panic_message(payload.as_ref()))
+    }
+
+    #[test]
+    fn agreeing_variants_pass() {
+        assert_eq!(failure(&day(&[sum, sum])), None);
+    }
+
+    #[test]
+    fn disagreement_shrinks_to_a_single_line() {
+        let message = failure(&day(&[sum, sum_without_sevens])).expect("the variants disagree");
+        assert!(message.contains("size 1):\n7\n"), "{message}");
+        assert!(message.contains("  sum: 7\n  other: 0\n"), "{message}");
+    }
+
+    #[test]
+    fn shrinking_lines_keeps_only_what_still_fails() {
+        let still_fails = |input: &str| sum(input).ok() != sum_without_sevens(input).ok();
+        let input = "1\n2\n7\n3\n4\n7\n5\n".to_string();
+        assert_eq!(shrink_lines(input, &still_fails), "7\n");
+    }
+
+    #[test]
+    fn shrinking_size_finds_the_smallest_failing_size() {
+        let still_fails = |input: &str| sum(input).ok() != sum_without_sevens(input).ok();
+        let (size, input) = shrink_size(digits, 30, "unused".to_string(), &still_fails);
+        assert_eq!(size, 1);
+        assert_eq!(input, "7\n");
+    }
+
+    #[test]
+    fn inputs_every_variant_rejects_are_not_disagreements() {
+        assert!(!disagree(&[Err("bad".to_string()), Err("worse".to_string())]));
+        assert!(disagree(&[Ok("1".to_string()), Err("bad".to_string())]));
+        assert!(disagree(&[Ok("1".to_string()), Ok("2".to_string())]));
+        assert!(!disagree(&[Ok("1".to_string()), Ok("1".to_string())]));
+    }
+}
@@ -0,0 +1,384 @@
+//! Every `#[aoc]` day of a crate, behind one binary.
+//!
+//! `#[aoc]` gives each day module an `AOC_DAY` entry, and `day_registry!` in the crate root collects
+//! them into `DAYS`. The crate's `src/main.rs` is then just
+//! `fn main() { aoc_runner::registry::main(aoc_2023::DAYS) }`, which can list the days and run any
+//! day, part or variant by name. `main_all` does the same across every year crate of a workspace.
+
+use std::{fs, path::PathBuf};
+
+use clap::{error::ErrorKind, Args, CommandFactory, FromArgMatches, Parser};
+
+use crate::{
+    bench::BenchCase,
+    harness::{isolated_day, label_matches, DayFailure, RunOptions, SolveError, VariantTags},
+};
+
+/// A solution with its answer already formatted, so days with different answer types fit in one list.
+pub type ErasedSolution = fn(&str) -> Result<String, SolveError>;
+
+/// One `#[aoc]` day, as registered by the macro.
+pub struct DayEntry {
+    pub year: u32,
+    pub day: u32,
+    /// The day's real input, empty if there wasn't one when it was compiled.
+    pub input: &'static str,
+    /// Each part's variants are listed primary first.
+    pub p1_labels: &'static [&'static str],
+    pub p1_tags: &'static [VariantTags],
+    pub p1_solutions: &'static [ErasedSolution],
+    pub p2_labels: &'static [&'static str],
+    pub p2_tags: &'static [VariantTags],
+    pub p2_solutions: &'static [ErasedSolution],
+    /// Runs the day as its own `main` would, on the input and with the options given.
+    pub run: fn(&str, &RunOptions) -> Result<(), DayFailure>,
+    /// The day's `cargo bench` cases.
+    pub bench_cases: Option<fn() -> Vec<BenchCase>>,
+}
+
+impl DayEntry {
+    pub fn labels(&self, part: u8) -> &'static [&'static str] {
+        match part {
+            1 => self.p1_labels,
+            _ => self.p2_labels,
+        }
+    }
+
+    pub fn tags(&self, part: u8) -> &'static [VariantTags] {
+        match part {
+            1 => self.p1_tags,
+            _ => self.p2_tags,
+        }
+    }
+
+    pub fn solutions(&self, part: u8) -> &'static [ErasedSolution] {
+        match part {
+            1 => self.p1_solutions,
+            _ => self.p2_solutions,
+        }
+    }
+
+    /// Whether any variant of the selected parts matches `variant`.
+    pub fn has_variant(&self, part: Option<u8>, variant: &str) -> bool {
+        [1, 2]
+            .into_iter()
+            .filter(|&p| part.is_none_or(|wanted| wanted == p))
+            .any(|p| self.labels(p).iter().any(|label| label_matches(label, variant)))
+    }
+}
+
+/// Collects the given day modules' `AOC_DAY` entries into a `pub static DAYS`, for the crate root,
+/// eg. `aoc_runner::day_registry!(day_01, day_02);`.
+#[macro_export]
+macro_rules! day_registry {
+    ($($day:ident),* $(,)?) => {
+        /// Every `#[aoc]` day of this crate, in order.
+        pub static DAYS: &[$crate::registry::DayEntry] = &[$($day::AOC_DAY),*];
+    };
+}
+
+/// Which parts, variants and input to run a day with.
+#[derive(Args, Debug)]
+struct Selection {
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Only run the variants with this label, or this generator or solver slug
+    #[arg(short, long)]
+    variant: Option<String>,
+
+    /// Run on this file instead of the input compiled in
+    #[arg(short, long, value_name = "FILE")]
+    input: Option<PathBuf>,
+
+    /// Also run variants tagged `slow`
+    #[arg(long)]
+    include_slow: bool,
+}
+
+/// Lists a crate's days, or runs one of them.
+#[derive(Parser, Debug)]
+struct DispatchArgs {
+    /// The day to run, or `list` to show every day and its variants. Defaults to the last day
+    day: Option<String>,
+
+    #[command(flatten)]
+    selection: Selection,
+}
+
+/// Lists or runs the days of every year crate in a workspace.
+#[derive(Parser, Debug)]
+#[command(arg_required_else_help = true)]
+struct WorkspaceArgs {
+    /// The year to run, or `list` to show every day and its variants, or `all` to run every day
+    target: String,
+
+    /// The day to run, defaulting to the year's last. After `list` or `all`, the year to limit them to
+    day: Option<String>,
+
+    #[command(flatten)]
+    selection: Selection,
+}
+
+/// Entry point for a crate's `main`, given its `DAYS`.
+pub fn main(days: &[DayEntry]) {
+    let mut command = DispatchArgs::command();
+    let args = DispatchArgs::from_arg_matches(&command.get_matches_mut()).unwrap_or_else(|e| e.exit());
+
+    let result = match (isolated_entry([days]), args.day.as_deref()) {
+        (Some(entry), _) => run(entry, &args.selection),
+        (None, Some("list")) => {
+            list(days);
+            Ok(())
+        }
+        (None, day) => find_day(days, day).and_then(|entry| run(entry, &args.selection)),
+    };
+    if let Err(message) = result {
+        command.error(ErrorKind::InvalidValue, message).exit();
+    }
+}
+
+/// Entry point for a workspace binary's `main`, given every year crate's `DAYS`.
+pub fn main_all(years: &[&[DayEntry]]) {
+    let mut command = WorkspaceArgs::command();
+    let args = WorkspaceArgs::from_arg_matches(&command.get_matches_mut()).unwrap_or_else(|e| e.exit());
+
+    let result = match (isolated_entry(years.iter().copied()), args.target.as_str()) {
+        (Some(entry), _) => run(entry, &args.selection),
+        (None, "list") => chosen_years(years, args.day.as_deref()).map(|chosen| chosen.into_iter().for_each(list)),
+        (None, "all") => chosen_years(years, args.day.as_deref()).and_then(|chosen| run_all(&chosen, &args.selection)),
+        (None, year) => find_year(years, year)
+            .and_then(|days| find_day(days, args.day.as_deref()))
+            .and_then(|entry| run(entry, &args.selection)),
+    };
+    if let Err(message) = result {
+        command.error(ErrorKind::InvalidValue, message).exit();
+    }
+}
+
+/// The day a solution process was started for, if this is one.
+fn isolated_entry<'a>(years: impl IntoIterator<Item = &'a [DayEntry]>) -> Option<&'a DayEntry> {
+    let (year, day) = isolated_day()?;
+    years
+        .into_iter()
+        .flatten()
+        .find(|entry| entry.year == year && entry.day == day)
+}
+
+fn find_year<'a>(years: &[&'a [DayEntry]], text: &str) -> Result<&'a [DayEntry], String> {
+    let number = parse_number(text, "").ok_or_else(|| format!("`{text}` isn't a year, `list` or `all`"))?;
+    let found = years
+        .iter()
+        .find(|days| days.first().is_some_and(|entry| entry.year == number));
+    found.copied().ok_or_else(|| {
+        let known: Vec<_> = years
+            .iter()
+            .filter_map(|days| Some(days.first()?.year.to_string()))
+            .collect();
+        format!("No days for {number}, expected one of: {}", known.join(", "))
+    })
+}
+
+/// Every year, or just the one given.
+fn chosen_years<'a>(years: &[&'a [DayEntry]], year: Option<&str>) -> Result<Vec<&'a [DayEntry]>, String> {
+    match year {
+        None => Ok(years.to_vec()),
+        Some(year) => Ok(vec![find_year(years, year)?]),
+    }
+}
+
+/// The day given, or the last one.
+fn find_day<'a>(days: &'a [DayEntry], day: Option<&str>) -> Result<&'a DayEntry, String> {
+    let entry = match day {
+        None => days.last(),
+        Some(day) => {
+            let number = parse_number(day, "day").ok_or_else(|| format!("`{day}` isn't a day number"))?;
+            days.iter().find(|entry| entry.day == number)
+        }
+    };
+    entry.ok_or_else(|| {
+        let numbers: Vec<_> = days.iter().map(|entry| entry.day.to_string()).collect();
+        format!("No such day, expected one of: {}", numbers.join(", "))
+    })
+}
+
+fn run(entry: &DayEntry, selection: &Selection) -> Result<(), String> {
+    let options = options(selection);
+    if let Some(variant) = &options.variant {
+        if !entry.has_variant(options.part, variant) {
+            return Err(format!("Day {} has no variant matching `{variant}`", entry.day));
+        }
+    }
+    let input = match &selection.input {
+        None => entry.input.to_string(),
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("Can't read {}: {e}", path.display()))?,
+    };
+    (entry.run)(&input, &options).unwrap_or_else(|failure| failure.exit());
+    Ok(())
+}
+
+/// Runs every day of the years given, one after another, then exits with the worst failure. Days
+/// without the variant asked for have nothing to run and are skipped, and days without an input
+/// are reported but don't fail the run, as a fresh checkout has none.
+fn run_all(years: &[&[DayEntry]], selection: &Selection) -> Result<(), String> {
+    if selection.input.is_some() {
+        return Err("`--input` is for running a single day".to_string());
+    }
+    let options = options(selection);
+    let variant = options.variant.as_deref();
+    let worst = years
+        .iter()
+        .copied()
+        .flatten()
+        .filter(|entry| variant.is_none_or(|variant| entry.has_variant(options.part, variant)))
+        .filter_map(|entry| (entry.run)(entry.input, &options).err())
+        .filter(|&failure| failure != DayFailure::NoInput)
+        .max();
+    match worst {
+        Some(failure) => failure.exit(),
+        None => Ok(()),
+    }
+}
+
+fn options(selection: &Selection) -> RunOptions {
+    let mut options = RunOptions::from_env();
+    options.part = selection.part.or(options.part);
+    options.variant = selection.variant.clone().or(options.variant);
+    options.include_slow |= selection.include_slow;
+    options
+}
+
+/// `text` as a number, allowing a prefix like `day` or `day_`.
+fn parse_number(text: &str, prefix: &str) -> Option<u32> {
+    text.trim_start_matches(prefix).trim_start_matches('_').parse().ok()
+}
+
+fn list(days: &[DayEntry]) {
+    for entry in days {
+        let missing = if entry.input.is_empty() { " (no input)" } else { "" };
+        println!("{} day {}{missing}", entry.year, entry.day);
+        for part in [1, 2] {
+            let labels: Vec<_> = entry
+                .labels(part)
+                .iter()
+                .zip(entry.tags(part))
+                .map(|(label, tags)| format!("{label}{}", tags.suffix()))
+                .collect();
+            if !labels.is_empty() {
+                println!("  Part {part}: {}", labels.join(", "));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(_: &str, _: &RunOptions) -> Result<(), DayFailure> {
+        Ok(())
+    }
+
+    fn day(year: u32, day: u32) -> DayEntry {
+        DayEntry {
+            year,
+            day,
+            input: "",
+            p1_labels: &["gen / main", "gen / fast"],
+            p1_tags: &[VariantTags { slow: false, experimental: false }; 2],
+            p1_solutions: &[],
+            p2_labels: &["brute_force"],
+            p2_tags: &[VariantTags { slow: true, experimental: false }],
+            p2_solutions: &[],
+            run,
+            bench_cases: None,
+        }
+    }
+
+    #[test]
+    fn numbers_can_have_a_prefix() {
+        assert_eq!(parse_number("7", "day"), Some(7));
+        assert_eq!(parse_number("day7", "day"), Some(7));
+        assert_eq!(parse_number("day_07", "day"), Some(7));
+        assert_eq!(parse_number("2023", ""), Some(2023));
+        assert_eq!(parse_number("d7", "day"), None);
+        assert_eq!(parse_number("list", ""), None);
+    }
+
+    #[test]
+    fn days_are_found_by_number_or_the_last() {
+        let days = [day(2023, 1), day(2023, 5), day(2023, 11)];
+        assert_eq!(find_day(&days, None).unwrap().day, 11);
+        assert_eq!(find_day(&days, Some("5")).unwrap().day, 5);
+        assert_eq!(find_day(&days, Some("day_01")).unwrap().day, 1);
+        assert_eq!(find_day(&days, Some("2")).err().unwrap(), "No such day, expected one of: 1, 5, 11");
+        assert_eq!(find_day(&days, Some("first")).err().unwrap(), "`first` isn't a day number");
+        assert_eq!(find_day(&[], None).err().unwrap(), "No such day, expected one of: ");
+    }
+
+    #[test]
+    fn variants_match_labels_or_slugs_of_the_parts_asked_for() {
+        let entry = day(2023, 1);
+        assert!(entry.has_variant(None, "gen / fast"));
+        assert!(entry.has_variant(None, "fast"));
+        assert!(entry.has_variant(None, "gen"));
+        assert!(entry.has_variant(Some(2), "brute_force"));
+        assert!(!entry.has_variant(Some(1), "brute_force"));
+        assert!(!entry.has_variant(None, "fas"));
+    }
+
+    #[test]
+    fn years_are_found_by_number() {
+        let (y2022, y2023) = ([day(2022, 1)], [day(2023, 1), day(2023, 2)]);
+        let years: [&[DayEntry]; 3] = [&y2022, &[], &y2023];
+        assert_eq!(find_year(&years, "2023").unwrap().len(), 2);
+        assert_eq!(find_year(&years, "2021").err().unwrap(), "No days for 2021, expected one of: 2022, 2023");
+        assert_eq!(find_year(&years, "every").err().unwrap(), "`every` isn't a year, `list` or `all`");
+        assert_eq!(chosen_years(&years, None).unwrap().len(), 3);
+        assert_eq!(chosen_years(&years, Some("2022")).unwrap()[0][0].year, 2022);
+        assert!(chosen_years(&years, Some("2021")).is_err());
+    }
+
+    thread_local! {
+        static RAN: std::cell::RefCell<Vec<&'static str>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// Records the day it ran for by its input, and fails like a day without one if it's empty.
+    fn record(input: &str, _: &RunOptions) -> Result<(), DayFailure> {
+        let name = ["2022-1", "2023-1", "2023-2"].into_iter().find(|&name| name == input);
+        RAN.with(|ran| ran.borrow_mut().extend(name));
+        name.map(|_| ()).ok_or(DayFailure::NoInput)
+    }
+
+    fn run_all_days(part: Option<u8>, variant: Option<&str>) -> Result<Vec<&'static str>, String> {
+        let (y2022, y2023) = (
+            [DayEntry { input: "2022-1", run: record, p2_labels: &["fast"], ..day(2022, 1) }],
+            [
+                DayEntry { input: "2023-1", run: record, ..day(2023, 1) },
+                DayEntry { input: "2023-2", run: record, p1_labels: &["slow"], ..day(2023, 2) },
+                DayEntry { input: "", run: record, ..day(2023, 3) },
+            ],
+        );
+        let selection = Selection { part, variant: variant.map(String::from), input: None, include_slow: false };
+        RAN.with(|ran| ran.borrow_mut().clear());
+        run_all(&[&y2022, &y2023], &selection)?;
+        Ok(RAN.with(|ran| ran.take()))
+    }
+
+    #[test]
+    fn run_all_runs_the_days_with_the_variant_asked_for() {
+        assert_eq!(run_all_days(None, None).unwrap(), ["2022-1", "2023-1", "2023-2"]);
+        assert_eq!(run_all_days(None, Some("fast")).unwrap(), ["2022-1", "2023-1"]);
+        assert_eq!(run_all_days(Some(2), Some("fast")).unwrap(), ["2022-1"]);
+        assert_eq!(run_all_days(Some(1), Some("slow")).unwrap(), ["2023-2"]);
+        assert_eq!(run_all_days(None, Some("none")).unwrap(), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn run_all_cant_take_an_input_file() {
+        let days = [day(2023, 1)];
+        let selection = Selection { part: None, variant: None, input: Some("in.txt".into()), include_slow: false };
+        assert_eq!(run_all(&[&days], &selection).err().unwrap(), "`--input` is for running a single day");
+    }
+}
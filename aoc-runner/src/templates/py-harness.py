@@ -0,0 +1,107 @@
+import contextlib
+import importlib
+import math
+import os
+import signal
+import sys
+import time
+import types
+
+# Day modules import `support.timing` for their own `main()`, which isn't always installed.
+try:
+    import support  # noqa: F401
+except ImportError:
+    support = types.ModuleType("support")
+    support.timing = contextlib.nullcontext
+    sys.modules["support"] = support
+
+module_name, input_path, year, day = sys.argv[1:]
+
+
+def env_limit(name):
+    value = os.environ.get(name, "none").strip()
+    return None if value in ("", "none", "0") else float(value)
+
+
+class Timeout(Exception):
+    pass
+
+
+def raise_timeout(signum, frame):
+    raise Timeout()
+
+
+wall_limit = env_limit("AOC_TIMEOUT")
+cpu_limit = env_limit("AOC_CPU_TIME")
+memory_limit = env_limit("AOC_MEMORY_LIMIT")
+
+try:
+    import resource
+
+    if cpu_limit is not None:
+        secs = max(1, math.floor(cpu_limit))
+        resource.setrlimit(resource.RLIMIT_CPU, (secs, secs + 1))
+        signal.signal(signal.SIGXCPU, raise_timeout)
+    if memory_limit is not None:
+        resource.setrlimit(resource.RLIMIT_AS, (int(memory_limit), int(memory_limit)))
+except ImportError:
+    pass
+if hasattr(signal, "SIGALRM"):
+    signal.signal(signal.SIGALRM, raise_timeout)
+
+day_module = importlib.import_module(module_name)
+with open(input_path) as f:
+    raw_input = f.read()
+
+
+def fmt_elapsed(ns):
+    for unit, scale in (("s", 1e9), ("ms", 1e6), ("µs", 1e3)):
+        if ns >= scale:
+            return f"{ns / scale:.3f}{unit}"
+    return f"{ns}ns"
+
+
+def timed(fn):
+    """Returns `(result, elapsed)`, or `(outcome, None)` when the limits stopped it."""
+    if wall_limit is not None and hasattr(signal, "setitimer"):
+        signal.setitimer(signal.ITIMER_REAL, wall_limit)
+    start = time.perf_counter_ns()
+    try:
+        result = fn(raw_input)
+        return result, time.perf_counter_ns() - start
+    except Timeout:
+        return "TIMEOUT", None
+    except MemoryError:
+        return "OOM", None
+    except Exception as e:
+        return f"CRASHED: {type(e).__name__}: {e}", None
+    finally:
+        if hasattr(signal, "setitimer"):
+            signal.setitimer(signal.ITIMER_REAL, 0)
+
+
+def report(part, label, answer, elapsed):
+    if elapsed is None:
+        print(f"Part {part}, {label} Solution: {answer}")
+    else:
+        print(f"Part {part}, {label} Solution ({fmt_elapsed(elapsed)}): {answer}")
+
+
+print(f"## AOC {year}, Day {day} ----------")
+if len(raw_input) == 0:
+    print("No input found.")
+    sys.exit(0)
+
+if hasattr(day_module, "part1") or hasattr(day_module, "part2"):
+    for part in (1, 2):
+        solver = getattr(day_module, f"part{part}", None)
+        if solver is None:
+            continue
+        answer, elapsed = timed(solver)
+        report(part, f"part{part}", answer, elapsed)
+else:
+    answers, elapsed = timed(day_module.solve)
+    if elapsed is None:
+        answers = (answers, answers)
+    for part, answer in enumerate(answers, start=1):
+        report(part, "solve", answer, elapsed)
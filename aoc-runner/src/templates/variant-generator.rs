@@ -0,0 +1,4 @@
+#[generator({{slug}})]
+pub fn input_generator_{{slug}}(_input: &str) -> {{output_type}} {
+    todo!()
+}
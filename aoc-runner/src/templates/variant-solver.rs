@@ -0,0 +1,4 @@
+#[solver({{part}}, {{slug}})]
+pub fn solve_{{part}}_{{slug}}(_input: {{input_type}}) -> {{output_type}} {
+    todo!()
+}
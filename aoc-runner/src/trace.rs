@@ -0,0 +1,183 @@
+//! Chrome trace files for `aoc run --trace`, viewable in Perfetto or `chrome://tracing`.
+//!
+//! `#[aoc]` wraps every generator, solver and solution call in a `tracing` span named after its
+//! year, day, part and label, so the trace shows where parsing and solving time goes, along with
+//! any spans the solutions open themselves:
+//!
+//! ```ignore
+//! #[solver(part1, main)]
+//! pub fn solve_part_1(data: Input) -> u32 {
+//!     let graph = tracing::info_span!("build graph").in_scope(|| Graph::new(&data));
+//!     // ..
+//! }
+//! ```
+//!
+//! Each solution process writes its own part of the trace next to the requested file, and the
+//! day process merges them into it afterwards, one trace process per variant.
+
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+/// Path to write a Chrome trace of the day's run to.
+pub const TRACE_VAR: &str = "AOC_TRACE";
+
+/// What `run_day` prints once the trace is written, before its path.
+pub const WROTE: &str = "Wrote trace to ";
+
+/// Installs a solution process's subscriber: a `RUST_LOG` filtered log to stderr when that's set,
+/// and, when `trace` is, a Chrome trace of every span into this process's part of it. Keep the
+/// guard until the solution returns, so the part is complete for `merge`.
+pub fn install(trace: Option<&Path>, request: &str) -> Option<FlushGuard> {
+    let log = env::var_os(EnvFilter::DEFAULT_ENV).map(|_| {
+        fmt::layer()
+            .with_writer(io::stderr)
+            .with_filter(EnvFilter::from_default_env())
+    });
+    // Opened here rather than by the layer, which panics if it can't.
+    let part = trace.and_then(|trace| {
+        let path = part_path(trace, request);
+        File::create(&path)
+            .map_err(|e| eprintln!("Could not write trace to {}: {e}", path.display()))
+            .ok()
+    });
+    let (chrome, guard) = part
+        .map(|part| ChromeLayerBuilder::new().writer(part).include_args(true).build())
+        .unzip();
+    let _ = tracing_subscriber::registry().with(log).with(chrome).try_init();
+
+    guard
+}
+
+/// Where the solution process for `request`, like `1:0` or `gen:2`, writes its part of `trace`.
+fn part_path(trace: &Path, request: &str) -> PathBuf {
+    let name = trace.file_name().unwrap_or_default().to_string_lossy();
+    trace.with_file_name(format!("{name}.{}.part", request.replace(':', "-")))
+}
+
+/// Gathers the parts written for each `(request, name)` into `trace`, as a process with that name,
+/// and removes them. Requests that never got to write a part are left out.
+pub fn merge(trace: &Path, requests: &[(String, String)]) -> io::Result<()> {
+    let mut events = Vec::new();
+    for (pid, (request, name)) in requests.iter().enumerate() {
+        let part = part_path(trace, request);
+        let Ok(text) = fs::read_to_string(&part) else {
+            continue;
+        };
+        let _ = fs::remove_file(&part);
+
+        let pid = pid + 1;
+        events.push(json!({ "ph": "M", "pid": pid, "name": "process_name", "args": { "name": name } }));
+        events.push(json!({ "ph": "M", "pid": pid, "name": "process_sort_index", "args": { "sort_index": pid } }));
+        for mut event in parse_events(&text) {
+            event["pid"] = pid.into();
+            events.push(event);
+        }
+    }
+
+    let mut out = BufWriter::new(File::create(trace)?);
+    serde_json::to_writer(&mut out, &events)?;
+    out.flush()
+}
+
+/// A part's events. A process that was killed, like on a timeout, leaves its array unterminated,
+/// so what it got to write is read as if it had been closed.
+fn parse_events(text: &str) -> Vec<Value> {
+    serde_json::from_str(text)
+        .or_else(|_| serde_json::from_str(&format!("{}]", text.trim_end().trim_end_matches(','))))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A trace path in a folder of its own under the system's temporary one, emptied first.
+    fn scratch_trace(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-trace-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("trace.json")
+    }
+
+    /// Writes `request`'s part as a solution process would, with one span per name.
+    fn write_part(trace: &Path, request: &str, spans: &[&str]) {
+        let part = File::create(part_path(trace, request)).unwrap();
+        let (chrome, guard) = ChromeLayerBuilder::new().writer(part).include_args(true).build();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(chrome), || {
+            for &span in spans {
+                tracing::info_span!("span", label = %span).in_scope(|| {});
+            }
+        });
+        drop(guard);
+    }
+
+    fn requests(requests: &[(&str, &str)]) -> Vec<(String, String)> {
+        requests
+            .iter()
+            .map(|&(request, name)| (request.to_string(), name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn merged_parts_are_one_chrome_trace() {
+        let trace = scratch_trace("merge");
+        write_part(&trace, "1:0", &["parse", "solve"]);
+        write_part(&trace, "2:1", &["solve"]);
+        let requests = requests(&[("1:0", "part 1 / main"), ("gen:0", "gen"), ("2:1", "part 2 / fast")]);
+        merge(&trace, &requests).unwrap();
+
+        let events: Vec<Value> = serde_json::from_str(&fs::read_to_string(&trace).unwrap()).unwrap();
+        for event in &events {
+            assert!(event["ph"].is_string(), "{event}");
+            assert!(event["pid"].is_u64(), "{event}");
+            assert!(event["name"].is_string(), "{event}");
+        }
+
+        let process_names: Vec<_> = events
+            .iter()
+            .filter(|event| event["ph"] == "M" && event["name"] == "process_name")
+            .map(|event| (event["pid"].as_u64().unwrap(), event["args"]["name"].as_str().unwrap()))
+            .collect();
+        assert_eq!(process_names, [(1, "part 1 / main"), (3, "part 2 / fast")]);
+
+        let spans = |pid: u64| {
+            events
+                .iter()
+                .filter(|event| event["pid"] == pid && event["ph"] == "B")
+                .map(|event| event["args"]["label"].as_str().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(spans(1), ["parse", "solve"]);
+        assert_eq!(spans(3), ["solve"]);
+        let begins = events.iter().filter(|event| event["ph"] == "B").count();
+        assert_eq!(events.iter().filter(|event| event["ph"] == "E").count(), begins);
+
+        assert!(!part_path(&trace, "1:0").exists());
+        assert!(!part_path(&trace, "2:1").exists());
+        fs::remove_dir_all(trace.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn parts_cut_off_mid_write_keep_their_events() {
+        let events = parse_events("[\n{\"ph\":\"B\",\"pid\":1,\"tid\":1,\"ts\":1.0,\"name\":\"solve\"},\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["name"], "solve");
+        assert!(parse_events("").is_empty());
+    }
+
+    #[test]
+    fn part_paths_sit_next_to_the_trace() {
+        assert_eq!(
+            part_path(Path::new("out/trace.json"), "gen:2"),
+            Path::new("out/trace.json.gen-2.part")
+        );
+    }
+}
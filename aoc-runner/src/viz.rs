@@ -0,0 +1,389 @@
+//! Frames recorded by solutions, drawn by `aoc viz` as an animated GIF or a sequence of SVGs.
+//!
+//! A generator, solver or solution that takes an extra `Option<Recorder>` argument is handed
+//! `Some` when its day runs under `aoc viz`, and `None` otherwise, so recording costs nothing in
+//! normal runs, tests and benchmarks:
+//!
+//! ```ignore
+//! #[solver(part2, main)]
+//! pub fn solve_part_2(data: Input, viz: Option<Recorder>) -> u32 {
+//!     // ..
+//!     if let Some(viz) = viz {
+//!         viz.text(&grid, &[('#', (90, 90, 90)), ('O', (230, 180, 40))]);
+//!     }
+//! }
+//! ```
+//!
+//! Each solution process draws its own frames when it's done, named after the day, part and
+//! variant.
+
+use std::{
+    cell::RefCell,
+    env,
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use anyhow as ah;
+use plotters::{coord::Shift, prelude::*};
+
+use crate::cli::VizFormat;
+
+/// The folder to draw recorded frames into. Recording is off unless it's set.
+pub const VIZ_VAR: &str = "AOC_VIZ";
+/// `gif` or `svg`.
+pub const VIZ_FORMAT_VAR: &str = "AOC_VIZ_FORMAT";
+/// Time between frames, in milliseconds.
+pub const VIZ_DELAY_VAR: &str = "AOC_VIZ_DELAY";
+
+/// What `run_day` prints for each animation drawn, before its path.
+pub const DREW: &str = "Drew ";
+
+/// Frames kept per solution. Past this, every other frame is dropped and only every other later
+/// one recorded, so long runs still animate from start to end.
+const MAX_FRAMES: usize = 512;
+/// Largest side of a drawn frame, in pixels, before cells are scaled down to a single pixel.
+const TARGET_SIZE: usize = 640;
+const MAX_CELL_SIZE: usize = 16;
+const BACKGROUND: Rgb = (0, 0, 0);
+
+pub type Rgb = (u8, u8, u8);
+
+/// How and where to draw frames, passed to solution processes through the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub dir: PathBuf,
+    pub format: VizFormat,
+    pub delay: Duration,
+}
+
+impl Settings {
+    pub fn from_env() -> Option<Settings> {
+        let dir = env::var_os(VIZ_VAR)?;
+        Some(Settings {
+            dir: PathBuf::from(dir),
+            format: env::var(VIZ_FORMAT_VAR)
+                .ok()
+                .and_then(|format| clap::ValueEnum::from_str(&format, true).ok())
+                .unwrap_or(VizFormat::Gif),
+            delay: env::var(VIZ_DELAY_VAR)
+                .ok()
+                .and_then(|ms| ms.parse().ok())
+                .map_or(Duration::from_millis(100), Duration::from_millis),
+        })
+    }
+
+    /// Passes these settings on to a child process, in the format `from_env` reads.
+    pub fn apply_env(&self, cmd: &mut Command) {
+        cmd.env(VIZ_VAR, &self.dir)
+            .env(VIZ_FORMAT_VAR, self.format.extension())
+            .env(VIZ_DELAY_VAR, self.delay.as_millis().to_string());
+    }
+}
+
+/// One recorded picture: a grid of coloured cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row.
+    pub cells: Vec<Rgb>,
+}
+
+struct Recording {
+    frames: Vec<Frame>,
+    /// Only every `stride`th frame pushed is kept.
+    stride: usize,
+    pushed: usize,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Adds frames to the current recording. `#[aoc]` hands one to functions taking an
+/// `Option<Recorder>` while their day is being visualized.
+#[derive(Debug, Clone, Copy)]
+pub struct Recorder(());
+
+impl Recorder {
+    /// Records a `width` × `height` grid, with cell `(x, y)` coloured `palette[cell(x, y)]`.
+    pub fn grid(&self, width: usize, height: usize, palette: &[Rgb], cell: impl Fn(usize, usize) -> usize) {
+        self.push(|| Frame {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| palette.get(cell(x, y)).copied().unwrap_or(BACKGROUND))
+                .collect(),
+        });
+    }
+
+    /// Records text like a grid printed for debugging, one row per line, with each character
+    /// coloured as `palette` says and any other left as the background.
+    pub fn text(&self, text: &str, palette: &[(char, Rgb)]) {
+        self.push(|| {
+            let width = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+            let height = text.lines().count();
+            let mut cells = vec![BACKGROUND; width * height];
+            for (y, line) in text.lines().enumerate() {
+                for (x, c) in line.chars().enumerate() {
+                    if let Some(&(_, colour)) = palette.iter().find(|(p, _)| *p == c) {
+                        cells[y * width + x] = colour;
+                    }
+                }
+            }
+            Frame { width, height, cells }
+        });
+    }
+
+    /// Only builds the frame if it's one to keep.
+    fn push(&self, frame: impl FnOnce() -> Frame) {
+        RECORDING.with(|recording| {
+            let mut recording = recording.borrow_mut();
+            let Some(recording) = recording.as_mut() else {
+                return;
+            };
+            recording.pushed += 1;
+            if (recording.pushed - 1) % recording.stride != 0 {
+                return;
+            }
+            if recording.frames.len() == MAX_FRAMES {
+                let mut index = 0;
+                recording.frames.retain(|_| {
+                    index += 1;
+                    index % 2 == 1
+                });
+                recording.stride *= 2;
+                if (recording.pushed - 1) % recording.stride != 0 {
+                    return;
+                }
+            }
+            recording.frames.push(frame());
+        });
+    }
+}
+
+/// The argument `#[aoc]` passes for an `Option<Recorder>`: `Some` while recording.
+pub fn recorder() -> Option<Recorder> {
+    RECORDING
+        .with(|recording| recording.borrow().is_some())
+        .then_some(Recorder(()))
+}
+
+/// Starts recording on this thread, dropping anything recorded before.
+pub fn start() {
+    RECORDING.with(|recording| {
+        *recording.borrow_mut() = Some(Recording {
+            frames: Vec::new(),
+            stride: 1,
+            pushed: 0,
+        })
+    });
+}
+
+/// Stops recording, returning the frames kept.
+pub fn finish() -> Vec<Frame> {
+    RECORDING
+        .with(|recording| recording.borrow_mut().take())
+        .map_or_else(Vec::new, |recording| recording.frames)
+}
+
+/// A file name for a variant's animation, eg. `2023-day14-part2-gen-main`.
+pub fn file_stem(year: u32, day: u32, part: u8, label: &str) -> String {
+    let slug: String = label
+        .split(" / ")
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("{year}-day{day:02}-part{part}-{slug}")
+}
+
+/// Draws `frames` into the settings' folder, returning the GIF, or the folder of SVGs, written.
+pub fn render(frames: &[Frame], settings: &Settings, stem: &str) -> ah::Result<PathBuf> {
+    let width = frames.iter().map(|frame| frame.width).max().unwrap_or(0).max(1);
+    let height = frames.iter().map(|frame| frame.height).max().unwrap_or(0).max(1);
+    let cell = (TARGET_SIZE / width.max(height)).clamp(1, MAX_CELL_SIZE);
+    let size = ((width * cell) as u32, (height * cell) as u32);
+    create_dir_all(&settings.dir)?;
+
+    match settings.format {
+        VizFormat::Gif => {
+            let path = settings.dir.join(format!("{stem}.gif"));
+            let delay = settings.delay.as_millis() as u32;
+            let root = BitMapBackend::gif(&path, size, delay)?.into_drawing_area();
+            for frame in frames {
+                draw(&root, frame, cell as i32)?;
+                root.present()?;
+            }
+            Ok(path)
+        }
+        VizFormat::Svg => {
+            let dir = settings.dir.join(stem);
+            create_dir_all(&dir)?;
+            for (index, frame) in frames.iter().enumerate() {
+                let path = dir.join(format!("frame-{index:04}.svg"));
+                let root = SVGBackend::new(&path, size).into_drawing_area();
+                draw(&root, frame, cell as i32)?;
+                root.present()?;
+            }
+            Ok(dir)
+        }
+    }
+}
+
+/// The animations `run_day` reported drawing in its output.
+pub fn drawn(stdout: &str) -> Vec<&Path> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix(DREW)?
+                .split_once(" to ")
+                .map(|(_, path)| Path::new(path))
+        })
+        .collect()
+}
+
+fn draw<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    frame: &Frame,
+    cell: i32,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let (r, g, b) = BACKGROUND;
+    root.fill(&RGBColor(r, g, b))?;
+    for (index, &colour) in frame.cells.iter().enumerate() {
+        if colour == BACKGROUND {
+            continue;
+        }
+        let (x, y) = ((index % frame.width) as i32 * cell, (index / frame.width) as i32 * cell);
+        let (r, g, b) = colour;
+        root.draw(&Rectangle::new(
+            [(x, y), (x + cell - 1, y + cell - 1)],
+            RGBColor(r, g, b).filled(),
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const RED: Rgb = (255, 0, 0);
+
+    /// A folder of its own under the system's temporary one, emptied first.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-viz-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn svg_size(svg: &str) -> (u32, u32) {
+        let attribute = |name: &str| {
+            let start = svg.find(&format!(" {name}=\"")).expect("the svg has a size") + name.len() + 3;
+            let end = start + svg[start..].find('"').unwrap();
+            svg[start..end].parse().unwrap()
+        };
+        (attribute("width"), attribute("height"))
+    }
+
+    #[test]
+    fn records_only_while_started() {
+        assert!(recorder().is_none());
+        start();
+        let viz = recorder().expect("recording");
+        viz.text("#.\n.#\n#.\n", &[('#', RED)]);
+        viz.grid(3, 1, &[BACKGROUND, RED], |x, _| x % 2);
+        let frames = finish();
+        assert!(recorder().is_none());
+
+        assert_eq!(
+            frames,
+            [
+                Frame {
+                    width: 2,
+                    height: 3,
+                    cells: vec![RED, BACKGROUND, BACKGROUND, RED, RED, BACKGROUND],
+                },
+                Frame {
+                    width: 3,
+                    height: 1,
+                    cells: vec![BACKGROUND, RED, BACKGROUND],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn long_recordings_keep_every_other_frame() {
+        start();
+        let viz = recorder().unwrap();
+        for index in 0..MAX_FRAMES * 2 {
+            viz.grid(1, 1, &[BACKGROUND, RED], |_, _| index % 2);
+        }
+        let frames = finish();
+        assert_eq!(frames.len(), MAX_FRAMES);
+        assert!(frames.iter().all(|frame| frame.cells == [BACKGROUND]));
+    }
+
+    #[test]
+    fn svgs_are_one_per_frame_at_the_largest_frame_size() {
+        start();
+        let viz = recorder().unwrap();
+        viz.text("#..\n.#.\n", &[('#', RED)]);
+        viz.text("#\n#\n#\n#\n", &[('#', RED)]);
+        viz.text("##\n", &[('#', RED)]);
+        let frames = finish();
+
+        let settings = Settings {
+            dir: scratch_dir("svg"),
+            format: VizFormat::Svg,
+            delay: Duration::from_millis(100),
+        };
+        let dir = render(&frames, &settings, "2099-day01-part1-main").unwrap();
+        assert_eq!(dir, settings.dir.join("2099-day01-part1-main"));
+
+        let mut files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["frame-0000.svg", "frame-0001.svg", "frame-0002.svg"]);
+
+        // 3 × 4 cells at most, each scaled up to the largest cell size.
+        let cell = MAX_CELL_SIZE as u32;
+        for file in &files {
+            let svg = fs::read_to_string(dir.join(file)).unwrap();
+            assert_eq!(svg_size(&svg), (3 * cell, 4 * cell), "{file}");
+        }
+        let first = fs::read_to_string(dir.join(&files[0])).unwrap();
+        assert_eq!(first.matches("<rect").count(), 1 + 2, "the background and two cells");
+
+        fs::remove_dir_all(&settings.dir).unwrap();
+    }
+
+    #[test]
+    fn large_frames_scale_cells_down() {
+        let frames = [Frame {
+            width: 1000,
+            height: 10,
+            cells: vec![BACKGROUND; 1000 * 10],
+        }];
+        let settings = Settings {
+            dir: scratch_dir("large"),
+            format: VizFormat::Svg,
+            delay: Duration::from_millis(100),
+        };
+        let dir = render(&frames, &settings, "large").unwrap();
+        let svg = fs::read_to_string(dir.join("frame-0000.svg")).unwrap();
+        assert_eq!(svg_size(&svg), (1000, 10));
+
+        fs::remove_dir_all(&settings.dir).unwrap();
+    }
+}
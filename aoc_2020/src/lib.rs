@@ -1,8 +1,6 @@
-use common::register_year;
+// Import all day modules
+pub mod day01;
 
-// Import all day modules to trigger solution registration
-mod day01;
-
-// Register this year with the dynamic registry
-// Solutions are automatically discovered from day modules
-register_year!(2020);
\ No newline at end of file
+aoc_runner::day_registry!(
+    day01,
+);
@@ -23,4 +23,25 @@ pub mod day20;
 // pub mod day22; // Not yet converted to aoc-runner
 // pub mod day23; // Not yet converted to aoc-runner
 // pub mod day24; // Not yet converted to aoc-runner
-// pub mod day25; // Not yet converted to aoc-runner
\ No newline at end of file
+// pub mod day25; // Not yet converted to aoc-runner
+
+aoc_runner::day_registry!(
+    day01,
+    day02,
+    day03,
+    day04,
+    day05,
+    day06,
+    day07,
+    day08,
+    day09,
+    day10,
+    day11,
+    day12,
+    day13,
+    day14,
+    day15,
+    day16,
+    day17,
+    day20,
+);
@@ -178,6 +178,17 @@ pub mod solutions {
             .unwrap()
     }
 
+    #[solver(both, once)]
+    pub fn solve_both(input: &Input) -> (u64, u64) {
+        let size_map = calculate_dir_sizes(input);
+
+        let small = size_map.values().filter(|v| **v <= 100_000).sum::<u64>();
+        let space_needed = 30_000_000 - (70_000_000 - size_map["/"]);
+        let freed = *size_map.values().filter(|v| **v > space_needed).min().unwrap();
+
+        (small, freed)
+    }
+
     #[solution(part1, gen)]
     pub fn part_1(input: &str) -> u64 {
         let data = input_generator(input);
@@ -193,8 +204,10 @@ pub mod solutions {
 
 #[cfg(test)]
 mod test {
+    use aoc_runner_macros::aoc_case;
     use indoc::indoc;
 
+    #[aoc_case(95437, 24933642)]
     const EXAMPLE: &str = indoc! {"
         $ cd /
         $ ls
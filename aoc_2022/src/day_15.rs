@@ -108,30 +108,30 @@ pub mod solutions {
         data
     }
 
-    #[solver(part1, gen)]
-    pub fn solve_part1(input: &Input) -> usize {
-        solve_part1_helper::<2_000_000>(input)
+    #[solver(part1, gen, params(row = 2_000_000))]
+    pub fn solve_part1(input: &Input, row: i32) -> usize {
+        solve_part1_helper(input, row)
     }
 
-    #[solver(part2, gen)]
-    pub fn solve_part2(input: &Input) -> usize {
-        solve_part2_helper::<4_000_000>(input)
+    #[solver(part2, gen, params(max_coord = 4_000_000))]
+    pub fn solve_part2(input: &Input, max_coord: i32) -> usize {
+        solve_part2_helper(input, max_coord)
     }
 
-    #[solution(part1, gen)]
-    pub fn part_1(input: &str) -> usize {
+    #[solution(part1, gen, params(row = 2_000_000))]
+    pub fn part_1(input: &str, row: i32) -> usize {
         let data = input_generator(input);
-        solve_part1(&data)
+        solve_part1(&data, row)
     }
 
-    #[solution(part2, gen)]
-    pub fn part_2(input: &str) -> usize {
+    #[solution(part2, gen, params(max_coord = 4_000_000))]
+    pub fn part_2(input: &str, max_coord: i32) -> usize {
         let data = input_generator(input);
-        solve_part2(&data)
+        solve_part2(&data, max_coord)
     }
 }
 
-fn solve_part1_helper<const Y: isize>(input: &[Sensor]) -> usize {
+fn solve_part1_helper(input: &[Sensor], row: i32) -> usize {
     let fsensor = &input[0];
     let init_range = fsensor.loc.x - fsensor.distance..fsensor.loc.x + fsensor.distance;
     let x_bounds = input.iter().fold(init_range, |mut range, sensor| {
@@ -142,7 +142,7 @@ fn solve_part1_helper<const Y: isize>(input: &[Sensor]) -> usize {
     println!("x to try: {} .. {}", x_bounds.start, x_bounds.end);
 
     (x_bounds.start..=x_bounds.end)
-        .map(|x| IVec2::new(x, Y as i32))
+        .map(|x| IVec2::new(x, row))
         .map(|pos| {
             input
                 .iter()
@@ -154,7 +154,7 @@ fn solve_part1_helper<const Y: isize>(input: &[Sensor]) -> usize {
         .sum()
 }
 
-fn solve_part2_helper<const N: i32>(input: &[Sensor]) -> usize {
+fn solve_part2_helper(input: &[Sensor], max_coord: i32) -> usize {
     /*
     As there is only one missing value, it's going to be just outside the
     boundaries of at least two scanners (unless we're incredibly unlucky and
@@ -193,7 +193,7 @@ fn solve_part2_helper<const N: i32>(input: &[Sensor]) -> usize {
 
     let possible_pts = iproduct!(a_vec.iter(), b_vec.iter())
         .map(|(a, b)| IVec2::new((b - a) / 2, (a + b) / 2))
-        .filter(|p| 0 <= p.x && p.x <= N && 0 <= p.y && p.y <= N)
+        .filter(|p| 0 <= p.x && p.x <= max_coord && 0 <= p.y && p.y <= max_coord)
         .filter(|p| input.iter().all(|sen| !sen.contains(p)))
         .collect_vec();
 
@@ -203,22 +203,24 @@ fn solve_part2_helper<const N: i32>(input: &[Sensor]) -> usize {
 
 #[cfg(test)]
 mod test {
-
-
-
-
-    // Tests commented out because they call private functions
-    // #[test]
-    // fn part_1_example() {
-    //     let (_, data) = super::parse(EXAMPLE).map_err(|e| miette::miette!("Parse error: {}", e))?;
-    //     let result = super::solve_part1_helper::<10>(&data);
-    //     assert_eq!(result, 26);
-    // }
-
-    // #[test]
-    // fn part_2_example() {
-    //     let (_, data) = super::parse(EXAMPLE).map_err(|e| miette::miette!("Parse error: {}", e))?;
-    //     let result = super::solve_part2_helper::<20>(&data);
-    //     assert_eq!(result, 56000011);
-    // }
+    use aoc_runner_macros::aoc_case;
+    use indoc::indoc;
+
+    #[aoc_case(26, 56000011, params(row = 10, max_coord = 20))]
+    const EXAMPLE: &str = indoc! {"
+        Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+        Sensor at x=9, y=16: closest beacon is at x=10, y=16
+        Sensor at x=13, y=2: closest beacon is at x=15, y=3
+        Sensor at x=12, y=14: closest beacon is at x=10, y=16
+        Sensor at x=10, y=20: closest beacon is at x=10, y=16
+        Sensor at x=14, y=17: closest beacon is at x=10, y=16
+        Sensor at x=8, y=7: closest beacon is at x=2, y=10
+        Sensor at x=2, y=0: closest beacon is at x=2, y=10
+        Sensor at x=0, y=11: closest beacon is at x=2, y=10
+        Sensor at x=20, y=14: closest beacon is at x=25, y=17
+        Sensor at x=17, y=20: closest beacon is at x=21, y=22
+        Sensor at x=16, y=7: closest beacon is at x=15, y=3
+        Sensor at x=14, y=3: closest beacon is at x=15, y=3
+        Sensor at x=20, y=1: closest beacon is at x=15, y=3
+    "};
 }
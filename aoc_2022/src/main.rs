@@ -0,0 +1,3 @@
+fn main() {
+    aoc_runner::registry::main(aoc_2022::DAYS)
+}
@@ -1,7 +0,0 @@
-pub use crate::YEAR;
-pub use anyhow::anyhow;
-pub use framework::day;
-pub use framework::prelude::*;
-pub use framework::tests;
-pub use itertools::Itertools;
-pub use std::str::FromStr;
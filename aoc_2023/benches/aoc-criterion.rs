@@ -0,0 +1 @@
+aoc_runner::criterion_benches!(aoc_2023::DAYS);
@@ -0,0 +1 @@
+aoc_runner::divan_benches!(aoc_2023::DAYS);
@@ -153,12 +153,56 @@ pub mod solutions {
         let data = input_generator(input);
         solve_part_2(data)
     }
+
+    /// Maps every seed on its own, to check the range splitting of `main` against.
+    #[solution(part2, brute_force, slow)]
+    pub fn part_2_brute_force(input: &str) -> u64 {
+        let data = input_generator(input);
+        data.seeds
+            .iter()
+            .tuples()
+            .flat_map(|(&start, &len)| start..start + len)
+            .map(|seed| data.maps.iter().fold(seed, |seed, map| map.convert(seed)))
+            .min()
+            .expect("to have min")
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use aoc_runner_macros::aoc_case;
-    
+    use aoc_runner::property::Rng;
+    use aoc_runner_macros::{aoc_case, aoc_property};
+
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    #[aoc_property(cases = 200, max_size = 10, part = 2)]
+    fn random_almanac(rng: &mut Rng, size: usize) -> String {
+        let top = 10 * size as u64;
+        let seeds: Vec<String> = (0..rng.between(1..=size.div_ceil(2) as u64))
+            .map(|_| format!("{} {}", rng.between(0..=top), rng.between(1..=size as u64)))
+            .collect();
+        let mut input = format!("seeds: {}\n", seeds.join(" "));
+        let maps = rng.between(1..=CATEGORIES.len() as u64 - 1) as usize;
+        for pair in CATEGORIES[..=maps].windows(2) {
+            input += &format!("\n{}-to-{} map:\n", pair[0], pair[1]);
+            let mut source = rng.between(0..=size as u64);
+            for _ in 0..rng.between(1..=size as u64) {
+                let len = rng.between(1..=size as u64);
+                input += &format!("{} {source} {len}\n", rng.between(0..=top));
+                source += len + rng.between(0..=size as u64);
+            }
+        }
+        input
+    }
 
     #[aoc_case(35, 46)]
     const EXAMPLE: &str = "seeds: 79 14 55 13
@@ -194,4 +238,8 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
+
+    /// Picking the slow variant by name runs it without `AOC_INCLUDE_SLOW`.
+    #[aoc_case(part2 = 46, variant = "brute_force")]
+    const EXAMPLE_BRUTE_FORCE: &str = EXAMPLE;
 }
@@ -160,44 +160,7 @@ pub mod solutions {
 
 #[cfg(test)]
 mod test {
-    use super::solutions::*;
-    use indoc::indoc;
-
-    const EXAMPLE: &str = indoc! {"
-        RL
-        
-        AAA = (BBB, CCC)
-        BBB = (DDD, EEE)
-        CCC = (ZZZ, GGG)
-        DDD = (DDD, DDD)
-        EEE = (EEE, EEE)
-        GGG = (GGG, GGG)
-        ZZZ = (ZZZ, ZZZ)
-    "};
-
-    const EXAMPLE2: &str = indoc! {"
-        LLR
-        
-        AAA = (BBB, BBB)
-        BBB = (AAA, ZZZ)
-        ZZZ = (ZZZ, ZZZ)
-    "};
-
-
-
-    #[test]
-    fn test_example1_part1() {
-        let data = input_generator(EXAMPLE);
-        assert_eq!(2, solve_part_1(data));
-    }
-
-    #[test]
-    fn test_example2_part1() {
-        let data = input_generator(EXAMPLE2);
-        assert_eq!(6, solve_part_1(data));
-    }
+    use aoc_runner_macros::aoc_examples;
 
-    // Note: EXAMPLE3 part2 test would be:
-    // let data = input_generator(EXAMPLE3);
-    // assert_eq!(6, solve_part_2(data));
-}
\ No newline at end of file
+    aoc_examples!(2023, day8);
+}
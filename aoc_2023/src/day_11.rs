@@ -71,7 +71,7 @@ fn parse_grid(input: Span) -> nom::IResult<Span, HashMap<IVec2, SpaceType>> {
     res.map(|(input, _)| (input, parsed))
 }
 
-fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
+fn solve(data: HashMap<IVec2, SpaceType>, expansion: usize) -> u64 {
     let width = data
         .iter()
         .fold(0, |res, v| if v.0.x > res { v.0.x } else { res });
@@ -91,7 +91,6 @@ fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
         .copied()
         .sorted()
         .collect_vec();
-    dbg!(non_empty_rows);
     let non_empty_cols: &Vec<i32> = &data
         .iter()
         .fold(cols, |mut acc, (pos, _)| {
@@ -102,7 +101,6 @@ fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
         .copied()
         .sorted()
         .collect_vec();
-    dbg!(non_empty_cols);
 
     let galaxies = data
         .iter()
@@ -113,16 +111,13 @@ fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
                 .copied()
                 .filter(|p| p < &v.0.x)
                 .count()
-                * (EXPANSION - 1)) as i32;
+                * (expansion - 1)) as i32;
             let row_exp = (non_empty_rows
                 .iter()
                 .copied()
                 .filter(|p| p < &v.0.y)
                 .count()
-                * (EXPANSION - 1)) as i32;
-            dbg!(&v.0);
-            dbg!(col_exp);
-            dbg!(row_exp);
+                * (expansion - 1)) as i32;
             (i + 1, *v.0 + IVec2::new(col_exp, row_exp))
         })
         .collect_vec();
@@ -131,7 +126,6 @@ fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
         .iter()
         .combinations(2)
         .map(|pair| {
-            // dbg!(&pair);
             let diff = (pair[0].1 - pair[1].1).abs();
             (diff.x + diff.y).unsigned_abs() as u64
         })
@@ -151,12 +145,12 @@ fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
 
     #[solver(part1, main)]
     pub fn solve_part_1(data: Input) -> u64 {
-        solve::<2>(data)
+        solve(data, 2)
     }
 
-    #[solver(part2, main)]
-    pub fn solve_part_2(data: Input) -> u64 {
-        solve::<1_000_000>(data)
+    #[solver(part2, main, params(expansion = 1_000_000))]
+    pub fn solve_part_2(data: Input, expansion: usize) -> u64 {
+        solve(data, expansion)
     }
 
     #[solution(part1, main)]
@@ -165,10 +159,10 @@ fn solve<const EXPANSION: usize>(data: HashMap<IVec2, SpaceType>) -> u64 {
         solve_part_1(data)
     }
 
-    #[solution(part2, main)]
-    pub fn part_2(input: &str) -> u64 {
+    #[solution(part2, main, params(expansion = 1_000_000))]
+    pub fn part_2(input: &str, expansion: usize) -> u64 {
         let data = input_generator(input);
-        solve_part_2(data)
+        solve_part_2(data, expansion)
     }
 }
 
@@ -188,4 +182,10 @@ mod tests {
 ..........
 .......#..
 #...#.....";
+
+    #[aoc_case(part2 = 1030, params(expansion = 10))]
+    const EXAMPLE_TIMES_10: &str = EXAMPLE;
+
+    #[aoc_case(part2 = 8410, params(expansion = 100))]
+    const EXAMPLE_TIMES_100: &str = EXAMPLE;
 }
@@ -1,3 +1,4 @@
+use aoc_runner::viz::{Recorder, Rgb};
 use aoc_runner_macros::{aoc, generator, solver, solution};
 use glam::IVec2;
 use nom::branch::alt;
@@ -8,6 +9,7 @@ use nom::Parser;
 use nom_locate::LocatedSpan;
 use std::collections::HashMap;
 use std::fmt::Display;
+use tracing::instrument;
 
 type Input = RockMap;
 
@@ -136,15 +138,15 @@ fn parse_grid(input: Span) -> IBaseResult<Span, RockState> {
         final_map
             .iter()
             .filter_map(|(p, t)| match t {
-                RockType::Rounded => Some((data.size.x - p.y) as u32),
+                RockType::Rounded => Some((data.size.y - p.y) as u32),
                 _ => None,
             })
             .sum::<u32>()
     }
 
     #[solver(part2, main)]
-    pub fn solve_part_2(data: Input) -> u32 {
-        let (spins, cycle_len, mut state) = find_cycle(&data.grid, &data.size);
+    pub fn solve_part_2(data: Input, viz: Option<Recorder>) -> u32 {
+        let (spins, cycle_len, mut state) = find_cycle(&data.grid, &data.size, viz);
         let spins_left = (1_000_000_000 - spins) % cycle_len;
         for _ in 0..spins_left {
             state = spin(&state, &data.size);
@@ -152,7 +154,7 @@ fn parse_grid(input: Span) -> IBaseResult<Span, RockState> {
         state
             .iter()
             .filter_map(|(p, t)| match t {
-                RockType::Rounded => Some((data.size.x - p.y) as u32),
+                RockType::Rounded => Some((data.size.y - p.y) as u32),
                 _ => None,
             })
             .sum::<u32>()
@@ -164,20 +166,50 @@ fn parse_grid(input: Span) -> IBaseResult<Span, RockState> {
         solve_part_1(data)
     }
 
+    /// Tilts each column of the text in place, without building the rock map. The fastest, so the
+    /// one the others are checked against.
+    #[solution(part1, columns, primary)]
+    pub fn part_1_columns(input: &str) -> u32 {
+        let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let height = rows.len() as u32;
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        (0..width)
+            .map(|x| {
+                let mut free = 0;
+                let mut load = 0;
+                for (y, row) in rows.iter().enumerate() {
+                    match row.get(x) {
+                        Some(b'#') => free = y as u32 + 1,
+                        Some(b'O') => {
+                            load += height - free;
+                            free += 1;
+                        }
+                        _ => {}
+                    }
+                }
+                load
+            })
+            .sum()
+    }
+
     #[solution(part2, main)]
     pub fn part_2(input: &str) -> u32 {
         let data = input_generator(input);
-        solve_part_2(data)
+        solve_part_2(data, None)
     }
 }
 
-fn find_cycle(d: &RockState, s: &IVec2) -> (usize, usize, RockState) {
+#[instrument(skip_all)]
+fn find_cycle(d: &RockState, s: &IVec2, viz: Option<Recorder>) -> (usize, usize, RockState) {
     let mut cache = HashMap::new();
     let mut i = 0;
     let mut state = d.clone();
     loop {
         i += 1;
         state = spin(&state, s);
+        if let Some(viz) = viz {
+            record(viz, &state, s);
+        }
         if let Some(start_cycle) = cache.insert(grid_as_str(&state, s), i) {
             return (i, i - start_cycle, state);
         };
@@ -304,6 +336,19 @@ fn print(d: &RockState, size: &IVec2) {
     }
 }
 
+/// Empty, rounded and cube cells.
+const PALETTE: [Rgb; 3] = [(0, 0, 0), (230, 180, 40), (90, 90, 110)];
+
+fn record(viz: Recorder, d: &RockState, size: &IVec2) {
+    viz.grid(size.x as usize, size.y as usize, &PALETTE, |x, y| {
+        match d.get(&IVec2::new(x as i32, y as i32)) {
+            Some(RockType::Rounded) => 1,
+            Some(RockType::Cube) => 2,
+            _ => 0,
+        }
+    });
+}
+
 fn grid_as_str(d: &RockState, size: &IVec2) -> String {
     (0..size.y)
         .flat_map(|y| {
@@ -319,8 +364,17 @@ fn grid_as_str(d: &RockState, size: &IVec2) -> String {
 
 #[cfg(test)]
 mod tests {
-    use aoc_runner_macros::aoc_case;
-    
+    use aoc_runner::property::Rng;
+    use aoc_runner_macros::{aoc_case, aoc_property};
+
+    #[aoc_property(cases = 200, max_size = 12)]
+    fn random_grid(rng: &mut Rng, size: usize) -> String {
+        let width = rng.between(1..=size as u64);
+        let height = rng.between(1..=size as u64);
+        (0..height)
+            .map(|_| (0..width).map(|_| *rng.pick(&['.', '.', 'O', '#'])).collect::<String>() + "\n")
+            .collect()
+    }
 
     #[aoc_case(136, 64)]
     const EXAMPLE: &str = "O....#....
@@ -333,4 +387,10 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#....";
+
+    /// Taller than it is wide, so the load has to be measured from the grid's height.
+    #[aoc_case(part1 = 6)]
+    const TALL: &str = "O.
+.O
+#.";
 }
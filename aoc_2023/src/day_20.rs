@@ -342,18 +342,7 @@ fn gcd_of_two_numbers(a: u64, b: u64) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    
-    use super::solutions::*;
+    use aoc_runner_macros::aoc_examples;
 
-    #[test]
-    fn part_1_example_1() {
-        let input = "broadcaster -> a, b, c\n%a -> b\n%b -> c\n%c -> inv\n&inv -> a";
-        assert_eq!(part_1(input), 32000000);
-    }
-
-    #[test]
-    fn part_1_example_2() {
-        let input = "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output";
-        assert_eq!(part_1(input), 11687500);
-    }
+    aoc_examples!(2023, day20);
 }
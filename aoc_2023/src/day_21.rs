@@ -103,9 +103,9 @@ fn num_steps(start: &IVec2, set: &HashSet<IVec2>, step_count: usize) -> u64 {
         parse_input(input)
     }
 
-    #[solver(part1, main)]
-    pub fn solve_part_1((start, set): Input) -> u64 {
-        num_steps(&start, &set, 64)
+    #[solver(part1, main, params(steps = 64))]
+    pub fn solve_part_1((start, set): Input, steps: usize) -> u64 {
+        num_steps(&start, &set, steps)
     }
 
     #[solver(part2, main)]
@@ -121,10 +121,10 @@ fn num_steps(start: &IVec2, set: &HashSet<IVec2>, step_count: usize) -> u64 {
         quad(n, x, y, z)
     }
 
-    #[solution(part1, main)]
-    pub fn part_1(input: &str) -> u64 {
+    #[solution(part1, main, params(steps = 64))]
+    pub fn part_1(input: &str, steps: usize) -> u64 {
         let data = input_generator(input);
-        solve_part_1(data)
+        solve_part_1(data, steps)
     }
 
     #[solution(part2, main)]
@@ -159,8 +159,18 @@ fn print(d: &HashSet<IVec2>, size: &IVec2) {
 
 #[cfg(test)]
 mod tests {
-    
-    
-
-    // No example test case available for this problem
+    use aoc_runner_macros::aoc_case;
+
+    #[aoc_case(part1 = 16, params(steps = 6))]
+    const EXAMPLE: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
 }
@@ -1,6 +1,8 @@
 use aoc_runner_macros::{aoc, generator, solver, solution};
 use glam::I64Vec3;
 use itertools::Itertools;
+use std::ops::RangeInclusive;
+
 use nom::{
     bytes::complete::tag,
     character::complete::{self, line_ending, space1},
@@ -66,14 +68,19 @@ fn parse_coords(input: &str) -> nom::IResult<&str, I64Vec3> {
     }
 
     #[generator(gen)]
-    pub fn input_generator(input: &str) -> Input {
-        let (_, data) = parse(input).unwrap();
-        data
+    pub fn input_generator(input: &str) -> Result<Input, String> {
+        match parse(input.trim_end()) {
+            Ok(("", data)) => Ok(data),
+            Ok((rest, _)) => {
+                let line = rest.trim_start().lines().next().unwrap_or_default();
+                Err(format!("Expected a hailstone at `{line}`"))
+            }
+            Err(e) => Err(e.to_string()),
+        }
     }
 
-    #[solver(part1, main)]
-    pub fn solve_part_1(data: Input) -> u64 {
-        let range = 200000000000000.0..=400000000000000.0;
+    #[solver(part1, main, params(range = 200000000000000.0..=400000000000000.0))]
+    pub fn solve_part_1(data: Input, range: RangeInclusive<f64>) -> u64 {
         data
             .iter()
             .tuple_combinations()
@@ -99,23 +106,31 @@ fn parse_coords(input: &str) -> nom::IResult<&str, I64Vec3> {
         0
     }
 
-    #[solution(part1, main)]
-    pub fn part_1(input: &str) -> u64 {
-        let data = input_generator(input);
-        solve_part_1(data)
+    #[solution(part1, main, params(range = 200000000000000.0..=400000000000000.0))]
+    pub fn part_1(input: &str, range: RangeInclusive<f64>) -> Result<u64, String> {
+        let data = input_generator(input)?;
+        Ok(solve_part_1(data, range))
     }
 
     #[solution(part2, main)]
-    pub fn part_2(input: &str) -> u64 {
-        let data = input_generator(input);
-        solve_part_2(data)
+    pub fn part_2(input: &str) -> Result<u64, String> {
+        let data = input_generator(input)?;
+        Ok(solve_part_2(data))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    
-    
-
-    // No simple test case available for this problem
+    use aoc_runner_macros::aoc_case;
+
+    #[aoc_case(part1 = 2, params(range = 7.0..=27.0))]
+    const EXAMPLE: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[aoc_case(part1 = Err("Expected a hailstone at `12, 31 @ -1, -2, -1`"), part2 = Err(_))]
+    const MISSING_COORD: &str = "19, 13, 30 @ -2,  1, -2
+12, 31 @ -1, -2, -1";
 }
@@ -0,0 +1 @@
+aoc_runner::benches!(aoc_2024::DAYS);
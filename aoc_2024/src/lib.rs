@@ -4,3 +4,10 @@ pub mod day_02;
 pub mod day_03;
 pub mod day_04;
 // [import_marker]
+
+aoc_runner::day_registry!(
+    day_01,
+    day_02,
+    day_03,
+    day_04,
+);
@@ -0,0 +1,9 @@
+fn main() {
+    aoc_runner::registry::main_all(&[
+        aoc_2020::DAYS,
+        aoc_2021::DAYS,
+        aoc_2022::DAYS,
+        aoc_2023::DAYS,
+        aoc_2024::DAYS,
+    ])
+}